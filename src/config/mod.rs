@@ -1,10 +1,16 @@
 //! Configuration file support for pytest-language-server.
 //!
-//! Reads settings from `[tool.pytest-language-server]` section in `pyproject.toml`.
+//! Reads settings from `[tool.pytest-language-server]` section in `pyproject.toml`,
+//! plus pytest's own collection options (see [`PytestIniOptions`]).
 
+mod pytest_ini;
+
+pub use pytest_ini::PytestIniOptions;
+
+use crate::fixtures::venv::VenvSettings;
 use glob::Pattern;
 use serde::Deserialize;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tracing::{debug, warn};
 
 /// Configuration for pytest-language-server.
@@ -17,28 +23,85 @@ pub struct Config {
     pub disabled_diagnostics: Vec<String>,
 
     /// Additional directories to scan for fixtures (beyond conftest.py hierarchy).
-    #[allow(dead_code)] // Planned feature
+    /// Their fixtures are visible to every test, like plugin fixtures.
     pub fixture_paths: Vec<String>,
 
-    /// Third-party plugins to skip when scanning virtual environment.
-    #[allow(dead_code)] // Used in tests, venv scanning integration planned
+    /// Third-party plugins to skip when scanning the virtual environment, by
+    /// pytest11 entry point name or distribution name.
     pub skip_plugins: Vec<String>,
+
+    /// pytest's collection settings (`python_files`, `python_functions`,
+    /// `python_classes`, `testpaths`) from pytest.ini, pyproject.toml, tox.ini or setup.cfg.
+    pub pytest: PytestIniOptions,
+
+    /// Whether the language server persists its fixture index between sessions
+    /// (off by default, since it writes into the workspace).
+    pub index_cache: bool,
+
+    /// Directory for the persistent fixture index (relative paths are resolved
+    /// against the workspace root). Defaults to `.pytest_cache/pytest-language-server`.
+    pub cache_dir: Option<PathBuf>,
+
+    /// Python interpreter whose environment provides third-party fixtures
+    /// (relative paths are resolved against the workspace root).
+    pub python: Option<PathBuf>,
+
+    /// Virtual environment providing third-party fixtures (relative paths are
+    /// resolved against the workspace root). Takes precedence over `python`.
+    pub venv: Option<PathBuf>,
 }
 
-/// Raw configuration as parsed from TOML (before validation).
+/// Raw configuration as parsed from TOML or client settings (before validation).
+/// Unset keys are `None` so client settings only override what they contain.
 #[derive(Debug, Deserialize, Default)]
 struct RawConfig {
-    #[serde(default)]
-    exclude: Vec<String>,
+    exclude: Option<Vec<String>>,
+
+    disabled_diagnostics: Option<Vec<String>>,
 
-    #[serde(default)]
-    disabled_diagnostics: Vec<String>,
+    fixture_paths: Option<Vec<String>>,
 
-    #[serde(default)]
-    fixture_paths: Vec<String>,
+    skip_plugins: Option<Vec<String>>,
+
+    index_cache: Option<bool>,
+
+    cache_dir: Option<String>,
+
+    python: Option<String>,
+
+    venv: Option<String>,
+}
+
+impl RawConfig {
+    /// Settings sent by the client, either as the `[tool.pytest-language-server]`
+    /// keys directly or nested under a `pytest-language-server` key.
+    fn from_settings(settings: &serde_json::Value) -> Self {
+        if settings.is_null() {
+            return Self::default();
+        }
+        let settings = settings.get("pytest-language-server").unwrap_or(settings);
+        match serde_json::from_value(settings.clone()) {
+            Ok(raw) => raw,
+            Err(e) => {
+                warn!("Ignoring invalid client settings: {}", e);
+                Self::default()
+            }
+        }
+    }
 
-    #[serde(default)]
-    skip_plugins: Vec<String>,
+    /// Overlay `other` on top of `self`: keys set in `other` win.
+    fn merge(self, other: RawConfig) -> Self {
+        Self {
+            exclude: other.exclude.or(self.exclude),
+            disabled_diagnostics: other.disabled_diagnostics.or(self.disabled_diagnostics),
+            fixture_paths: other.fixture_paths.or(self.fixture_paths),
+            skip_plugins: other.skip_plugins.or(self.skip_plugins),
+            index_cache: other.index_cache.or(self.index_cache),
+            cache_dir: other.cache_dir.or(self.cache_dir),
+            python: other.python.or(self.python),
+            venv: other.venv.or(self.venv),
+        }
+    }
 }
 
 /// Wrapper for the pyproject.toml structure.
@@ -54,44 +117,63 @@ struct Tool {
 }
 
 impl Config {
-    /// Load configuration from pyproject.toml in the given workspace root.
+    /// Load configuration from pyproject.toml in the given workspace root,
+    /// along with pytest's collection options.
     /// Returns default configuration if file doesn't exist or has errors.
     pub fn load(workspace_root: &Path) -> Self {
+        Self::load_with_settings(workspace_root, &serde_json::Value::Null)
+    }
+
+    /// Like [`load`](Self::load), with settings sent by the client (via
+    /// `initializationOptions` or `workspace/didChangeConfiguration`) taking
+    /// precedence over the keys in pyproject.toml.
+    pub fn load_with_settings(workspace_root: &Path, settings: &serde_json::Value) -> Self {
         let pyproject_path = workspace_root.join("pyproject.toml");
+        let raw = Self::load_pyproject(&pyproject_path).merge(RawConfig::from_settings(settings));
+        let mut config = Self::from_raw(raw, &pyproject_path);
+        config.pytest = PytestIniOptions::load(workspace_root);
+        config
+    }
 
+    /// Load the `[tool.pytest-language-server]` section from pyproject.toml.
+    fn load_pyproject(pyproject_path: &Path) -> RawConfig {
         if !pyproject_path.exists() {
             debug!(
                 "No pyproject.toml found at {:?}, using defaults",
                 pyproject_path
             );
-            return Self::default();
+            return RawConfig::default();
         }
 
-        match std::fs::read_to_string(&pyproject_path) {
-            Ok(content) => Self::parse(&content, &pyproject_path),
+        match std::fs::read_to_string(pyproject_path) {
+            Ok(content) => Self::parse_raw(&content, pyproject_path),
             Err(e) => {
                 warn!("Failed to read pyproject.toml: {}", e);
-                Self::default()
+                RawConfig::default()
             }
         }
     }
 
     /// Parse configuration from TOML content.
+    #[cfg(test)]
     fn parse(content: &str, path: &Path) -> Self {
+        Self::from_raw(Self::parse_raw(content, path), path)
+    }
+
+    /// Parse the raw `[tool.pytest-language-server]` section from TOML content.
+    fn parse_raw(content: &str, path: &Path) -> RawConfig {
         let parsed: PyProjectToml = match toml::from_str(content) {
             Ok(p) => p,
             Err(e) => {
                 warn!("Failed to parse pyproject.toml at {:?}: {}", path, e);
-                return Self::default();
+                return RawConfig::default();
             }
         };
 
-        let raw = parsed
+        parsed
             .tool
             .and_then(|t| t.pytest_language_server)
-            .unwrap_or_default();
-
-        Self::from_raw(raw, path)
+            .unwrap_or_default()
     }
 
     /// Convert raw config to validated config.
@@ -99,6 +181,7 @@ impl Config {
         // Parse exclude patterns, warning on invalid ones
         let exclude: Vec<Pattern> = raw
             .exclude
+            .unwrap_or_default()
             .into_iter()
             .filter_map(|pattern| match Pattern::new(&pattern) {
                 Ok(p) => Some(p),
@@ -112,11 +195,14 @@ impl Config {
         // Validate diagnostic codes
         let valid_diagnostics = [
             "undeclared-fixture",
+            "unknown-fixture",
             "scope-mismatch",
             "circular-dependency",
+            "fixture-override",
         ];
         let disabled_diagnostics: Vec<String> = raw
             .disabled_diagnostics
+            .unwrap_or_default()
             .into_iter()
             .filter(|code| {
                 if valid_diagnostics.contains(&code.as_str()) {
@@ -141,9 +227,84 @@ impl Config {
         Self {
             exclude,
             disabled_diagnostics,
-            fixture_paths: raw.fixture_paths,
-            skip_plugins: raw.skip_plugins,
+            fixture_paths: raw.fixture_paths.unwrap_or_default(),
+            skip_plugins: raw.skip_plugins.unwrap_or_default(),
+            pytest: PytestIniOptions::default(),
+            index_cache: raw.index_cache.unwrap_or(false),
+            cache_dir: raw.cache_dir.map(PathBuf::from),
+            python: raw.python.map(PathBuf::from),
+            venv: raw.venv.map(PathBuf::from),
+        }
+    }
+
+    /// Directory of the persistent fixture index for a workspace, or `None`
+    /// when `index_cache = false`.
+    pub fn index_cache_dir(&self, workspace_root: &Path) -> Option<PathBuf> {
+        if !self.index_cache {
+            return None;
         }
+        Some(match &self.cache_dir {
+            Some(dir) => workspace_root.join(dir),
+            None => crate::fixtures::default_cache_dir(workspace_root),
+        })
+    }
+
+    /// The `fixture_paths` directories of a workspace, canonicalized.
+    /// Directories that do not exist are skipped.
+    pub fn fixture_path_dirs(&self, workspace_root: &Path) -> Vec<PathBuf> {
+        self.fixture_paths
+            .iter()
+            .filter_map(|dir| match workspace_root.join(dir).canonicalize() {
+                Ok(path) if path.is_dir() => Some(path),
+                _ => {
+                    warn!("Fixture path is not a directory: {:?}", dir);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Explicit virtual environment settings for a workspace, with relative
+    /// paths resolved against its root.
+    pub fn venv_settings(&self, workspace_root: &Path) -> VenvSettings {
+        VenvSettings {
+            python: self.python.as_ref().map(|path| workspace_root.join(path)),
+            venv: self.venv.as_ref().map(|path| workspace_root.join(path)),
+            skip_plugins: self.skipped_plugins(),
+        }
+    }
+
+    /// Plugins not to load: `skip_plugins` plus those disabled with
+    /// `-p no:name` in pytest's `addopts`.
+    pub fn skipped_plugins(&self) -> Vec<String> {
+        let mut plugins = self.skip_plugins.clone();
+        for plugin in &self.pytest.disabled_plugins {
+            if !plugins.contains(plugin) {
+                plugins.push(plugin.clone());
+            }
+        }
+        plugins
+    }
+
+    /// Whether switching from `other` to this configuration changes which
+    /// files are scanned or how they are analyzed (as opposed to only which
+    /// diagnostics are reported).
+    pub fn scan_settings_differ(&self, other: &Config) -> bool {
+        let patterns = |config: &Config| -> Vec<String> {
+            config
+                .exclude
+                .iter()
+                .map(|pattern| pattern.as_str().to_string())
+                .collect()
+        };
+        patterns(self) != patterns(other)
+            || self.fixture_paths != other.fixture_paths
+            || self.skip_plugins != other.skip_plugins
+            || self.pytest != other.pytest
+            || self.python != other.python
+            || self.venv != other.venv
+            || self.index_cache != other.index_cache
+            || self.cache_dir != other.cache_dir
     }
 
     /// Check if a diagnostic code is disabled.
@@ -159,12 +320,6 @@ impl Config {
             .iter()
             .any(|pattern| pattern.matches(&path_str))
     }
-
-    /// Check if a plugin should be skipped when scanning venv.
-    #[allow(dead_code)] // Used in tests, venv scanning integration planned
-    pub fn should_skip_plugin(&self, plugin_name: &str) -> bool {
-        self.skip_plugins.iter().any(|p| p == plugin_name)
-    }
 }
 
 #[cfg(test)]
@@ -266,15 +421,40 @@ exclude = ["build/**", "dist"]
     }
 
     #[test]
-    fn test_should_skip_plugin() {
+    fn test_skipped_plugins_include_addopts() {
+        let config = Config {
+            skip_plugins: vec!["pytest-xdist".to_string()],
+            pytest: PytestIniOptions {
+                disabled_plugins: vec!["django".to_string(), "pytest-xdist".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(config.skipped_plugins(), vec!["pytest-xdist", "django"]);
+        let settings = config.venv_settings(Path::new("/repo"));
+        assert_eq!(settings.skip_plugins, vec!["pytest-xdist", "django"]);
+        assert!(settings.skips_plugin("xdist", Some("pytest_xdist")));
+        assert!(settings.skips_plugin("django", None));
+    }
+
+    #[test]
+    fn test_venv_settings_resolve_against_root() {
         let content = r#"
 [tool.pytest-language-server]
-skip_plugins = ["pytest-xdist", "pytest-cov"]
+venv = ".venvs/py312"
+python = "/opt/envs/project/bin/python"
 "#;
         let config = Config::parse(content, Path::new("pyproject.toml"));
-        assert!(config.should_skip_plugin("pytest-xdist"));
-        assert!(config.should_skip_plugin("pytest-cov"));
-        assert!(!config.should_skip_plugin("pytest-mock"));
+        let settings = config.venv_settings(Path::new("/work/project"));
+        assert_eq!(
+            settings.venv,
+            Some(PathBuf::from("/work/project/.venvs/py312"))
+        );
+        assert_eq!(
+            settings.python,
+            Some(PathBuf::from("/opt/envs/project/bin/python"))
+        );
+        assert!(config.scan_settings_differ(&Config::default()));
     }
 
     #[test]
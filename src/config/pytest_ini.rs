@@ -0,0 +1,436 @@
+//! pytest's own collection settings.
+//!
+//! Reads `python_files`, `python_functions`, `python_classes`, `testpaths`
+//! and the plugins disabled in `addopts` from the first pytest configuration
+//! file found in the workspace root, using pytest's precedence: `pytest.ini`,
+//! `.pytest.ini`, `pyproject.toml` (`[tool.pytest.ini_options]`), `tox.ini`
+//! (`[pytest]`) and `setup.cfg` (`[tool:pytest]`).
+
+use glob::Pattern;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// Collection settings that control which files, classes and functions pytest
+/// treats as tests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PytestIniOptions {
+    /// Glob patterns for test module file names (default: `test_*.py *_test.py`).
+    pub python_files: Vec<String>,
+    /// Prefixes or glob patterns for test function names (default: `test`).
+    pub python_functions: Vec<String>,
+    /// Prefixes or glob patterns for test class names (default: `Test`).
+    pub python_classes: Vec<String>,
+    /// Directories (relative to the workspace root, globs allowed) to collect from.
+    /// Empty means the whole workspace.
+    pub testpaths: Vec<String>,
+    /// Plugins disabled with `-p no:name` in `addopts`.
+    pub disabled_plugins: Vec<String>,
+    /// The configuration file the options were read from, if any.
+    pub source: Option<PathBuf>,
+}
+
+impl Default for PytestIniOptions {
+    fn default() -> Self {
+        Self {
+            python_files: vec!["test_*.py".to_string(), "*_test.py".to_string()],
+            python_functions: vec!["test".to_string()],
+            python_classes: vec!["Test".to_string()],
+            testpaths: Vec::new(),
+            disabled_plugins: Vec::new(),
+            source: None,
+        }
+    }
+}
+
+impl PytestIniOptions {
+    /// Load pytest options from the workspace root.
+    /// Returns pytest's defaults if no configuration file is found.
+    pub fn load(workspace_root: &Path) -> Self {
+        let candidates: [(&str, Option<&str>); 5] = [
+            ("pytest.ini", Some("pytest")),
+            (".pytest.ini", Some("pytest")),
+            ("pyproject.toml", None),
+            ("tox.ini", Some("pytest")),
+            ("setup.cfg", Some("tool:pytest")),
+        ];
+
+        for (file_name, ini_section) in candidates {
+            let path = workspace_root.join(file_name);
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+
+            let values = match ini_section {
+                Some(section) => {
+                    let values = parse_ini_section(&content, section);
+                    // pytest.ini is used even when it has no [pytest] section
+                    if values.is_none() && !file_name.ends_with("pytest.ini") {
+                        continue;
+                    }
+                    values.unwrap_or_default()
+                }
+                None => match parse_pyproject(&content, &path) {
+                    Some(values) => values,
+                    None => continue,
+                },
+            };
+
+            debug!("Loaded pytest options from {:?}", path);
+            return Self::from_values(values, path);
+        }
+
+        debug!(
+            "No pytest configuration found in {:?}, using pytest defaults",
+            workspace_root
+        );
+        Self::default()
+    }
+
+    /// Build options from raw `key -> values` pairs, falling back to defaults
+    /// for keys that are not set.
+    fn from_values(values: Vec<(String, Vec<String>)>, source: PathBuf) -> Self {
+        let mut options = Self {
+            source: Some(source),
+            ..Self::default()
+        };
+
+        for (key, value) in values {
+            match key.as_str() {
+                "python_files" => options.python_files = value,
+                "python_functions" => options.python_functions = value,
+                "python_classes" => options.python_classes = value,
+                "testpaths" => options.testpaths = value,
+                "addopts" => options.disabled_plugins = disabled_plugins(&value),
+                _ => {}
+            }
+        }
+
+        options
+    }
+
+    /// Check whether a file is a test module according to `python_files`.
+    ///
+    /// Like pytest, patterns are matched against the file name unless they
+    /// contain a path separator, in which case they are matched against the
+    /// end of the full path.
+    pub fn is_test_file(&self, path: &Path) -> bool {
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            return false;
+        };
+        let full_path = path.to_string_lossy();
+
+        self.python_files.iter().any(|pattern| {
+            if pattern.contains('/') {
+                glob_matches(&format!("*/{}", pattern), &full_path)
+            } else {
+                glob_matches(pattern, file_name)
+            }
+        })
+    }
+
+    /// Check whether a function name is a test according to `python_functions`.
+    pub fn is_test_function(&self, name: &str) -> bool {
+        self.python_functions
+            .iter()
+            .any(|pattern| name_matches(pattern, name))
+    }
+
+    /// Check whether a class name is collected according to `python_classes`.
+    pub fn is_test_class(&self, name: &str) -> bool {
+        self.python_classes
+            .iter()
+            .any(|pattern| name_matches(pattern, name))
+    }
+
+    /// Check whether a workspace-relative path lies inside one of `testpaths`.
+    /// Always true when `testpaths` is not set.
+    pub fn is_within_testpaths(&self, relative_path: &Path) -> bool {
+        if self.testpaths.is_empty() {
+            return true;
+        }
+
+        self.testpaths.iter().any(|testpath| {
+            let testpath = testpath.trim_end_matches('/');
+            if has_glob_chars(testpath) {
+                match Pattern::new(testpath) {
+                    Ok(pattern) => relative_path
+                        .ancestors()
+                        .any(|ancestor| pattern.matches_path(ancestor)),
+                    Err(_) => false,
+                }
+            } else {
+                relative_path.starts_with(testpath)
+            }
+        })
+    }
+
+    /// Check whether a workspace-relative directory is an ancestor of one of
+    /// `testpaths`.  pytest still loads `conftest.py` files from such
+    /// directories (e.g. the rootdir) even though no tests are collected there.
+    pub fn is_testpaths_ancestor(&self, relative_dir: &Path) -> bool {
+        if self.testpaths.is_empty() {
+            return true;
+        }
+
+        self.testpaths
+            .iter()
+            .any(|testpath| Path::new(literal_prefix(testpath)).starts_with(relative_dir))
+    }
+}
+
+/// Match a `python_functions`/`python_classes` entry: glob patterns are matched
+/// in full, anything else is treated as a name prefix.
+fn name_matches(pattern: &str, name: &str) -> bool {
+    if has_glob_chars(pattern) {
+        glob_matches(pattern, name)
+    } else {
+        name.starts_with(pattern)
+    }
+}
+
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    match Pattern::new(pattern) {
+        Ok(p) => p.matches(text),
+        Err(e) => {
+            warn!("Invalid pytest pattern '{}': {}", pattern, e);
+            false
+        }
+    }
+}
+
+fn has_glob_chars(s: &str) -> bool {
+    s.contains(['*', '?', '['])
+}
+
+/// Plugin names blocked with `-p no:name` (or `-pno:name`) in `addopts`.
+fn disabled_plugins(addopts: &[String]) -> Vec<String> {
+    let mut plugins = Vec::new();
+    let mut args = addopts.iter();
+    while let Some(arg) = args.next() {
+        let value = if arg == "-p" {
+            args.next().map(String::as_str)
+        } else {
+            arg.strip_prefix("-p")
+        };
+        if let Some(name) = value.and_then(|value| value.strip_prefix("no:")) {
+            plugins.push(name.to_string());
+        }
+    }
+    plugins
+}
+
+/// The leading path components of `pattern` that contain no glob characters.
+fn literal_prefix(pattern: &str) -> &str {
+    let pattern = pattern.trim_end_matches('/');
+    match pattern.find(['*', '?', '[']) {
+        Some(idx) => pattern[..idx]
+            .rfind('/')
+            .map_or("", |slash| &pattern[..slash]),
+        None => pattern,
+    }
+}
+
+/// Split an ini value into arguments the way pytest's `args` option type does
+/// (whitespace-separated, with optional quoting).
+fn split_args(value: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut in_arg = false;
+
+    for ch in value.chars() {
+        match quote {
+            Some(q) if ch == q => quote = None,
+            Some(_) => current.push(ch),
+            None if ch == '"' || ch == '\'' => {
+                quote = Some(ch);
+                in_arg = true;
+            }
+            None if ch.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            None => {
+                current.push(ch);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+
+    args
+}
+
+/// Parse `key = value` pairs (with indented continuation lines) from an ini
+/// section.  Returns `None` if the section does not exist.
+fn parse_ini_section(content: &str, section: &str) -> Option<Vec<(String, Vec<String>)>> {
+    let mut in_section = false;
+    let mut found = false;
+    let mut entries: Vec<(String, String)> = Vec::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            in_section = trimmed[1..trimmed.len() - 1].trim() == section;
+            found |= in_section;
+            continue;
+        }
+        if !in_section || trimmed.is_empty() || trimmed.starts_with(['#', ';']) {
+            continue;
+        }
+
+        let is_continuation = line.starts_with([' ', '\t']);
+        if is_continuation {
+            if let Some((_, value)) = entries.last_mut() {
+                value.push(' ');
+                value.push_str(trimmed);
+            }
+            continue;
+        }
+
+        if let Some((key, value)) = trimmed.split_once(['=', ':']) {
+            entries.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    found.then(|| {
+        entries
+            .into_iter()
+            .map(|(key, value)| (key, split_args(&value)))
+            .collect()
+    })
+}
+
+/// Read `[tool.pytest.ini_options]` from pyproject.toml.
+/// Returns `None` if the table does not exist.
+fn parse_pyproject(content: &str, path: &Path) -> Option<Vec<(String, Vec<String>)>> {
+    let parsed: toml::Value = match toml::from_str(content) {
+        Ok(v) => v,
+        Err(e) => {
+            warn!("Failed to parse {:?}: {}", path, e);
+            return None;
+        }
+    };
+
+    let table = parsed
+        .get("tool")?
+        .get("pytest")?
+        .get("ini_options")?
+        .as_table()?;
+
+    Some(
+        table
+            .iter()
+            .filter_map(|(key, value)| {
+                let values = match value {
+                    toml::Value::String(s) => split_args(s),
+                    toml::Value::Array(items) => items
+                        .iter()
+                        .filter_map(|item| item.as_str().map(str::to_string))
+                        .collect(),
+                    _ => return None,
+                };
+                Some((key.clone(), values))
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_match_pytest() {
+        let options = PytestIniOptions::default();
+        assert!(options.is_test_file(Path::new("/repo/tests/test_api.py")));
+        assert!(options.is_test_file(Path::new("/repo/tests/api_test.py")));
+        assert!(!options.is_test_file(Path::new("/repo/tests/helpers.py")));
+        assert!(options.is_test_function("test_api"));
+        assert!(options.is_test_function("testapi"));
+        assert!(!options.is_test_function("helper"));
+        assert!(options.is_test_class("TestApi"));
+        assert!(!options.is_test_class("ApiTests"));
+    }
+
+    #[test]
+    fn test_glob_and_prefix_names() {
+        let options = PytestIniOptions {
+            python_functions: vec!["check_*".to_string(), "verify".to_string()],
+            python_classes: vec!["*Suite".to_string()],
+            ..Default::default()
+        };
+        assert!(options.is_test_function("check_login"));
+        assert!(options.is_test_function("verify_login"));
+        assert!(!options.is_test_function("test_login"));
+        assert!(options.is_test_class("LoginSuite"));
+        assert!(!options.is_test_class("TestLogin"));
+    }
+
+    #[test]
+    fn test_python_files_with_directory_pattern() {
+        let options = PytestIniOptions {
+            python_files: vec!["checks/*.py".to_string()],
+            ..Default::default()
+        };
+        assert!(options.is_test_file(Path::new("/repo/checks/login.py")));
+        assert!(!options.is_test_file(Path::new("/repo/other/login.py")));
+    }
+
+    #[test]
+    fn test_testpaths() {
+        let options = PytestIniOptions {
+            testpaths: vec!["tests/".to_string(), "packages/*/tests".to_string()],
+            ..Default::default()
+        };
+        assert!(options.is_within_testpaths(Path::new("tests/unit/test_a.py")));
+        assert!(options.is_within_testpaths(Path::new("packages/core/tests/test_b.py")));
+        assert!(!options.is_within_testpaths(Path::new("docs/test_c.py")));
+
+        assert!(options.is_testpaths_ancestor(Path::new("")));
+        assert!(options.is_testpaths_ancestor(Path::new("packages")));
+        assert!(!options.is_testpaths_ancestor(Path::new("docs")));
+    }
+
+    #[test]
+    fn test_split_args_handles_quotes() {
+        assert_eq!(
+            split_args("check_*.py  'with space.py'\n\"x\""),
+            vec!["check_*.py", "with space.py", "x"]
+        );
+    }
+
+    #[test]
+    fn test_addopts_disabled_plugins() {
+        let options = PytestIniOptions::from_values(
+            vec![(
+                "addopts".to_string(),
+                split_args("-ra -p no:cacheprovider -pno:django -p pytester --strict-markers"),
+            )],
+            PathBuf::from("/repo/pytest.ini"),
+        );
+        assert_eq!(options.disabled_plugins, vec!["cacheprovider", "django"]);
+    }
+
+    #[test]
+    fn test_parse_ini_section_with_continuation_lines() {
+        let content = "[metadata]\nname = x\n\n[tool:pytest]\n# comment\ntestpaths =\n    tests\n    integration\npython_functions = check_*\n";
+        let values = parse_ini_section(content, "tool:pytest").unwrap();
+        assert_eq!(
+            values,
+            vec![
+                (
+                    "testpaths".to_string(),
+                    vec!["tests".to_string(), "integration".to_string()]
+                ),
+                ("python_functions".to_string(), vec!["check_*".to_string()]),
+            ]
+        );
+        assert!(parse_ini_section(content, "pytest").is_none());
+    }
+}
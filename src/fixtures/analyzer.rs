@@ -5,10 +5,13 @@
 //! and undeclared fixture scanning is in `undeclared.rs`.
 
 use super::decorators;
-use super::types::{FixtureDefinition, FixtureUsage, TypeImportSpec};
+use super::decorators::ParametrizeInfo;
+use super::types::{
+    ClassDefinition, FixtureDefinition, FixtureUsage, ParametrizedArgument, TypeImportSpec,
+};
 use super::FixtureDatabase;
 use once_cell::sync::Lazy;
-use rustpython_parser::ast::{ArgWithDefault, Arguments, Expr, Stmt};
+use rustpython_parser::ast::{ArgWithDefault, Arguments, Expr, Ranged, Stmt};
 use rustpython_parser::{parse, Mode};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
@@ -27,6 +30,22 @@ impl FixtureDatabase {
         self.analyze_file_internal(file_path, content, false);
     }
 
+    /// Remove everything known about a file: definitions, usages, imports and
+    /// cached content. Used when the file leaves the workspace.
+    pub(crate) fn forget_file(&self, file_path: &PathBuf) {
+        debug!("Forgetting file: {:?}", file_path);
+
+        self.cleanup_definitions_for_file(file_path);
+        self.cleanup_usages_for_file(file_path);
+        self.usages.remove(file_path);
+        self.parametrized_args.remove(file_path);
+        self.classes.remove(file_path);
+        self.undeclared_fixtures.remove(file_path);
+        self.imports.remove(file_path);
+        self.cleanup_file_cache(file_path);
+        self.invalidate_cycle_cache();
+    }
+
     /// Internal file analysis with optional cleanup of previous definitions
     fn analyze_file_internal(&self, file_path: PathBuf, content: &str, cleanup_previous: bool) {
         // Use cached canonical path to avoid repeated filesystem calls
@@ -56,6 +75,8 @@ impl FixtureDatabase {
         // Clear previous usages for this file (only after successful parse)
         self.cleanup_usages_for_file(&file_path);
         self.usages.remove(&file_path);
+        self.parametrized_args.remove(&file_path);
+        self.classes.remove(&file_path);
 
         // Clear previous undeclared fixtures for this file
         self.undeclared_fixtures.remove(&file_path);
@@ -108,6 +129,9 @@ impl FixtureDatabase {
             // underlying type before import resolution.
             let type_aliases = self.collect_type_aliases(&module.body, content);
 
+            // `pytestmark = pytest.mark.parametrize(...)` applies to every test in the module.
+            let module_marks = Self::collect_pytestmark_parametrize(&module.body, content);
+
             // Second pass: analyze fixtures and tests
             for stmt in &module.body {
                 self.visit_stmt(
@@ -119,6 +143,9 @@ impl FixtureDatabase {
                     &import_map,
                     &module_level_names,
                     &type_aliases,
+                    &module_marks,
+                    true,
+                    None,
                 );
             }
         }
@@ -254,27 +281,79 @@ impl FixtureDatabase {
         end_char: usize,
         is_parameter: bool,
     ) {
-        let file_path_buf = file_path.to_path_buf();
-        let usage = FixtureUsage {
-            name: fixture_name.clone(),
-            file_path: file_path_buf.clone(),
+        self.record_usage(FixtureUsage {
+            name: fixture_name,
+            file_path: file_path.to_path_buf(),
             line,
             start_char,
             end_char,
             is_parameter,
-        };
+            is_dynamic: false,
+        });
+    }
 
+    /// Record a usage in the per-file map and the usage_by_fixture reverse index.
+    pub(super) fn record_usage(&self, usage: FixtureUsage) {
         // Add to per-file usages map
         self.usages
-            .entry(file_path_buf.clone())
+            .entry(usage.file_path.clone())
             .or_default()
             .push(usage.clone());
 
         // Add to reverse index for efficient reference lookups
         self.usage_by_fixture
-            .entry(fixture_name)
+            .entry(usage.name.clone())
+            .or_default()
+            .push((usage.file_path.clone(), usage));
+    }
+
+    /// Helper to record a parameter supplied by `@pytest.mark.parametrize`.
+    fn record_parametrized_argument(&self, argument: ParametrizedArgument) {
+        self.parametrized_args
+            .entry(argument.file_path.clone())
+            .or_default()
+            .push(argument);
+    }
+
+    /// Helper to record a class, spanning its decorators and body.
+    fn record_class_definition(
+        &self,
+        class_def: &rustpython_parser::ast::StmtClassDef,
+        file_path: &Path,
+        line_index: &[usize],
+        enclosing_class: Option<&str>,
+    ) {
+        let start = class_def
+            .decorator_list
+            .iter()
+            .map(|decorator| decorator.range().start())
+            .chain(std::iter::once(class_def.range.start()))
+            .min()
+            .unwrap_or(class_def.range.start());
+        let bases = class_def.bases.iter().filter_map(dotted_name).collect();
+
+        self.classes
+            .entry(file_path.to_path_buf())
             .or_default()
-            .push((file_path_buf, usage));
+            .push(ClassDefinition {
+                name: class_def.name.to_string(),
+                file_path: file_path.to_path_buf(),
+                line: self.get_line_from_offset(start.to_usize(), line_index),
+                end_line: self.get_line_from_offset(class_def.range.end().to_usize(), line_index),
+                bases,
+                outer: enclosing_class.map(str::to_string),
+            });
+    }
+
+    /// Where the fixtures of a file come from: `(is_third_party, is_plugin,
+    /// fixture_path)`. These depend on the environment rather than the file's
+    /// content, so they are recomputed whenever definitions are recorded.
+    pub(crate) fn definition_origin(&self, file_path: &Path) -> (bool, bool, Option<PathBuf>) {
+        let is_third_party = file_path.to_string_lossy().contains("site-packages")
+            || self.is_editable_install_third_party(file_path);
+        let fixture_path = self.fixture_path_for(file_path);
+        let is_plugin = self.plugin_fixture_files.contains_key(file_path) || fixture_path.is_some();
+        (is_third_party, is_plugin, fixture_path)
     }
 
     /// Helper to record a fixture definition in the database.
@@ -299,22 +378,31 @@ impl FixtureDatabase {
         self.invalidate_cycle_cache();
     }
 
-    /// Visit a statement and extract fixture definitions and usages
+    /// Visit a statement and extract fixture definitions and usages.
+    ///
+    /// `parametrize_marks` holds the parametrize marks inherited from an
+    /// enclosing class or a `pytestmark` assignment. `collects_tests` is false
+    /// inside classes that pytest does not collect (names not matching
+    /// `python_classes`); fixtures there are still recorded. `enclosing_class`
+    /// is the innermost class the statement is in.
     #[allow(clippy::too_many_arguments)]
     fn visit_stmt(
         &self,
         stmt: &Stmt,
         file_path: &PathBuf,
-        _is_conftest: bool,
+        is_conftest: bool,
         content: &str,
         line_index: &[usize],
         import_map: &HashMap<String, TypeImportSpec>,
         module_level_names: &HashSet<String>,
         type_aliases: &HashMap<String, String>,
+        parametrize_marks: &[ParametrizeInfo],
+        collects_tests: bool,
+        enclosing_class: Option<&str>,
     ) {
         // First check for assignment-style fixtures: fixture_name = pytest.fixture()(func)
         if let Stmt::Assign(assign) = stmt {
-            self.visit_assignment_fixture(assign, file_path, content, line_index);
+            self.visit_assignment_fixture(assign, file_path, content, line_index, enclosing_class);
 
             // Check for pytestmark = pytest.mark.usefixtures(...) or
             // pytestmark = [pytest.mark.usefixtures(...), ...]
@@ -343,6 +431,8 @@ impl FixtureDatabase {
 
         // Handle class definitions - recurse into class body to find test methods
         if let Stmt::ClassDef(class_def) = stmt {
+            self.record_class_definition(class_def, file_path, line_index, enclosing_class);
+
             // Check for @pytest.mark.usefixtures decorator on the class
             for decorator in &class_def.decorator_list {
                 let usefixtures = decorators::extract_usefixtures_names(decorator);
@@ -370,16 +460,35 @@ impl FixtureDatabase {
                 }
             }
 
+            // Parametrize marks on the class apply to every test method in it
+            let mut class_marks = parametrize_marks.to_vec();
+            class_marks.extend(
+                class_def
+                    .decorator_list
+                    .iter()
+                    .filter_map(|decorator| decorators::extract_parametrize(decorator, content)),
+            );
+            class_marks.extend(Self::collect_pytestmark_parametrize(
+                &class_def.body,
+                content,
+            ));
+
+            let class_collects_tests =
+                collects_tests && self.is_test_class_name(file_path, class_def.name.as_str());
+
             for class_stmt in &class_def.body {
                 self.visit_stmt(
                     class_stmt,
                     file_path,
-                    _is_conftest,
+                    is_conftest,
                     content,
                     line_index,
                     import_map,
                     module_level_names,
                     type_aliases,
+                    &class_marks,
+                    class_collects_tests,
+                    Some(class_def.name.as_str()),
                 );
             }
             return;
@@ -480,6 +589,7 @@ impl FixtureDatabase {
             // Extract scope from decorator (defaults to function scope)
             let scope = decorators::extract_fixture_scope(decorator).unwrap_or_default();
             let autouse = decorators::extract_fixture_autouse(decorator);
+            let (params, param_ids) = decorators::extract_fixture_params(decorator, content);
 
             let line = self.get_line_from_offset(range.start().to_usize(), line_index);
             let docstring = self.extract_docstring(body);
@@ -512,9 +622,7 @@ impl FixtureDatabase {
 
             let (start_char, end_char) = self.find_function_name_position(content, line, func_name);
 
-            let is_third_party = file_path.to_string_lossy().contains("site-packages")
-                || self.is_editable_install_third_party(file_path);
-            let is_plugin = self.plugin_fixture_files.contains_key(file_path);
+            let (is_third_party, is_plugin, fixture_path) = self.definition_origin(file_path);
 
             // Fixtures can depend on other fixtures - collect dependencies first
             let mut declared_params: HashSet<String> = HashSet::new();
@@ -551,6 +659,10 @@ impl FixtureDatabase {
                 scope,
                 yield_line: self.find_yield_line(body, line_index),
                 autouse,
+                params,
+                param_ids,
+                class_name: enclosing_class.map(str::to_string),
+                fixture_path,
             };
 
             self.record_fixture_definition(definition);
@@ -601,7 +713,7 @@ impl FixtureDatabase {
         }
 
         // Check if this is a test function
-        let is_test = func_name.starts_with("test_");
+        let is_test = collects_tests && self.is_test_function_name(file_path, func_name);
 
         if is_test {
             debug!("Found test function: {}", func_name);
@@ -610,6 +722,16 @@ impl FixtureDatabase {
             declared_params.insert("self".to_string());
             declared_params.insert("request".to_string());
 
+            // Stacked parametrize decorators plus marks inherited from class/module
+            let marks: Vec<ParametrizeInfo> =
+                parametrize_marks
+                    .iter()
+                    .cloned()
+                    .chain(decorator_list.iter().filter_map(|decorator| {
+                        decorators::extract_parametrize(decorator, content)
+                    }))
+                    .collect();
+
             for arg in Self::all_args(args) {
                 let arg_name = arg.def.arg.as_str();
                 declared_params.insert(arg_name.to_string());
@@ -621,6 +743,26 @@ impl FixtureDatabase {
                     // Use parameter name length, not AST range (which includes type annotation)
                     let end_char = start_char + arg_name.len();
 
+                    // Direct parametrize argnames are supplied by pytest, not by a fixture
+                    if let Some(mark) = marks.iter().find(|m| m.is_direct_argname(arg_name)) {
+                        info!(
+                            "Found parametrized argument: {} at {:?}:{}:{}",
+                            arg_name, file_path, arg_line, start_char
+                        );
+
+                        let (values, ids) = mark.values_for(arg_name);
+                        self.record_parametrized_argument(ParametrizedArgument {
+                            name: arg_name.to_string(),
+                            file_path: file_path.clone(),
+                            line: arg_line,
+                            start_char,
+                            end_char,
+                            values,
+                            ids,
+                        });
+                        continue;
+                    }
+
                     debug!(
                         "Parameter {} at offset {}, calculated line {}, char {}",
                         arg_name, arg_offset, arg_line, start_char
@@ -650,9 +792,47 @@ impl FixtureDatabase {
                 func_name,
                 function_line,
             );
+        } else if fixture_decorator.is_none() && (is_conftest || self.is_test_file_path(file_path))
+        {
+            // Hooks and helpers take no fixtures, but may still look them up
+            // through `item._request.getfixturevalue(...)`
+            let function_line = self.get_line_from_offset(range.start().to_usize(), line_index);
+            self.scan_function_body_for_getfixturevalue(
+                body,
+                file_path,
+                line_index,
+                func_name,
+                function_line,
+            );
         }
     }
 
+    /// Collect parametrize marks applied through `pytestmark = ...` assignments
+    /// in a module or class body.
+    fn collect_pytestmark_parametrize(body: &[Stmt], content: &str) -> Vec<ParametrizeInfo> {
+        body.iter()
+            .filter_map(|stmt| match stmt {
+                Stmt::Assign(assign)
+                    if assign.targets.iter().any(
+                        |t| matches!(t, Expr::Name(name) if name.id.as_str() == "pytestmark"),
+                    ) =>
+                {
+                    Some(assign.value.as_ref())
+                }
+                Stmt::AnnAssign(ann_assign)
+                    if matches!(
+                        ann_assign.target.as_ref(),
+                        Expr::Name(name) if name.id.as_str() == "pytestmark"
+                    ) =>
+                {
+                    ann_assign.value.as_deref()
+                }
+                _ => None,
+            })
+            .flat_map(|value| decorators::extract_parametrize_from_expr(value, content))
+            .collect()
+    }
+
     /// Handle assignment-style fixtures: fixture_name = pytest.fixture()(func)
     fn visit_assignment_fixture(
         &self,
@@ -660,6 +840,7 @@ impl FixtureDatabase {
         file_path: &PathBuf,
         _content: &str,
         line_index: &[usize],
+        enclosing_class: Option<&str>,
     ) {
         if let Expr::Call(outer_call) = &*assign.value {
             if let Expr::Call(inner_call) = &*outer_call.func {
@@ -684,10 +865,8 @@ impl FixtureDatabase {
                                 fixture_name, file_path, line, start_char, end_char
                             );
 
-                            let is_third_party =
-                                file_path.to_string_lossy().contains("site-packages")
-                                    || self.is_editable_install_third_party(file_path);
-                            let is_plugin = self.plugin_fixture_files.contains_key(file_path);
+                            let (is_third_party, is_plugin, fixture_path) =
+                                self.definition_origin(file_path);
                             let definition = FixtureDefinition {
                                 name: fixture_name.to_string(),
                                 file_path: file_path.clone(),
@@ -705,6 +884,10 @@ impl FixtureDatabase {
                                     .unwrap_or_default(),
                                 yield_line: None, // Assignment-style fixtures don't have yield statements
                                 autouse: false,   // Assignment-style fixtures are never autouse
+                                params: vec![],
+                                param_ids: vec![],
+                                class_name: enclosing_class.map(str::to_string),
+                                fixture_path,
                             };
 
                             self.record_fixture_definition(definition);
@@ -756,6 +939,17 @@ impl FixtureDatabase {
     }
 }
 
+/// Dotted name of a class base such as `Base` or `module.Base`.
+fn dotted_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Name(name) => Some(name.id.to_string()),
+        Expr::Attribute(attr) => {
+            dotted_name(&attr.value).map(|value| format!("{}.{}", value, attr.attr))
+        }
+        _ => None,
+    }
+}
+
 /// Python builtin types that never require an import statement.
 /// Uses O(1) `HashSet` lookup, consistent with `is_standard_library_module()`.
 static BUILTINS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
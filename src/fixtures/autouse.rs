@@ -0,0 +1,121 @@
+//! Autouse fixtures applying to tests.
+//!
+//! pytest runs every autouse fixture visible from a test (same file,
+//! conftest.py hierarchy, plugins, enclosing test classes) even though the
+//! test never requests it, which makes their side effects easy to miss.
+
+use super::types::FixtureDefinition;
+use super::FixtureDatabase;
+use rustpython_parser::ast::{Mod, Stmt};
+use std::cmp::Reverse;
+use std::path::Path;
+
+/// The autouse fixtures pytest runs for a test function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestAutouse {
+    pub test_name: String,
+    pub line: usize, // 1-based line of the `def`
+    pub start_char: usize,
+    pub end_char: usize,
+    /// Autouse fixtures in setup order: broader scopes first.
+    pub fixtures: Vec<FixtureDefinition>,
+}
+
+impl FixtureDatabase {
+    /// Get the autouse fixtures visible at a line (1-based), broader scopes
+    /// first and by name within a scope.
+    pub fn get_autouse_fixtures_at_line(
+        &self,
+        file_path: &Path,
+        line: usize,
+    ) -> Vec<FixtureDefinition> {
+        let mut autouse: Vec<FixtureDefinition> = self
+            .get_available_fixtures_at_line(file_path, line)
+            .into_iter()
+            .filter(|def| def.autouse)
+            .collect();
+        autouse.sort_by(|a, b| {
+            Reverse(a.scope)
+                .cmp(&Reverse(b.scope))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        autouse
+    }
+
+    /// Find the autouse fixtures of every test in a file. Tests without any
+    /// are omitted.
+    pub fn find_test_autouse_fixtures(&self, file_path: &Path) -> Vec<TestAutouse> {
+        let Some(content) = self.get_file_content(file_path) else {
+            return vec![];
+        };
+        let Some(parsed) = self.get_parsed_ast(file_path, &content) else {
+            return vec![];
+        };
+        let Mod::Module(module) = parsed.as_ref() else {
+            return vec![];
+        };
+        let line_index = self.get_line_index(file_path, &content);
+
+        let mut tests = Vec::new();
+        self.collect_test_autouse(file_path, &module.body, &content, &line_index, &mut tests);
+        tests
+    }
+
+    /// Find the autouse fixtures of the test whose name is at a position
+    /// (0-based line).
+    pub fn find_test_autouse_at_position(
+        &self,
+        file_path: &Path,
+        line: u32,
+        character: u32,
+    ) -> Option<TestAutouse> {
+        let line = line as usize + 1;
+        let character = character as usize;
+        self.find_test_autouse_fixtures(file_path)
+            .into_iter()
+            .find(|test| {
+                test.line == line && character >= test.start_char && character < test.end_char
+            })
+    }
+
+    fn collect_test_autouse(
+        &self,
+        file_path: &Path,
+        body: &[Stmt],
+        content: &str,
+        line_index: &[usize],
+        tests: &mut Vec<TestAutouse>,
+    ) {
+        for stmt in body {
+            let (name, offset) = match stmt {
+                Stmt::FunctionDef(func) => (func.name.as_str(), func.range.start().to_usize()),
+                Stmt::AsyncFunctionDef(func) => (func.name.as_str(), func.range.start().to_usize()),
+                Stmt::ClassDef(class)
+                    if self.is_test_class_name(file_path, class.name.as_str()) =>
+                {
+                    self.collect_test_autouse(file_path, &class.body, content, line_index, tests);
+                    continue;
+                }
+                _ => continue,
+            };
+            if !self.is_test_function_name(file_path, name) {
+                continue;
+            }
+
+            let line = self.get_line_from_offset(offset, line_index);
+            let fixtures = self.get_autouse_fixtures_at_line(file_path, line);
+            if fixtures.is_empty() {
+                continue;
+            }
+            let (start_char, end_char) =
+                super::string_utils::find_function_name_position(content, line, name);
+            tests.push(TestAutouse {
+                test_name: name.to_string(),
+                line,
+                start_char,
+                end_char,
+                fixtures,
+            });
+        }
+    }
+}
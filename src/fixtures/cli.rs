@@ -54,12 +54,17 @@ impl FixtureDatabase {
                         &usage.name,
                         fixture_def_at_line.as_ref(),
                     )
+                } else if !self.visible_classes(file_path, usage.line).is_empty() {
+                    // Usages inside a test class may see its class-scoped fixtures
+                    self.find_closest_definition_at_line(file_path, &usage.name, usage.line)
                 } else {
                     let cache_key = (file_path.clone(), usage.name.clone());
                     if let Some(cached) = resolution_cache.get(&cache_key) {
                         cached.as_ref().and_then(|def_path| {
                             self.definitions.get(&usage.name).and_then(|defs| {
-                                defs.iter().find(|d| &d.file_path == def_path).cloned()
+                                defs.iter()
+                                    .find(|d| &d.file_path == def_path && d.class_name.is_none())
+                                    .cloned()
                             })
                         })
                     } else {
@@ -111,7 +116,17 @@ impl FixtureDatabase {
 
         // Remap editable install paths to virtual site-packages paths for display.
         // Only remap files that are outside the workspace (third-party editable installs).
-        let mut editable_dirs: HashSet<PathBuf> = HashSet::new();
+        // Directory labels: editable installs and `fixture_paths` directories
+        let mut dir_labels: HashMap<PathBuf, &str> = HashMap::new();
+        for dir in self
+            .root_fixture_paths
+            .read()
+            .unwrap()
+            .iter()
+            .flat_map(|(_, dirs)| dirs)
+        {
+            dir_labels.insert(dir.clone(), "fixture path");
+        }
         {
             let installs = self.editable_install_roots.lock().unwrap();
             let workspace = self.workspace_root.lock().unwrap();
@@ -143,7 +158,7 @@ impl FixtureDatabase {
                             for part in &parts {
                                 label_path = label_path.join(part.replace('-', "_"));
                             }
-                            editable_dirs.insert(label_path);
+                            dir_labels.insert(label_path, "editable install");
                         }
                         remapped.push((original_path, virtual_path));
                     }
@@ -250,7 +265,7 @@ impl FixtureDatabase {
                 &definition_usage_counts,
                 skip_unused,
                 only_unused,
-                &editable_dirs,
+                &dir_labels,
                 &autouse_fixtures,
             );
         }
@@ -268,7 +283,7 @@ impl FixtureDatabase {
         definition_usage_counts: &HashMap<(PathBuf, String), usize>,
         skip_unused: bool,
         only_unused: bool,
-        editable_dirs: &HashSet<PathBuf>,
+        dir_labels: &HashMap<PathBuf, &str>,
         autouse_fixtures: &HashSet<(PathBuf, String)>,
     ) {
         use colored::Colorize;
@@ -389,10 +404,9 @@ impl FixtureDatabase {
                 return;
             }
 
-            let dir_label = if editable_dirs.contains(path) {
-                format!("{}/ (editable install)", name)
-            } else {
-                format!("{}/", name)
+            let dir_label = match dir_labels.get(path) {
+                Some(label) => format!("{}/ ({})", name, label),
+                None => format!("{}/", name),
             };
             let dir_display = dir_label.blue().bold();
             println!("{}{}{}", prefix, connector, dir_display);
@@ -415,7 +429,7 @@ impl FixtureDatabase {
                     definition_usage_counts,
                     skip_unused,
                     only_unused,
-                    editable_dirs,
+                    dir_labels,
                     autouse_fixtures,
                 );
             }
@@ -468,8 +482,17 @@ impl FixtureDatabase {
     /// Returns a vector of (file_path, fixture_name) tuples sorted by path then name.
     /// Excludes third-party fixtures from site-packages.
     pub fn get_unused_fixtures(&self) -> Vec<(PathBuf, String)> {
+        self.get_unused_fixture_definitions()
+            .into_iter()
+            .map(|def| (def.file_path, def.name))
+            .collect()
+    }
+
+    /// Get the definitions of all unused fixtures, sorted by path then name.
+    /// Excludes third-party and autouse fixtures.
+    pub fn get_unused_fixture_definitions(&self) -> Vec<FixtureDefinition> {
         let definition_usage_counts = self.compute_definition_usage_counts();
-        let mut unused: Vec<(PathBuf, String)> = Vec::new();
+        let mut unused: Vec<FixtureDefinition> = Vec::new();
 
         for entry in self.definitions.iter() {
             let fixture_name = entry.key();
@@ -490,13 +513,17 @@ impl FixtureDatabase {
                     .unwrap_or(0);
 
                 if usage_count == 0 {
-                    unused.push((def.file_path.clone(), fixture_name.clone()));
+                    unused.push(def.clone());
                 }
             }
         }
 
         // Sort by file path, then by fixture name for deterministic output
-        unused.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+        unused.sort_by(|a, b| {
+            a.file_path
+                .cmp(&b.file_path)
+                .then_with(|| a.name.cmp(&b.name))
+        });
         unused
     }
 }
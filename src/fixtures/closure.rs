@@ -0,0 +1,419 @@
+//! Effective fixture closure of a test.
+//!
+//! Mirrors what pytest sets up before running a test: the autouse fixtures,
+//! `usefixtures` marks and parameters it requests, plus every fixture they
+//! depend on, in setup order.
+
+use super::decorators;
+use super::types::{FixtureDefinition, FixtureScope};
+use super::FixtureDatabase;
+use crate::report::relative_path;
+use rustpython_parser::ast::{Arguments, Expr, Mod, Stmt};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Why a fixture is part of a test's closure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClosureSource {
+    Autouse,
+    Usefixtures,
+    Parameter,
+    /// Requested by another fixture of the closure.
+    Dependency,
+}
+
+impl ClosureSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Autouse => "autouse",
+            Self::Usefixtures => "usefixtures",
+            Self::Parameter => "parameter",
+            Self::Dependency => "dependency",
+        }
+    }
+}
+
+/// A fixture pytest sets up for a test.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClosureEntry {
+    pub name: String,
+    /// The resolved definition, or `None` if it could not be resolved (e.g.
+    /// pytest built-ins such as `tmp_path`).
+    pub definition: Option<FixtureDefinition>,
+    pub source: ClosureSource,
+    /// The fixture that first requested this one, for dependencies.
+    pub requested_by: Option<String>,
+    /// The definition this one overrides: requesting its own name from the
+    /// resolved definition redirects there.
+    pub overrides: Option<FixtureDefinition>,
+}
+
+/// The fixtures pytest sets up for a test, in setup order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixtureClosure {
+    /// `test_name` or `TestClass::test_name`.
+    pub test_name: String,
+    pub file_path: PathBuf,
+    pub line: usize, // 1-based line of the `def`
+    pub entries: Vec<ClosureEntry>,
+}
+
+/// A test function found in a module.
+struct TestFunction<'a> {
+    qualified_name: String,
+    line: usize,
+    end_line: usize,
+    args: &'a Arguments,
+    /// Marks of the function, its classes and `pytestmark` assignments.
+    marks: Vec<&'a Expr>,
+}
+
+#[derive(Clone, Hash, PartialEq, Eq)]
+enum EntryKey {
+    Definition(PathBuf, usize, String),
+    Unresolved(String),
+}
+
+impl EntryKey {
+    fn of(name: &str, definition: Option<&FixtureDefinition>) -> Self {
+        match definition {
+            Some(def) => Self::Definition(def.file_path.clone(), def.line, def.name.clone()),
+            None => Self::Unresolved(name.to_string()),
+        }
+    }
+}
+
+impl FixtureDatabase {
+    /// Compute the fixture closure of a test, given as `test_name` or
+    /// `TestClass::test_name`.
+    pub fn compute_fixture_closure(
+        &self,
+        file_path: &Path,
+        test_name: &str,
+    ) -> Option<FixtureClosure> {
+        self.compute_closure_matching(file_path, |test| test.qualified_name == test_name)
+    }
+
+    /// Compute the fixture closure of the test containing a line (1-based).
+    pub fn compute_fixture_closure_at_line(
+        &self,
+        file_path: &Path,
+        line: usize,
+    ) -> Option<FixtureClosure> {
+        self.compute_closure_matching(file_path, |test| test.line <= line && line <= test.end_line)
+    }
+
+    fn compute_closure_matching(
+        &self,
+        file_path: &Path,
+        matches: impl Fn(&TestFunction<'_>) -> bool,
+    ) -> Option<FixtureClosure> {
+        let content = self.get_file_content(file_path)?;
+        let parsed = self.get_parsed_ast(file_path, &content)?;
+        let Mod::Module(module) = parsed.as_ref() else {
+            return None;
+        };
+        let line_index = self.get_line_index(file_path, &content);
+
+        let mut tests = Vec::new();
+        self.collect_test_functions(file_path, &module.body, "", &[], &line_index, &mut tests);
+        let test = tests.into_iter().find(|test| matches(test))?;
+        let entries = self.closure_entries(file_path, &test, &content);
+
+        Some(FixtureClosure {
+            test_name: test.qualified_name,
+            file_path: file_path.to_path_buf(),
+            line: test.line,
+            entries,
+        })
+    }
+
+    fn collect_test_functions<'a>(
+        &self,
+        file_path: &Path,
+        body: &'a [Stmt],
+        prefix: &str,
+        outer_marks: &[&'a Expr],
+        line_index: &[usize],
+        tests: &mut Vec<TestFunction<'a>>,
+    ) {
+        let mut marks: Vec<&'a Expr> = outer_marks.to_vec();
+        for stmt in body {
+            if let Stmt::Assign(assign) = stmt {
+                let is_pytestmark = assign.targets.iter().any(
+                    |target| matches!(target, Expr::Name(name) if name.id.as_str() == "pytestmark"),
+                );
+                if is_pytestmark {
+                    marks.push(&assign.value);
+                }
+            }
+        }
+
+        for stmt in body {
+            let (name, args, decorator_list, range) = match stmt {
+                Stmt::FunctionDef(func) => {
+                    (&func.name, &*func.args, &func.decorator_list, func.range)
+                }
+                Stmt::AsyncFunctionDef(func) => {
+                    (&func.name, &*func.args, &func.decorator_list, func.range)
+                }
+                Stmt::ClassDef(class)
+                    if self.is_test_class_name(file_path, class.name.as_str()) =>
+                {
+                    let mut class_marks = marks.clone();
+                    class_marks.extend(class.decorator_list.iter());
+                    self.collect_test_functions(
+                        file_path,
+                        &class.body,
+                        &format!("{}{}::", prefix, class.name),
+                        &class_marks,
+                        line_index,
+                        tests,
+                    );
+                    continue;
+                }
+                _ => continue,
+            };
+            if !self.is_test_function_name(file_path, name.as_str()) {
+                continue;
+            }
+
+            let mut test_marks = marks.clone();
+            test_marks.extend(decorator_list.iter());
+            tests.push(TestFunction {
+                qualified_name: format!("{}{}", prefix, name),
+                line: self.get_line_from_offset(range.start().to_usize(), line_index),
+                end_line: self.get_line_from_offset(range.end().to_usize(), line_index),
+                args,
+                marks: test_marks,
+            });
+        }
+    }
+
+    /// Resolve the closure of a test in setup order: pytest sorts the whole
+    /// closure by scope (broader first) and sets up each fixture's
+    /// dependencies before the fixture itself.
+    fn closure_entries(
+        &self,
+        file_path: &Path,
+        test: &TestFunction<'_>,
+        content: &str,
+    ) -> Vec<ClosureEntry> {
+        // Names requested by the test itself; the first source wins
+        let mut requested: Vec<(String, ClosureSource)> = Vec::new();
+        for def in self.get_autouse_fixtures_at_line(file_path, test.line) {
+            requested.push((def.name, ClosureSource::Autouse));
+        }
+        for mark in &test.marks {
+            for (name, _) in decorators::extract_usefixtures_from_expr(mark) {
+                requested.push((name, ClosureSource::Usefixtures));
+            }
+        }
+        let parametrize: Vec<decorators::ParametrizeInfo> = test
+            .marks
+            .iter()
+            .flat_map(|mark| decorators::extract_parametrize_from_expr(mark, content))
+            .collect();
+        for arg in Self::all_args(test.args) {
+            let name = arg.def.arg.as_str();
+            // Directly parametrized arguments are values, not fixtures
+            if name == "self" || parametrize.iter().any(|info| info.is_direct_argname(name)) {
+                continue;
+            }
+            requested.push((name.to_string(), ClosureSource::Parameter));
+        }
+
+        // Collect the closure breadth-first, as pytest does
+        let mut entries: Vec<ClosureEntry> = Vec::new();
+        let mut dependencies: Vec<Vec<EntryKey>> = Vec::new();
+        let mut indices: HashMap<EntryKey, usize> = HashMap::new();
+        let mut seen_names: HashSet<String> = HashSet::new();
+        let mut queue: Vec<ClosureEntry> = Vec::new();
+        for (name, source) in requested {
+            if !seen_names.insert(name.clone()) {
+                continue;
+            }
+            let definition = self.find_closest_definition_at_line(file_path, &name, test.line);
+            queue.push(self.closure_entry(name, definition, source, None));
+        }
+
+        let mut next = 0;
+        while next < queue.len() {
+            let entry = queue[next].clone();
+            next += 1;
+            let key = EntryKey::of(&entry.name, entry.definition.as_ref());
+            if indices.contains_key(&key) {
+                continue;
+            }
+
+            let mut dep_keys = Vec::new();
+            if let Some(def) = &entry.definition {
+                for dep_name in &def.dependencies {
+                    // A fixture requesting its own name gets the definition it
+                    // overrides; every other name is resolved from the test, so
+                    // overrides closer to the test win
+                    let resolved = if *dep_name == def.name {
+                        entry.overrides.clone()
+                    } else {
+                        self.find_closest_definition_at_line(file_path, dep_name, test.line)
+                    };
+                    dep_keys.push(EntryKey::of(dep_name, resolved.as_ref()));
+                    queue.push(self.closure_entry(
+                        dep_name.clone(),
+                        resolved,
+                        ClosureSource::Dependency,
+                        Some(def.name.clone()),
+                    ));
+                }
+            }
+
+            indices.insert(key, entries.len());
+            entries.push(entry);
+            dependencies.push(dep_keys);
+        }
+
+        // Broader scopes first; unresolved fixtures count as function-scoped
+        let mut order: Vec<usize> = (0..entries.len()).collect();
+        order.sort_by_key(|&index| {
+            std::cmp::Reverse(
+                entries[index]
+                    .definition
+                    .as_ref()
+                    .map_or(FixtureScope::Function, |def| def.scope),
+            )
+        });
+
+        let mut setup_order = Vec::with_capacity(entries.len());
+        let mut visited = vec![false; entries.len()];
+        for index in order {
+            Self::visit_closure_entry(
+                index,
+                &indices,
+                &dependencies,
+                &mut visited,
+                &mut setup_order,
+            );
+        }
+        setup_order
+            .into_iter()
+            .map(|index| entries[index].clone())
+            .collect()
+    }
+
+    fn closure_entry(
+        &self,
+        name: String,
+        definition: Option<FixtureDefinition>,
+        source: ClosureSource,
+        requested_by: Option<String>,
+    ) -> ClosureEntry {
+        let overrides = definition.as_ref().and_then(|def| {
+            self.find_closest_definition_excluding(&def.file_path, &def.name, Some(def))
+        });
+        ClosureEntry {
+            name,
+            definition,
+            source,
+            requested_by,
+            overrides,
+        }
+    }
+
+    /// Depth-first post-order walk: dependencies are set up first. Cycles
+    /// are cut at the entry already being visited.
+    fn visit_closure_entry(
+        index: usize,
+        indices: &HashMap<EntryKey, usize>,
+        dependencies: &[Vec<EntryKey>],
+        visited: &mut [bool],
+        setup_order: &mut Vec<usize>,
+    ) {
+        if visited[index] {
+            return;
+        }
+        visited[index] = true;
+        for dep in &dependencies[index] {
+            if let Some(&dep_index) = indices.get(dep) {
+                Self::visit_closure_entry(dep_index, indices, dependencies, visited, setup_order);
+            }
+        }
+        setup_order.push(index);
+    }
+}
+
+impl FixtureClosure {
+    /// Render as an indented text listing. Paths are shown relative to
+    /// `root_path`.
+    pub fn to_text(&self, root_path: &Path) -> String {
+        let mut out = format!(
+            "{}::{} ({} fixture{})\n",
+            relative_path(&self.file_path, root_path),
+            self.test_name,
+            self.entries.len(),
+            if self.entries.len() == 1 { "" } else { "s" }
+        );
+        for (position, entry) in self.entries.iter().enumerate() {
+            let location = match &entry.definition {
+                Some(def) => format!(
+                    "{:<8} {}:{}",
+                    def.scope.as_str(),
+                    relative_path(&def.file_path, root_path),
+                    def.line
+                ),
+                None => format!("{:<8} (unresolved)", "-"),
+            };
+            let reason = match &entry.requested_by {
+                Some(parent) => format!("requested by {}", parent),
+                None => entry.source.as_str().to_string(),
+            };
+            out.push_str(&format!(
+                "{:>3}. {} [{}] {}",
+                position + 1,
+                entry.name,
+                reason,
+                location
+            ));
+            if let Some(overridden) = &entry.overrides {
+                out.push_str(&format!(
+                    " (overrides {}:{})",
+                    relative_path(&overridden.file_path, root_path),
+                    overridden.line
+                ));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Render as JSON. Paths are shown relative to `root_path`.
+    pub fn to_json(&self, root_path: &Path) -> serde_json::Value {
+        let location = |def: &Option<FixtureDefinition>| match def {
+            Some(def) => serde_json::json!({
+                "file": relative_path(&def.file_path, root_path),
+                "line": def.line,
+            }),
+            None => serde_json::Value::Null,
+        };
+        let entries: Vec<serde_json::Value> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                serde_json::json!({
+                    "name": entry.name,
+                    "scope": entry.definition.as_ref().map(|def| def.scope.as_str()),
+                    "source": entry.source.as_str(),
+                    "requested_by": entry.requested_by,
+                    "definition": location(&entry.definition),
+                    "overrides": location(&entry.overrides),
+                })
+            })
+            .collect();
+
+        serde_json::json!({
+            "test": self.test_name,
+            "file": relative_path(&self.file_path, root_path),
+            "line": self.line,
+            "fixtures": entries,
+        })
+    }
+}
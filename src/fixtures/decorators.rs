@@ -3,7 +3,7 @@
 //! This module contains shared logic for recognizing and extracting information
 //! from pytest decorators like @pytest.fixture, @pytest.mark.usefixtures, etc.
 
-use rustpython_parser::ast::Expr;
+use rustpython_parser::ast::{Expr, Ranged};
 
 /// Check if an expression is a @pytest.fixture or @pytest_asyncio.fixture decorator
 pub fn is_fixture_decorator(expr: &Expr) -> bool {
@@ -41,6 +41,25 @@ pub fn extract_fixture_name_from_decorator(expr: &Expr) -> Option<String> {
         })
 }
 
+/// Returns the range of the string literal passed as `name=` to a fixture decorator.
+/// The range includes the surrounding quotes.
+pub fn extract_fixture_name_range(expr: &Expr) -> Option<rustpython_parser::text_size::TextRange> {
+    let Expr::Call(call) = expr else { return None };
+    if !is_fixture_decorator(&call.func) {
+        return None;
+    }
+
+    call.keywords
+        .iter()
+        .filter(|kw| kw.arg.as_ref().is_some_and(|a| a.as_str() == "name"))
+        .find_map(|kw| match &kw.value {
+            Expr::Constant(c) if matches!(c.value, rustpython_parser::ast::Constant::Str(_)) => {
+                Some(c.range)
+            }
+            _ => None,
+        })
+}
+
 /// Checks if an expression is a pytest.mark.* decorator with the given marker name.
 /// This is a helper function to avoid duplicating the decorator matching logic.
 fn is_pytest_mark_decorator(expr: &Expr, marker_name: &str) -> bool {
@@ -127,72 +146,285 @@ pub fn is_parametrize_decorator(expr: &Expr) -> bool {
     is_pytest_mark_decorator(expr, "parametrize")
 }
 
-/// Extracts fixture names from @pytest.mark.parametrize when indirect=True.
-pub fn extract_parametrize_indirect_fixtures(
-    expr: &Expr,
+/// A single parameter set of a @pytest.mark.parametrize call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParametrizeCase {
+    /// Source text of each value, in argname order.
+    pub values: Vec<String>,
+    /// Explicit test id from `ids=[...]` or `pytest.param(..., id="...")`.
+    pub id: Option<String>,
+}
+
+/// Argnames and parameter sets extracted from a @pytest.mark.parametrize call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParametrizeInfo {
+    /// All argnames, in declaration order.
+    pub argnames: Vec<String>,
+    /// Argnames routed through a fixture via `indirect=`.
+    pub indirect: Vec<String>,
+    /// Parameter sets; empty when `argvalues` is not a literal list/tuple.
+    pub cases: Vec<ParametrizeCase>,
+}
+
+impl ParametrizeInfo {
+    /// Whether `name` is passed directly to the test rather than to a fixture.
+    pub fn is_direct_argname(&self, name: &str) -> bool {
+        self.argnames.iter().any(|a| a == name) && !self.indirect.iter().any(|a| a == name)
+    }
+
+    /// Source text of the value bound to `argname` and the id of each
+    /// parameter set that binds it, so both lists stay aligned.
+    pub fn values_for(&self, argname: &str) -> (Vec<String>, Vec<Option<String>>) {
+        let Some(index) = self.argnames.iter().position(|a| a == argname) else {
+            return (vec![], vec![]);
+        };
+        self.cases
+            .iter()
+            .filter_map(|case| Some((case.values.get(index)?.clone(), case.id.clone())))
+            .unzip()
+    }
+}
+
+/// Returns the value of a call argument given either positionally or by keyword.
+fn call_argument<'a>(
+    call: &'a rustpython_parser::ast::ExprCall,
+    position: usize,
+    keyword: &str,
+) -> Option<&'a Expr> {
+    call.args
+        .get(position)
+        .or_else(|| keyword_argument(call, keyword))
+}
+
+/// Returns the value of a keyword argument.
+fn keyword_argument<'a>(
+    call: &'a rustpython_parser::ast::ExprCall,
+    keyword: &str,
+) -> Option<&'a Expr> {
+    call.keywords
+        .iter()
+        .find(|kw| kw.arg.as_ref().is_some_and(|a| a.as_str() == keyword))
+        .map(|kw| &kw.value)
+}
+
+/// Returns the string value of a string constant expression.
+fn string_constant(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Constant(c) => match &c.value {
+            rustpython_parser::ast::Constant::Str(s) => Some(s.as_str()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Extracts parametrize argnames together with the range to report for each.
+///
+/// Supports the comma-separated string form (`"a, b"`, every name mapped to the
+/// whole string literal) and the list/tuple form (`["a", "b"]`, every name
+/// mapped to its own string literal).
+fn parametrize_argnames(
+    call: &rustpython_parser::ast::ExprCall,
 ) -> Vec<(String, rustpython_parser::text_size::TextRange)> {
+    match call_argument(call, 0, "argnames") {
+        Some(Expr::Constant(c)) => match &c.value {
+            rustpython_parser::ast::Constant::Str(s) => s
+                .split(',')
+                .map(|name| name.trim())
+                .filter(|name| !name.is_empty())
+                .map(|name| (name.to_string(), c.range))
+                .collect(),
+            _ => vec![],
+        },
+        Some(Expr::List(list)) => list
+            .elts
+            .iter()
+            .filter_map(|elt| match elt {
+                Expr::Constant(c) => match &c.value {
+                    rustpython_parser::ast::Constant::Str(s) => Some((s.to_string(), c.range)),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect(),
+        Some(Expr::Tuple(tuple)) => tuple
+            .elts
+            .iter()
+            .filter_map(|elt| match elt {
+                Expr::Constant(c) => match &c.value {
+                    rustpython_parser::ast::Constant::Str(s) => Some((s.to_string(), c.range)),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Returns the source text of an expression, as written.
+fn source_text(expr: &Expr, source: &str) -> String {
+    let range = expr.range();
+    source
+        .get(range.start().to_usize()..range.end().to_usize())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Checks if an expression is a `pytest.param(...)` call.
+fn is_pytest_param_call(expr: &Expr) -> bool {
     let Expr::Call(call) = expr else {
-        return vec![];
+        return false;
+    };
+    match &*call.func {
+        Expr::Name(name) => name.id.as_str() == "param",
+        Expr::Attribute(attr) => {
+            attr.attr.as_str() == "param"
+                && matches!(&*attr.value, Expr::Name(name) if name.id.as_str() == "pytest")
+        }
+        _ => false,
+    }
+}
+
+/// Extracts argnames, indirect names and parameter sets from a
+/// @pytest.mark.parametrize call.  `source` is the file content the expression
+/// was parsed from, used to render values as written.
+pub fn extract_parametrize(expr: &Expr, source: &str) -> Option<ParametrizeInfo> {
+    let Expr::Call(call) = expr else {
+        return None;
     };
     if !is_parametrize_decorator(&call.func) {
-        return vec![];
+        return None;
     }
 
-    let indirect_value = call.keywords.iter().find_map(|kw| {
-        if kw.arg.as_ref().is_some_and(|a| a.as_str() == "indirect") {
-            Some(&kw.value)
-        } else {
-            None
-        }
-    });
+    let argnames: Vec<String> = parametrize_argnames(call)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    if argnames.is_empty() {
+        return None;
+    }
 
-    let Some(indirect) = indirect_value else {
-        return vec![];
+    let indirect = extract_parametrize_indirect_fixtures(expr)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+
+    let text = |e: &Expr| source_text(e, source);
+
+    let elements: &[Expr] = match call_argument(call, 1, "argvalues") {
+        Some(Expr::List(list)) => &list.elts,
+        Some(Expr::Tuple(tuple)) => &tuple.elts,
+        _ => &[],
     };
 
-    let Some(first_arg) = call.args.first() else {
-        return vec![];
+    let mut cases: Vec<ParametrizeCase> = elements
+        .iter()
+        .map(|element| match element {
+            Expr::Call(param) if is_pytest_param_call(element) => ParametrizeCase {
+                values: param.args.iter().map(text).collect(),
+                id: keyword_argument(param, "id")
+                    .and_then(string_constant)
+                    .map(str::to_string),
+            },
+            Expr::Tuple(tuple) if argnames.len() > 1 => ParametrizeCase {
+                values: tuple.elts.iter().map(text).collect(),
+                id: None,
+            },
+            Expr::List(list) if argnames.len() > 1 => ParametrizeCase {
+                values: list.elts.iter().map(text).collect(),
+                id: None,
+            },
+            _ => ParametrizeCase {
+                values: vec![text(element)],
+                id: None,
+            },
+        })
+        .collect();
+
+    // `ids=[...]` applies positionally; `pytest.param(id=...)` takes precedence.
+    let ids: &[Expr] = match keyword_argument(call, "ids") {
+        Some(Expr::List(list)) => &list.elts,
+        Some(Expr::Tuple(tuple)) => &tuple.elts,
+        _ => &[],
     };
+    for (case, id) in cases.iter_mut().zip(ids) {
+        if case.id.is_none() {
+            case.id = string_constant(id).map(str::to_string);
+        }
+    }
 
-    let Expr::Constant(param_const) = first_arg else {
+    Some(ParametrizeInfo {
+        argnames,
+        indirect,
+        cases,
+    })
+}
+
+/// Extracts parametrize information from any expression, including the
+/// list/tuple forms used by `pytestmark = [pytest.mark.parametrize(...), ...]`.
+pub fn extract_parametrize_from_expr(expr: &Expr, source: &str) -> Vec<ParametrizeInfo> {
+    match expr {
+        Expr::Call(_) => extract_parametrize(expr, source).into_iter().collect(),
+        Expr::List(list) => list
+            .elts
+            .iter()
+            .flat_map(|elt| extract_parametrize_from_expr(elt, source))
+            .collect(),
+        Expr::Tuple(tuple) => tuple
+            .elts
+            .iter()
+            .flat_map(|elt| extract_parametrize_from_expr(elt, source))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Extracts fixture names from @pytest.mark.parametrize when indirect=True.
+pub fn extract_parametrize_indirect_fixtures(
+    expr: &Expr,
+) -> Vec<(String, rustpython_parser::text_size::TextRange)> {
+    let Expr::Call(call) = expr else {
         return vec![];
     };
+    if !is_parametrize_decorator(&call.func) {
+        return vec![];
+    }
 
-    let rustpython_parser::ast::Constant::Str(param_str) = &param_const.value else {
+    let Some(indirect) = keyword_argument(call, "indirect") else {
         return vec![];
     };
 
-    let param_names: Vec<&str> = param_str.split(',').map(|s| s.trim()).collect();
+    let param_names = parametrize_argnames(call);
+    if param_names.is_empty() {
+        return vec![];
+    }
 
-    match indirect {
+    let indirect_names: &[Expr] = match indirect {
         Expr::Constant(c) => {
             if matches!(c.value, rustpython_parser::ast::Constant::Bool(true)) {
-                return param_names
-                    .into_iter()
-                    .map(|name| (name.to_string(), param_const.range))
-                    .collect();
+                return param_names;
             }
+            return vec![];
         }
-        Expr::List(list) => {
-            return list
-                .elts
-                .iter()
-                .filter_map(|elt| {
-                    if let Expr::Constant(c) = elt {
-                        if let rustpython_parser::ast::Constant::Str(s) = &c.value {
-                            if param_names.contains(&s.as_str()) {
-                                return Some((s.to_string(), c.range));
-                            }
-                        }
-                    }
-                    None
-                })
-                .collect();
-        }
-        _ => {}
-    }
+        Expr::List(list) => &list.elts,
+        Expr::Tuple(tuple) => &tuple.elts,
+        _ => return vec![],
+    };
 
-    vec![]
+    indirect_names
+        .iter()
+        .filter_map(|elt| {
+            if let Expr::Constant(c) = elt {
+                if let rustpython_parser::ast::Constant::Str(s) = &c.value {
+                    if param_names.iter().any(|(name, _)| name == s.as_str()) {
+                        return Some((s.to_string(), c.range));
+                    }
+                }
+            }
+            None
+        })
+        .collect()
 }
 
 /// Extracts whether autouse=True is set on a @pytest.fixture decorator.
@@ -228,3 +460,61 @@ pub fn extract_fixture_scope(expr: &Expr) -> Option<super::types::FixtureScope>
             _ => None,
         })
 }
+
+/// Extracts `params=` and `ids=` from a @pytest.fixture(...) decorator.
+/// Returns the source text of each param and its explicit id; both are empty
+/// when `params` is missing or not a literal list/tuple.
+pub fn extract_fixture_params(expr: &Expr, source: &str) -> (Vec<String>, Vec<Option<String>>) {
+    let Expr::Call(call) = expr else {
+        return (vec![], vec![]);
+    };
+    if !is_fixture_decorator(&call.func) {
+        return (vec![], vec![]);
+    }
+
+    let elements: &[Expr] = match keyword_argument(call, "params") {
+        Some(Expr::List(list)) => &list.elts,
+        Some(Expr::Tuple(tuple)) => &tuple.elts,
+        _ => return (vec![], vec![]),
+    };
+
+    let mut params = Vec::with_capacity(elements.len());
+    let mut ids = Vec::with_capacity(elements.len());
+    for element in elements {
+        match element {
+            Expr::Call(param) if is_pytest_param_call(element) => {
+                params.push(
+                    param
+                        .args
+                        .iter()
+                        .map(|arg| source_text(arg, source))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                );
+                ids.push(
+                    keyword_argument(param, "id")
+                        .and_then(string_constant)
+                        .map(str::to_string),
+                );
+            }
+            _ => {
+                params.push(source_text(element, source));
+                ids.push(None);
+            }
+        }
+    }
+
+    // `ids=[...]` applies positionally; `pytest.param(id=...)` takes precedence.
+    let explicit_ids: &[Expr] = match keyword_argument(call, "ids") {
+        Some(Expr::List(list)) => &list.elts,
+        Some(Expr::Tuple(tuple)) => &tuple.elts,
+        _ => &[],
+    };
+    for (id, explicit) in ids.iter_mut().zip(explicit_ids) {
+        if id.is_none() {
+            *id = string_constant(explicit).map(str::to_string);
+        }
+    }
+
+    (params, ids)
+}
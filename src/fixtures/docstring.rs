@@ -44,44 +44,29 @@ impl FixtureDatabase {
     #[allow(clippy::only_used_in_recursion)]
     pub(crate) fn contains_yield(&self, body: &[Stmt]) -> bool {
         for stmt in body {
-            match stmt {
+            let found = match stmt {
                 Stmt::Expr(expr_stmt) => {
-                    if let Expr::Yield(_) | Expr::YieldFrom(_) = &*expr_stmt.value {
-                        return true;
-                    }
+                    matches!(&*expr_stmt.value, Expr::Yield(_) | Expr::YieldFrom(_))
                 }
                 Stmt::If(if_stmt) => {
-                    if self.contains_yield(&if_stmt.body) || self.contains_yield(&if_stmt.orelse) {
-                        return true;
-                    }
+                    self.contains_yield(&if_stmt.body) || self.contains_yield(&if_stmt.orelse)
                 }
                 Stmt::For(for_stmt) => {
-                    if self.contains_yield(&for_stmt.body) || self.contains_yield(&for_stmt.orelse)
-                    {
-                        return true;
-                    }
+                    self.contains_yield(&for_stmt.body) || self.contains_yield(&for_stmt.orelse)
                 }
                 Stmt::While(while_stmt) => {
-                    if self.contains_yield(&while_stmt.body)
-                        || self.contains_yield(&while_stmt.orelse)
-                    {
-                        return true;
-                    }
-                }
-                Stmt::With(with_stmt) => {
-                    if self.contains_yield(&with_stmt.body) {
-                        return true;
-                    }
+                    self.contains_yield(&while_stmt.body) || self.contains_yield(&while_stmt.orelse)
                 }
+                Stmt::With(with_stmt) => self.contains_yield(&with_stmt.body),
                 Stmt::Try(try_stmt) => {
-                    if self.contains_yield(&try_stmt.body)
+                    self.contains_yield(&try_stmt.body)
                         || self.contains_yield(&try_stmt.orelse)
                         || self.contains_yield(&try_stmt.finalbody)
-                    {
-                        return true;
-                    }
                 }
-                _ => {}
+                _ => false,
+            };
+            if found {
+                return true;
             }
         }
         false
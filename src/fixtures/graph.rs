@@ -0,0 +1,345 @@
+//! Fixture dependency graph export.
+//!
+//! Builds the resolved dependency graph with one node per fixture
+//! *definition* (so overrides stay distinct) and renders it as Graphviz DOT,
+//! Mermaid or JSON.
+
+use super::types::{FixtureDefinition, FixtureScope};
+use super::FixtureDatabase;
+use crate::report::relative_path;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// A node of the fixture graph.
+#[derive(Debug, Clone)]
+pub struct FixtureGraphNode {
+    pub name: String,
+    /// The resolved definition, or `None` if the dependency could not be
+    /// resolved (e.g. pytest built-ins such as `tmp_path`).
+    pub definition: Option<FixtureDefinition>,
+}
+
+/// A "depends on" edge between two nodes (indices into `FixtureGraph::nodes`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixtureGraphEdge {
+    pub from: usize,
+    pub to: usize,
+}
+
+/// The resolved fixture dependency graph.
+#[derive(Debug, Clone, Default)]
+pub struct FixtureGraph {
+    pub nodes: Vec<FixtureGraphNode>,
+    pub edges: Vec<FixtureGraphEdge>,
+}
+
+#[derive(Hash, PartialEq, Eq)]
+enum NodeKey {
+    Definition(PathBuf, usize, String),
+    Unresolved(String),
+}
+
+impl FixtureDatabase {
+    /// Build the fixture dependency graph.
+    ///
+    /// Starts from every definition named `root`, or from all non-third-party
+    /// definitions when `root` is `None`, and follows resolved dependencies up
+    /// to `max_depth` edges away from the starting nodes.
+    pub fn build_fixture_graph(
+        &self,
+        root: Option<&str>,
+        max_depth: Option<usize>,
+    ) -> FixtureGraph {
+        let mut starts: Vec<FixtureDefinition> = match root {
+            Some(name) => self
+                .definitions
+                .get(name)
+                .map(|defs| defs.value().clone())
+                .unwrap_or_default(),
+            None => self
+                .definitions
+                .iter()
+                .flat_map(|entry| entry.value().clone())
+                .filter(|def| !def.is_third_party)
+                .collect(),
+        };
+        starts
+            .sort_by(|a, b| (&a.file_path, a.line, &a.name).cmp(&(&b.file_path, b.line, &b.name)));
+
+        let mut graph = FixtureGraph::default();
+        let mut indices: HashMap<NodeKey, usize> = HashMap::new();
+        let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
+
+        for def in starts {
+            let (index, _) = graph.add_node(&mut indices, def.name.clone(), Some(def));
+            queue.push_back((index, 0));
+        }
+
+        while let Some((index, depth)) = queue.pop_front() {
+            if max_depth.is_some_and(|max| depth >= max) {
+                continue;
+            }
+            let Some(def) = graph.nodes[index].definition.clone() else {
+                continue;
+            };
+
+            for dep_name in &def.dependencies {
+                // A fixture requesting its own name gets the definition it overrides
+                let resolved = if *dep_name == def.name {
+                    self.find_closest_definition_excluding(&def.file_path, dep_name, Some(&def))
+                } else {
+                    self.find_closest_definition_at_line(&def.file_path, dep_name, def.line)
+                };
+
+                let (dep_index, is_new) = graph.add_node(&mut indices, dep_name.clone(), resolved);
+                graph.edges.push(FixtureGraphEdge {
+                    from: index,
+                    to: dep_index,
+                });
+                if is_new {
+                    queue.push_back((dep_index, depth + 1));
+                }
+            }
+        }
+
+        graph
+    }
+}
+
+impl FixtureGraph {
+    /// Return the index of the node for `definition` (or the unresolved
+    /// `name`), adding it if needed. The flag is `true` for new nodes.
+    fn add_node(
+        &mut self,
+        indices: &mut HashMap<NodeKey, usize>,
+        name: String,
+        definition: Option<FixtureDefinition>,
+    ) -> (usize, bool) {
+        let key = match &definition {
+            Some(def) => NodeKey::Definition(def.file_path.clone(), def.line, def.name.clone()),
+            None => NodeKey::Unresolved(name.clone()),
+        };
+        if let Some(&index) = indices.get(&key) {
+            return (index, false);
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(FixtureGraphNode { name, definition });
+        indices.insert(key, index);
+        (index, true)
+    }
+
+    /// Render as a Graphviz DOT digraph. Paths are shown relative to `root_path`.
+    pub fn to_dot(&self, root_path: &Path, color_scopes: bool) -> String {
+        let mut out = String::from("digraph fixtures {\n");
+        out.push_str("    rankdir=LR;\n");
+        out.push_str("    node [shape=box, fontname=\"Helvetica\"];\n");
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            let label = node_label(node, root_path, "\\n");
+            let mut attrs = vec![format!("label=\"{}\"", escape_dot(&label))];
+            match &node.definition {
+                Some(def) if color_scopes => {
+                    attrs.push("style=filled".to_string());
+                    attrs.push(format!("fillcolor=\"{}\"", scope_color(def.scope)));
+                }
+                Some(_) => {}
+                None => attrs.push("style=dashed".to_string()),
+            }
+            out.push_str(&format!("    n{} [{}];\n", index, attrs.join(", ")));
+        }
+
+        for edge in &self.edges {
+            out.push_str(&format!("    n{} -> n{};\n", edge.from, edge.to));
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    /// Render as a Mermaid flowchart. Paths are shown relative to `root_path`.
+    pub fn to_mermaid(&self, root_path: &Path, color_scopes: bool) -> String {
+        let mut out = String::from("flowchart LR\n");
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            let label = escape_mermaid(&node_label(node, root_path, "<br/>"));
+            out.push_str(&format!("    n{}[\"{}\"]\n", index, label));
+        }
+
+        for edge in &self.edges {
+            out.push_str(&format!("    n{} --> n{}\n", edge.from, edge.to));
+        }
+
+        let unresolved: Vec<String> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.definition.is_none())
+            .map(|(index, _)| format!("n{}", index))
+            .collect();
+        if !unresolved.is_empty() {
+            out.push_str("    classDef unresolved stroke-dasharray: 5 5\n");
+            out.push_str(&format!("    class {} unresolved\n", unresolved.join(",")));
+        }
+
+        if color_scopes {
+            for scope in [
+                FixtureScope::Function,
+                FixtureScope::Class,
+                FixtureScope::Module,
+                FixtureScope::Package,
+                FixtureScope::Session,
+            ] {
+                let members: Vec<String> = self
+                    .nodes
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, node)| node.definition.as_ref().is_some_and(|d| d.scope == scope))
+                    .map(|(index, _)| format!("n{}", index))
+                    .collect();
+                if members.is_empty() {
+                    continue;
+                }
+                out.push_str(&format!(
+                    "    classDef {} fill:{}\n",
+                    scope.as_str(),
+                    scope_color(scope)
+                ));
+                out.push_str(&format!(
+                    "    class {} {}\n",
+                    members.join(","),
+                    scope.as_str()
+                ));
+            }
+        }
+
+        out
+    }
+
+    /// Render as JSON (`{"nodes": [...], "edges": [...]}`). Paths are relative to `root_path`.
+    pub fn to_json(&self, root_path: &Path) -> serde_json::Value {
+        let nodes: Vec<serde_json::Value> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| match &node.definition {
+                Some(def) => serde_json::json!({
+                    "id": format!("n{}", index),
+                    "name": node.name,
+                    "file": relative_path(&def.file_path, root_path),
+                    "line": def.line,
+                    "scope": def.scope.as_str(),
+                    "autouse": def.autouse,
+                    "third_party": def.is_third_party,
+                }),
+                None => serde_json::json!({
+                    "id": format!("n{}", index),
+                    "name": node.name,
+                    "file": null,
+                    "line": null,
+                    "scope": null,
+                    "autouse": false,
+                    "third_party": false,
+                }),
+            })
+            .collect();
+
+        let edges: Vec<serde_json::Value> = self
+            .edges
+            .iter()
+            .map(|edge| {
+                serde_json::json!({
+                    "from": format!("n{}", edge.from),
+                    "to": format!("n{}", edge.to),
+                })
+            })
+            .collect();
+
+        serde_json::json!({ "nodes": nodes, "edges": edges })
+    }
+}
+
+/// Node label: name (plus scope when not function-scoped) and location.
+fn node_label(node: &FixtureGraphNode, root_path: &Path, line_break: &str) -> String {
+    match &node.definition {
+        Some(def) => {
+            let name = if def.scope == FixtureScope::Function {
+                node.name.clone()
+            } else {
+                format!("{} ({})", node.name, def.scope.as_str())
+            };
+            format!(
+                "{}{}{}:{}",
+                name,
+                line_break,
+                relative_path(&def.file_path, root_path),
+                def.line
+            )
+        }
+        None => node.name.clone(),
+    }
+}
+
+fn scope_color(scope: FixtureScope) -> &'static str {
+    match scope {
+        FixtureScope::Function => "#cfe2f3",
+        FixtureScope::Class => "#d9ead3",
+        FixtureScope::Module => "#fff2cc",
+        FixtureScope::Package => "#fce5cd",
+        FixtureScope::Session => "#f4cccc",
+    }
+}
+
+/// Escape a DOT string literal. `\n` line breaks in the label are kept.
+fn escape_dot(label: &str) -> String {
+    label.replace('"', "\\\"")
+}
+
+fn escape_mermaid(label: &str) -> String {
+    label.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph() -> FixtureGraph {
+        let def = FixtureDefinition {
+            name: "db".to_string(),
+            file_path: PathBuf::from("/project/tests/conftest.py"),
+            line: 4,
+            scope: FixtureScope::Session,
+            dependencies: vec!["tmp_path_factory".to_string()],
+            ..Default::default()
+        };
+        FixtureGraph {
+            nodes: vec![
+                FixtureGraphNode {
+                    name: "db".to_string(),
+                    definition: Some(def),
+                },
+                FixtureGraphNode {
+                    name: "tmp_path_factory".to_string(),
+                    definition: None,
+                },
+            ],
+            edges: vec![FixtureGraphEdge { from: 0, to: 1 }],
+        }
+    }
+
+    #[test]
+    fn test_dot_output() {
+        assert_eq!(
+            graph().to_dot(Path::new("/project"), true),
+            "digraph fixtures {\n    rankdir=LR;\n    node [shape=box, fontname=\"Helvetica\"];\n    n0 [label=\"db (session)\\ntests/conftest.py:4\", style=filled, fillcolor=\"#f4cccc\"];\n    n1 [label=\"tmp_path_factory\", style=dashed];\n    n0 -> n1;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_mermaid_output() {
+        assert_eq!(
+            graph().to_mermaid(Path::new("/project"), true),
+            "flowchart LR\n    n0[\"db (session)<br/>tests/conftest.py:4\"]\n    n1[\"tmp_path_factory\"]\n    n0 --> n1\n    classDef unresolved stroke-dasharray: 5 5\n    class n1 unresolved\n    classDef session fill:#f4cccc\n    class n0 session\n"
+        );
+    }
+}
@@ -313,6 +313,99 @@ impl FixtureDatabase {
         }
     }
 
+    /// Resolve a (possibly dotted) name used in `file_path` through the file's
+    /// module-level imports. Returns the file of the module it was imported
+    /// from and its name there, e.g. `Base` with `from .base import Base as B`
+    /// used as `B`, or `base.Base` with `import base`.
+    pub(crate) fn resolve_imported_name(
+        &self,
+        file_path: &Path,
+        name: &str,
+    ) -> Option<(PathBuf, String)> {
+        let content = self.get_file_content(file_path)?;
+        let parsed = self.get_parsed_ast(file_path, &content)?;
+        let rustpython_parser::ast::Mod::Module(module) = parsed.as_ref() else {
+            return None;
+        };
+
+        let (prefix, target) = match name.rsplit_once('.') {
+            Some((prefix, target)) => (Some(prefix), target),
+            None => (None, name),
+        };
+        let (head, rest) = match name.split_once('.') {
+            Some((head, rest)) => (head, rest.rsplit_once('.').map(|(rest, _)| rest)),
+            None => (name, None),
+        };
+        let with_rest = |module: String| match rest {
+            Some(rest) => format!("{}.{}", module, rest),
+            None => module,
+        };
+
+        // The last matching import wins, as it does at runtime.
+        let mut imported = None;
+        for stmt in &module.body {
+            match stmt {
+                Stmt::Import(import_stmt) => {
+                    let Some(prefix) = prefix else { continue };
+                    for alias in &import_stmt.names {
+                        let module_name = alias.name.to_string();
+                        match &alias.asname {
+                            Some(asname) if asname.as_str() == head => {
+                                imported = Some((with_rest(module_name), target.to_string()));
+                            }
+                            None if module_name.split('.').next() == Some(head) => {
+                                imported = Some((prefix.to_string(), target.to_string()));
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Stmt::ImportFrom(import_from) => {
+                    let level = import_from
+                        .level
+                        .as_ref()
+                        .map(|l| l.to_usize())
+                        .unwrap_or(0);
+                    let from_module = format!(
+                        "{}{}",
+                        ".".repeat(level),
+                        import_from
+                            .module
+                            .as_ref()
+                            .map(|m| m.to_string())
+                            .unwrap_or_default()
+                    );
+                    for alias in &import_from.names {
+                        let bound = alias.asname.as_ref().unwrap_or(&alias.name);
+                        if bound.as_str() != head {
+                            continue;
+                        }
+                        imported = Some(match prefix {
+                            // `from X import Name as Alias` binds the alias to `Name` in X.
+                            None => (from_module.clone(), alias.name.to_string()),
+                            // `from X import mod` followed by `mod.Name`.
+                            Some(_) => {
+                                let separator = if from_module.ends_with('.') { "" } else { "." };
+                                (
+                                    with_rest(format!(
+                                        "{}{}{}",
+                                        from_module, separator, alias.name
+                                    )),
+                                    target.to_string(),
+                                )
+                            }
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let (module_path, target) = imported?;
+        let resolved = self.resolve_module_to_file(&module_path, file_path)?;
+        Some((self.get_canonical_path(resolved), target))
+    }
+
     /// Resolve a relative import like `.pytest_fixtures` or `..utils`.
     fn resolve_relative_import(&self, module_path: &str, base_dir: &Path) -> Option<PathBuf> {
         let mut current_dir = base_dir.to_path_buf();
@@ -0,0 +1,445 @@
+//! Persistent on-disk index of per-file analysis results.
+//!
+//! Re-parsing every conftest, test module and site-packages plugin on each
+//! server start is slow in large repositories. After a workspace scan the
+//! analyzed definitions, usages, imports and import-scan references of every
+//! scanned file are written to an index file; on the next start a file whose
+//! content hash is unchanged is restored from the index instead of being
+//! parsed again, unless the `__init__.py` files that make up its package have
+//! been added or removed (type imports of fixtures are spelled with the
+//! module path).
+//!
+//! The whole index is discarded when the server version, the pytest
+//! collection options or `fixture_paths` change, since all affect analysis
+//! results. Hashes use FNV-1a, so they stay valid across Rust toolchains.
+
+use super::types::{
+    ClassDefinition, FixtureDefinition, FixtureUsage, ParametrizedArgument, UndeclaredFixture,
+};
+use super::FixtureDatabase;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// Bumped whenever the layout of [`CachedFile`] changes.
+const INDEX_FORMAT_VERSION: u32 = 7;
+
+/// Index file name inside the cache directory.
+const INDEX_FILE_NAME: &str = "index.json";
+
+/// Modules a file pulls fixtures from, as used by the import scan:
+/// `from X import ...` (with whether it is a star import) and `pytest_plugins`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct ModuleReferences {
+    pub(crate) imports: Vec<(String, bool)>,
+    pub(crate) pytest_plugins: Vec<String>,
+}
+
+/// Everything the analyzer records for a single file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct FileAnalysis {
+    definitions: Vec<FixtureDefinition>,
+    usages: Vec<FixtureUsage>,
+    parametrized_args: Vec<ParametrizedArgument>,
+    classes: Vec<ClassDefinition>,
+    undeclared_fixtures: Vec<UndeclaredFixture>,
+    imports: HashSet<String>,
+    /// Filled in lazily by the import scan.
+    module_references: Option<ModuleReferences>,
+}
+
+/// A cached file, keyed by its canonical path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CachedFile {
+    content_hash: u64,
+    /// Dotted module path of the file when it was analyzed; it changes when an
+    /// `__init__.py` above the file is added or removed.
+    module_path: Option<String>,
+    analysis: FileAnalysis,
+    /// Whether the file was scanned in this session; only those entries are saved.
+    #[serde(skip)]
+    scanned: bool,
+}
+
+impl CachedFile {
+    /// Only the content decides freshness; size and mtime can stay the same
+    /// across an edit.
+    fn is_fresh(&self, content_hash: u64, module_path: &Option<String>) -> bool {
+        self.content_hash == content_hash && &self.module_path == module_path
+    }
+}
+
+/// On-disk layout of the index file.
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexFile {
+    server_version: String,
+    format_version: u32,
+    /// Hash of the pytest collection options the files were analyzed with.
+    options_fingerprint: u64,
+    files: HashMap<PathBuf, CachedFile>,
+}
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher`, its output is fixed, so hashes
+/// written by one build can be compared by another.
+fn stable_hash(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// Default cache directory for a workspace: `.pytest_cache/pytest-language-server`,
+/// next to pytest's own cache so it is ignored by version control.
+pub fn default_cache_dir(workspace_root: &Path) -> PathBuf {
+    workspace_root
+        .join(".pytest_cache")
+        .join("pytest-language-server")
+}
+
+impl FixtureDatabase {
+    /// Enable the persistent index stored in `cache_dir`, loading any entries
+    /// written by a previous session. Should be called before scanning.
+    pub fn enable_index_cache(&self, cache_dir: &Path) {
+        let index_path = cache_dir.join(INDEX_FILE_NAME);
+        *self.index_cache_file.lock().unwrap() = Some(index_path.clone());
+        self.index_cache.clear();
+
+        let content = match std::fs::read_to_string(&index_path) {
+            Ok(content) => content,
+            Err(_) => {
+                debug!("No fixture index at {:?}, starting cold", index_path);
+                return;
+            }
+        };
+
+        let index: IndexFile = match serde_json::from_str(&content) {
+            Ok(index) => index,
+            Err(e) => {
+                warn!("Ignoring unreadable fixture index {:?}: {}", index_path, e);
+                return;
+            }
+        };
+
+        if index.server_version != env!("CARGO_PKG_VERSION")
+            || index.format_version != INDEX_FORMAT_VERSION
+            || index.options_fingerprint != self.options_fingerprint(&index_path)
+        {
+            info!(
+                "Discarding fixture index {:?} written by another server version or pytest configuration",
+                index_path
+            );
+            return;
+        }
+
+        info!(
+            "Loaded fixture index with {} files from {:?}",
+            index.files.len(),
+            index_path
+        );
+        for (path, entry) in index.files {
+            self.index_cache.insert(path, entry);
+        }
+    }
+
+    /// Stop reading and writing the persistent index.
+    pub fn disable_index_cache(&self) {
+        *self.index_cache_file.lock().unwrap() = None;
+        self.index_cache.clear();
+    }
+
+    /// Write the index for all files scanned in this session.
+    /// Does nothing unless [`enable_index_cache`](Self::enable_index_cache) was called.
+    pub fn save_index_cache(&self) {
+        let Some(index_path) = self.index_cache_file.lock().unwrap().clone() else {
+            return;
+        };
+
+        // Drop entries for files that no longer exist or were not scanned
+        self.index_cache.retain(|_, entry| entry.scanned);
+
+        let index = IndexFile {
+            server_version: env!("CARGO_PKG_VERSION").to_string(),
+            format_version: INDEX_FORMAT_VERSION,
+            options_fingerprint: self.options_fingerprint(&index_path),
+            files: self
+                .index_cache
+                .iter()
+                .map(|entry| (entry.key().clone(), entry.value().clone()))
+                .collect(),
+        };
+
+        if let Err(e) = write_index(&index_path, &index) {
+            warn!("Failed to write fixture index {:?}: {}", index_path, e);
+            return;
+        }
+        info!(
+            "Saved fixture index with {} files to {:?}",
+            index.files.len(),
+            index_path
+        );
+    }
+
+    /// Fingerprint of the pytest options and `fixture_paths` of the workspace
+    /// root holding the index.
+    fn options_fingerprint(&self, index_path: &Path) -> u64 {
+        let fixture_paths = self
+            .root_fixture_paths
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(root, _)| index_path.starts_with(root))
+            .max_by_key(|(root, _)| root.components().count())
+            .map(|(_, dirs)| dirs.clone())
+            .unwrap_or_default();
+        let options = format!(
+            "{:?}\n{:?}",
+            self.pytest_options_for(index_path),
+            fixture_paths
+        );
+        stable_hash(options.as_bytes())
+    }
+
+    /// Analyze a file read from disk during a scan, restoring it from the
+    /// persistent index when the cached entry is still fresh.
+    ///
+    /// `fresh` selects [`analyze_file_fresh`](Self::analyze_file_fresh) over
+    /// [`analyze_file`](Self::analyze_file) on a cache miss.
+    pub(crate) fn analyze_scanned_file(&self, file_path: PathBuf, content: &str, fresh: bool) {
+        let analyze = |path: PathBuf| {
+            if fresh {
+                self.analyze_file_fresh(path, content);
+            } else {
+                self.analyze_file(path, content);
+            }
+        };
+
+        if self.index_cache_file.lock().unwrap().is_none() {
+            analyze(file_path);
+            return;
+        }
+
+        let file_path = self.get_canonical_path(file_path);
+        let content_hash = stable_hash(content.as_bytes());
+        let module_path = Self::file_path_to_module_path(&file_path);
+
+        // Only restore into an empty slot; re-analysis needs the analyzer's cleanup
+        let restorable = !self.file_cache.contains_key(&file_path)
+            && self
+                .index_cache
+                .get(&file_path)
+                .is_some_and(|entry| entry.is_fresh(content_hash, &module_path));
+        if restorable {
+            let analysis = self.index_cache.get_mut(&file_path).map(|mut entry| {
+                entry.scanned = true;
+                entry.analysis.clone()
+            });
+            if let Some(analysis) = analysis {
+                debug!("Restoring {:?} from fixture index", file_path);
+                self.restore_analysis(&file_path, content, analysis);
+                return;
+            }
+        }
+
+        analyze(file_path.clone());
+        let analysis = self.capture_analysis(&file_path);
+        self.index_cache.insert(
+            file_path,
+            CachedFile {
+                content_hash,
+                module_path,
+                analysis,
+                scanned: true,
+            },
+        );
+    }
+
+    /// Record a cached analysis as if the file had just been analyzed.
+    fn restore_analysis(&self, file_path: &Path, content: &str, analysis: FileAnalysis) {
+        self.file_cache
+            .insert(file_path.to_path_buf(), Arc::new(content.to_string()));
+        self.imports
+            .insert(file_path.to_path_buf(), analysis.imports);
+
+        // Third-party and plugin status depend on the current environment, not the file
+        let (is_third_party, is_plugin, fixture_path) = self.definition_origin(file_path);
+        for mut definition in analysis.definitions {
+            definition.is_third_party = is_third_party;
+            definition.is_plugin = is_plugin;
+            definition.fixture_path = fixture_path.clone();
+            self.record_fixture_definition(definition);
+        }
+
+        for usage in &analysis.usages {
+            self.usage_by_fixture
+                .entry(usage.name.clone())
+                .or_default()
+                .push((file_path.to_path_buf(), usage.clone()));
+        }
+        if !analysis.usages.is_empty() {
+            self.usages.insert(file_path.to_path_buf(), analysis.usages);
+        }
+        if !analysis.parametrized_args.is_empty() {
+            self.parametrized_args
+                .insert(file_path.to_path_buf(), analysis.parametrized_args);
+        }
+        if !analysis.classes.is_empty() {
+            self.classes
+                .insert(file_path.to_path_buf(), analysis.classes);
+        }
+        if !analysis.undeclared_fixtures.is_empty() {
+            self.undeclared_fixtures
+                .insert(file_path.to_path_buf(), analysis.undeclared_fixtures);
+        }
+    }
+
+    /// Collect what the analyzer recorded for a file.
+    fn capture_analysis(&self, file_path: &Path) -> FileAnalysis {
+        let definitions = self
+            .file_definitions
+            .get(file_path)
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|name| self.definitions.get(name))
+                    .flat_map(|defs| {
+                        defs.iter()
+                            .filter(|def| def.file_path == file_path)
+                            .cloned()
+                            .collect::<Vec<_>>()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        FileAnalysis {
+            definitions,
+            usages: self
+                .usages
+                .get(file_path)
+                .map(|u| u.clone())
+                .unwrap_or_default(),
+            parametrized_args: self
+                .parametrized_args
+                .get(file_path)
+                .map(|a| a.clone())
+                .unwrap_or_default(),
+            classes: self
+                .classes
+                .get(file_path)
+                .map(|c| c.clone())
+                .unwrap_or_default(),
+            undeclared_fixtures: self
+                .undeclared_fixtures
+                .get(file_path)
+                .map(|u| u.clone())
+                .unwrap_or_default(),
+            imports: self
+                .imports
+                .get(file_path)
+                .map(|i| i.clone())
+                .unwrap_or_default(),
+            module_references: None,
+        }
+    }
+
+    /// Modules a file imports fixtures from, for the import scan.
+    ///
+    /// Served from the persistent index when available; otherwise the file is
+    /// parsed and the result is stored in its index entry.
+    pub(crate) fn fixture_module_references(
+        &self,
+        file_path: &Path,
+        content: &str,
+    ) -> Option<ModuleReferences> {
+        let content_hash = stable_hash(content.as_bytes());
+        if let Some(references) = self.index_cache.get(file_path).and_then(|entry| {
+            (entry.content_hash == content_hash)
+                .then(|| entry.analysis.module_references.clone())
+                .flatten()
+        }) {
+            return Some(references);
+        }
+
+        let parsed = self.get_parsed_ast(file_path, content)?;
+        let line_index = self.get_line_index(file_path, content);
+        let rustpython_parser::ast::Mod::Module(module) = parsed.as_ref() else {
+            return None;
+        };
+
+        let references = ModuleReferences {
+            imports: self
+                .extract_fixture_imports(&module.body, file_path, &line_index)
+                .into_iter()
+                .map(|import| (import.module_path, import.is_star_import))
+                .collect(),
+            pytest_plugins: self.extract_pytest_plugins(&module.body),
+        };
+
+        if let Some(mut entry) = self.index_cache.get_mut(file_path) {
+            if entry.content_hash == content_hash {
+                entry.analysis.module_references = Some(references.clone());
+            }
+        }
+        Some(references)
+    }
+}
+
+/// Write the index atomically (temporary file + rename).
+fn write_index(index_path: &Path, index: &IndexFile) -> std::io::Result<()> {
+    let Some(cache_dir) = index_path.parent() else {
+        return Ok(());
+    };
+
+    // Like pytest, keep a freshly created `.pytest_cache` out of version control
+    if let Some(pytest_cache) = cache_dir
+        .parent()
+        .filter(|dir| dir.file_name().is_some_and(|n| n == ".pytest_cache"))
+    {
+        if !pytest_cache.exists() {
+            std::fs::create_dir_all(pytest_cache)?;
+            std::fs::write(
+                pytest_cache.join(".gitignore"),
+                "# Created by pytest-language-server automatically.\n*\n",
+            )?;
+        }
+    }
+    std::fs::create_dir_all(cache_dir)?;
+
+    let tmp_path = index_path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, serde_json::to_vec(index)?)?;
+    std::fs::rename(&tmp_path, index_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cached(content_hash: u64, module_path: Option<&str>) -> CachedFile {
+        CachedFile {
+            content_hash,
+            module_path: module_path.map(str::to_string),
+            analysis: FileAnalysis::default(),
+            scanned: false,
+        }
+    }
+
+    #[test]
+    fn test_freshness() {
+        let entry = cached(42, Some("pkg.conftest"));
+        assert!(entry.is_fresh(42, &Some("pkg.conftest".to_string())));
+        // Edited content, even with the same size and mtime
+        assert!(!entry.is_fresh(43, &Some("pkg.conftest".to_string())));
+        // `pkg/__init__.py` was removed
+        assert!(!entry.is_fresh(42, &Some("conftest".to_string())));
+        assert!(cached(42, None).is_fresh(42, &None));
+    }
+
+    #[test]
+    fn test_stable_hash_is_fixed() {
+        assert_eq!(stable_hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(stable_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
@@ -7,30 +7,42 @@
 //! - Providing completion context for fixture suggestions
 
 mod analyzer;
+pub mod autouse;
 pub(crate) mod cli;
+pub mod closure;
 pub mod decorators; // Public for testing
 mod docstring;
+pub mod graph;
 pub mod import_analysis;
 mod imports;
+mod index_cache;
+mod rename;
 mod resolver;
 mod scanner;
+pub mod signature;
 pub(crate) mod string_utils; // pub(crate) for inlay_hint provider access
+mod text_sync;
 pub mod types;
 mod undeclared;
+pub mod variants;
+pub mod venv;
 
+pub use index_cache::default_cache_dir;
 #[allow(unused_imports)] // ParamInsertionInfo re-exported for public API via lib.rs
 pub use types::{
-    CompletionContext, FixtureCycle, FixtureDefinition, FixtureScope, FixtureUsage,
-    ParamInsertionInfo, ScopeMismatch, TypeImportSpec, UndeclaredFixture,
+    ClassDefinition, CompletionContext, ContentChange, DocumentPosition, FixtureCycle,
+    FixtureDefinition, FixtureOverride, FixtureScope, FixtureUsage, ParamInsertionInfo,
+    ParametrizedArgument, RenameLocation, ScopeMismatch, TypeImportSpec, UndeclaredFixture,
 };
 
+use crate::config::PytestIniOptions;
 use dashmap::DashMap;
 use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tracing::debug;
+use tracing::{debug, info};
 
 /// An editable install discovered via `direct_url.json` + `.pth` files in site-packages.
 #[derive(Debug, Clone)]
@@ -50,6 +62,9 @@ type LineIndexCacheEntry = (u64, Arc<Vec<usize>>);
 /// The content hash is used to invalidate the cache when file content changes.
 type AstCacheEntry = (u64, Arc<rustpython_parser::ast::Mod>);
 
+/// `fixture_paths` directories of a workspace root: (canonical_root, directories).
+type RootFixturePathsEntry = (PathBuf, Vec<PathBuf>);
+
 /// Cache entry for fixture cycles: (definitions_version, cycles).
 /// The version is incremented when definitions change to invalidate the cache.
 type CycleCacheEntry = (u64, Arc<Vec<types::FixtureCycle>>);
@@ -95,6 +110,12 @@ pub struct FixtureDatabase {
     pub file_definitions: Arc<DashMap<PathBuf, HashSet<String>>>,
     /// Map from file path to fixtures used in that file.
     pub usages: Arc<DashMap<PathBuf, Vec<FixtureUsage>>>,
+    /// Map from file path to test parameters supplied by `@pytest.mark.parametrize`.
+    /// These are not fixture requests and are kept separate from `usages`.
+    pub parametrized_args: Arc<DashMap<PathBuf, Vec<ParametrizedArgument>>>,
+    /// Map from file path to the classes defined in that file, used to scope
+    /// fixtures defined as class methods.
+    pub classes: Arc<DashMap<PathBuf, Vec<ClassDefinition>>>,
     /// Reverse index: fixture name -> (file_path, usage) pairs.
     /// Used for efficient O(1) lookup in find_references_for_definition.
     pub usage_by_fixture: Arc<DashMap<String, Vec<(PathBuf, FixtureUsage)>>>,
@@ -131,6 +152,8 @@ pub struct FixtureDatabase {
     pub editable_install_roots: Arc<std::sync::Mutex<Vec<EditableInstall>>>,
     /// Workspace root path, set during scan. Used to distinguish in-workspace editables.
     pub workspace_root: Arc<std::sync::Mutex<Option<PathBuf>>>,
+    /// Every workspace root scanned so far (multi-root workspaces), in scan order.
+    pub workspace_roots: Arc<std::sync::Mutex<Vec<PathBuf>>>,
     /// Files discovered via pytest11 entry point plugins.
     /// Used to mark fixtures from these files as `is_plugin` so the resolver
     /// can find them even when they are not in conftest.py or site-packages.
@@ -141,6 +164,29 @@ pub struct FixtureDatabase {
     ///
     /// Bounded implicitly: see [`NameImportMapCacheEntry`] for the eviction strategy.
     pub name_import_map_cache: Arc<DashMap<PathBuf, NameImportMapCacheEntry>>,
+    /// pytest collection options (`python_files`, `python_functions`, `python_classes`,
+    /// `testpaths`) that decide which files are scanned and which functions are tests.
+    pub pytest_options: Arc<std::sync::RwLock<PytestIniOptions>>,
+    /// pytest collection options of individual workspace roots, keyed by canonical root.
+    /// Files under a root use its options; all other files use `pytest_options`.
+    pub root_pytest_options: Arc<std::sync::RwLock<Vec<(PathBuf, PytestIniOptions)>>>,
+    /// Explicit virtual environment settings of individual workspace roots,
+    /// keyed by canonical root.
+    pub root_venv_settings: Arc<std::sync::RwLock<Vec<(PathBuf, venv::VenvSettings)>>>,
+    /// `fixture_paths` directories of individual workspace roots, keyed by
+    /// canonical root.
+    pub root_fixture_paths: Arc<std::sync::RwLock<Vec<RootFixturePathsEntry>>>,
+    /// site-packages directory of each workspace root's virtual environment,
+    /// keyed by canonical root. Plugin and third-party fixtures are only
+    /// visible to the roots whose environment provides them.
+    pub root_site_packages: Arc<std::sync::RwLock<Vec<(PathBuf, PathBuf)>>>,
+    /// Last applied version of each open document, used to reject stale
+    /// incremental changes.
+    pub document_versions: Arc<DashMap<PathBuf, i32>>,
+    /// Entries of the persistent on-disk index, keyed by canonical file path.
+    pub(crate) index_cache: Arc<DashMap<PathBuf, index_cache::CachedFile>>,
+    /// Location of the persistent index, or `None` when it is disabled.
+    pub index_cache_file: Arc<std::sync::Mutex<Option<PathBuf>>>,
 }
 
 impl Default for FixtureDatabase {
@@ -156,6 +202,8 @@ impl FixtureDatabase {
             definitions: Arc::new(DashMap::new()),
             file_definitions: Arc::new(DashMap::new()),
             usages: Arc::new(DashMap::new()),
+            parametrized_args: Arc::new(DashMap::new()),
+            classes: Arc::new(DashMap::new()),
             usage_by_fixture: Arc::new(DashMap::new()),
             file_cache: Arc::new(DashMap::new()),
             undeclared_fixtures: Arc::new(DashMap::new()),
@@ -170,9 +218,210 @@ impl FixtureDatabase {
             site_packages_paths: Arc::new(std::sync::Mutex::new(Vec::new())),
             editable_install_roots: Arc::new(std::sync::Mutex::new(Vec::new())),
             workspace_root: Arc::new(std::sync::Mutex::new(None)),
+            workspace_roots: Arc::new(std::sync::Mutex::new(Vec::new())),
             plugin_fixture_files: Arc::new(DashMap::new()),
             name_import_map_cache: Arc::new(DashMap::new()),
+            pytest_options: Arc::new(std::sync::RwLock::new(PytestIniOptions::default())),
+            root_pytest_options: Arc::new(std::sync::RwLock::new(Vec::new())),
+            root_venv_settings: Arc::new(std::sync::RwLock::new(Vec::new())),
+            root_fixture_paths: Arc::new(std::sync::RwLock::new(Vec::new())),
+            root_site_packages: Arc::new(std::sync::RwLock::new(Vec::new())),
+            document_versions: Arc::new(DashMap::new()),
+            index_cache: Arc::new(DashMap::new()),
+            index_cache_file: Arc::new(std::sync::Mutex::new(None)),
+        }
+    }
+
+    /// Replace the pytest collection options used for scanning and test detection.
+    /// Should be called before scanning the workspace.
+    pub fn set_pytest_options(&self, options: PytestIniOptions) {
+        *self.pytest_options.write().unwrap() = options;
+    }
+
+    /// Replace the pytest collection options of one workspace root.
+    /// Should be called before scanning that root.
+    pub fn set_root_pytest_options(&self, root: &Path, options: PytestIniOptions) {
+        let mut roots = self.root_pytest_options.write().unwrap();
+        roots.retain(|(path, _)| path != root);
+        roots.push((root.to_path_buf(), options));
+    }
+
+    /// Replace the explicit virtual environment settings of one workspace root.
+    /// Should be called before scanning that root.
+    pub fn set_root_venv_settings(&self, root: &Path, settings: venv::VenvSettings) {
+        let mut roots = self.root_venv_settings.write().unwrap();
+        roots.retain(|(path, _)| path != root);
+        roots.push((root.to_path_buf(), settings));
+    }
+
+    /// Replace the `fixture_paths` directories of one workspace root.
+    /// Should be called before scanning that root.
+    pub fn set_root_fixture_paths(&self, root: &Path, dirs: Vec<PathBuf>) {
+        let mut roots = self.root_fixture_paths.write().unwrap();
+        roots.retain(|(path, _)| path != root);
+        roots.push((root.to_path_buf(), dirs));
+    }
+
+    /// The `fixture_paths` directory containing `path`, if any.
+    pub(crate) fn fixture_path_for(&self, path: &Path) -> Option<PathBuf> {
+        self.root_fixture_paths
+            .read()
+            .unwrap()
+            .iter()
+            .flat_map(|(_, dirs)| dirs)
+            .find(|dir| path.starts_with(dir))
+            .cloned()
+    }
+
+    /// Run `f` with the pytest options that apply to `path`: those of the
+    /// innermost workspace root containing it, or the global options.
+    fn with_pytest_options<R>(&self, path: &Path, f: impl FnOnce(&PytestIniOptions) -> R) -> R {
+        let roots = self.root_pytest_options.read().unwrap();
+        let root_options = roots
+            .iter()
+            .filter(|(root, _)| path.starts_with(root))
+            .max_by_key(|(root, _)| root.components().count())
+            .map(|(_, options)| options);
+        match root_options {
+            Some(options) => f(options),
+            None => f(&self.pytest_options.read().unwrap()),
+        }
+    }
+
+    /// Get a copy of the pytest collection options that apply to `path`.
+    pub(crate) fn pytest_options_for(&self, path: &Path) -> PytestIniOptions {
+        self.with_pytest_options(path, PytestIniOptions::clone)
+    }
+
+    /// Check whether a function in `file_path` is a test function according to `python_functions`.
+    pub(crate) fn is_test_function_name(&self, file_path: &Path, name: &str) -> bool {
+        self.with_pytest_options(file_path, |options| options.is_test_function(name))
+    }
+
+    /// Check whether a class in `file_path` is a test class according to `python_classes`.
+    pub(crate) fn is_test_class_name(&self, file_path: &Path, name: &str) -> bool {
+        self.with_pytest_options(file_path, |options| options.is_test_class(name))
+    }
+
+    /// Check whether `path` is a test file according to `python_files`.
+    pub(crate) fn is_test_file_path(&self, path: &Path) -> bool {
+        self.with_pytest_options(path, |options| options.is_test_file(path))
+    }
+
+    /// The innermost workspace root containing `path`, if any.
+    pub(crate) fn innermost_workspace_root(&self, path: &Path) -> Option<PathBuf> {
+        self.all_workspace_roots()
+            .into_iter()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+    }
+
+    /// All known workspace roots, including one set directly via `workspace_root`.
+    pub(crate) fn all_workspace_roots(&self) -> Vec<PathBuf> {
+        let mut roots = self.workspace_roots.lock().unwrap().clone();
+        if let Some(root) = self.workspace_root.lock().unwrap().clone() {
+            if !roots.contains(&root) {
+                roots.push(root);
+            }
         }
+        roots
+    }
+
+    /// Stop tracking a workspace root and forget every file under it, and the
+    /// plugin and third-party files of its virtual environment unless another
+    /// root uses the same environment.
+    ///
+    /// Files under another root that is nested inside `root` are kept.
+    pub fn remove_workspace_root(&self, root: &Path) {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        info!("Removing workspace root: {:?}", root);
+
+        let remaining: Vec<PathBuf> = {
+            let mut roots = self.workspace_roots.lock().unwrap();
+            roots.retain(|r| *r != root);
+            roots.clone()
+        };
+        {
+            let mut primary = self.workspace_root.lock().unwrap();
+            if primary.as_ref() == Some(&root) {
+                *primary = remaining.first().cloned();
+            }
+        }
+        self.root_pytest_options
+            .write()
+            .unwrap()
+            .retain(|(path, _)| *path != root);
+        self.root_venv_settings
+            .write()
+            .unwrap()
+            .retain(|(path, _)| *path != root);
+        self.root_fixture_paths
+            .write()
+            .unwrap()
+            .retain(|(path, _)| *path != root);
+
+        // The root's virtual environment, unless another root shares it
+        let removed_site_packages: Vec<PathBuf> = {
+            let mut roots = self.root_site_packages.write().unwrap();
+            let removed: Vec<PathBuf> = roots
+                .iter()
+                .filter(|(path, _)| *path == root)
+                .map(|(_, site_packages)| site_packages.clone())
+                .collect();
+            roots.retain(|(path, _)| *path != root);
+            removed
+                .into_iter()
+                .filter(|site_packages| !roots.iter().any(|(_, other)| other == site_packages))
+                .collect()
+        };
+        self.site_packages_paths
+            .lock()
+            .unwrap()
+            .retain(|path| !removed_site_packages.contains(path));
+        let removed_editable_sources: Vec<PathBuf> = {
+            let mut installs = self.editable_install_roots.lock().unwrap();
+            let sources = installs
+                .iter()
+                .filter(|install| removed_site_packages.contains(&install.site_packages))
+                .map(|install| install.source_root.clone())
+                .collect();
+            installs.retain(|install| !removed_site_packages.contains(&install.site_packages));
+            sources
+        };
+
+        let belongs_to_root = |path: &Path| {
+            if path.starts_with(&root) {
+                !remaining
+                    .iter()
+                    .any(|other| other.starts_with(&root) && path.starts_with(other))
+            } else {
+                removed_site_packages
+                    .iter()
+                    .chain(&removed_editable_sources)
+                    .any(|dir| path.starts_with(dir))
+                    && !remaining.iter().any(|other| path.starts_with(other))
+            }
+        };
+        let files: HashSet<PathBuf> = self
+            .file_cache
+            .iter()
+            .map(|entry| entry.key().clone())
+            .chain(
+                self.file_definitions
+                    .iter()
+                    .map(|entry| entry.key().clone()),
+            )
+            .chain(self.usages.iter().map(|entry| entry.key().clone()))
+            .filter(|path| belongs_to_root(path))
+            .collect();
+        for file in &files {
+            self.forget_file(file);
+        }
+
+        self.plugin_fixture_files
+            .retain(|path, _| !belongs_to_root(path));
+        self.index_cache.retain(|path, _| !belongs_to_root(path));
+        info!("Forgot {} files under {:?}", files.len(), root);
     }
 
     /// Increment the definitions version to invalidate cycle cache.
@@ -320,11 +569,11 @@ impl FixtureDatabase {
     /// Returns true if the file is from an external editable install (third-party).
     pub(crate) fn is_editable_install_third_party(&self, file_path: &Path) -> bool {
         let installs = self.editable_install_roots.lock().unwrap();
-        let workspace = self.workspace_root.lock().unwrap();
+        let workspaces = self.all_workspace_roots();
 
         for install in installs.iter() {
             if file_path.starts_with(&install.source_root) {
-                if let Some(ref ws) = *workspace {
+                for ws in &workspaces {
                     // Not third-party if editable source is inside workspace
                     if install.source_root.starts_with(ws) {
                         return false;
@@ -341,6 +590,29 @@ impl FixtureDatabase {
         false
     }
 
+    /// Workspace files known to the database (with fixture definitions or
+    /// usages), excluding third-party files. Sorted for deterministic output.
+    pub fn workspace_files(&self) -> Vec<PathBuf> {
+        let workspace_roots = self.all_workspace_roots();
+
+        let files: HashSet<PathBuf> = self
+            .file_definitions
+            .iter()
+            .map(|entry| entry.key().clone())
+            .chain(self.usages.iter().map(|entry| entry.key().clone()))
+            .filter(|path| {
+                workspace_roots.is_empty()
+                    || workspace_roots.iter().any(|root| path.starts_with(root))
+            })
+            .filter(|path| !path.to_string_lossy().contains("site-packages"))
+            .filter(|path| !self.is_editable_install_third_party(path))
+            .collect();
+
+        let mut files: Vec<PathBuf> = files.into_iter().collect();
+        files.sort();
+        files
+    }
+
     /// Remove all cached data for a file.
     /// Called when a file is closed or deleted to prevent unbounded memory growth.
     pub fn cleanup_file_cache(&self, file_path: &Path) {
@@ -363,6 +635,9 @@ impl FixtureDatabase {
         // Remove from file_cache
         self.file_cache.remove(&canonical);
 
+        // Forget the document version; the next didOpen starts over
+        self.document_versions.remove(&canonical);
+
         // Remove from available_fixtures_cache (this file's cached available fixtures)
         self.available_fixtures_cache.remove(&canonical);
 
@@ -379,10 +654,40 @@ impl FixtureDatabase {
         // 2. They're cleaned up on next analyze_file call anyway
     }
 
+    /// Whether anything is known about a file (content, definitions or usages).
+    pub fn has_file(&self, file_path: &Path) -> bool {
+        self.file_cache.contains_key(file_path)
+            || self.file_definitions.contains_key(file_path)
+            || self.usages.contains_key(file_path)
+    }
+
+    /// Forget a file that was deleted from disk: its definitions, usages and
+    /// cached data. Returns `false` if nothing was known about it.
+    pub fn remove_file(&self, file_path: &Path) -> bool {
+        // The file itself is gone, so resolve symlinks through its directory
+        let canonical = file_path
+            .parent()
+            .and_then(|dir| dir.canonicalize().ok())
+            .zip(file_path.file_name())
+            .map(|(dir, name)| dir.join(name))
+            .unwrap_or_else(|| file_path.to_path_buf());
+
+        if !self.has_file(&canonical) {
+            return false;
+        }
+        info!("Removing deleted file: {:?}", canonical);
+        self.forget_file(&canonical);
+        self.index_cache.remove(&canonical);
+        true
+    }
+
     /// Evict entries from caches if they exceed the maximum size.
     /// Called periodically to prevent unbounded memory growth in very large workspaces.
     /// Most LSPs rely on did_close cleanup for open files; this is a safety net for
     /// workspace scan files that accumulate over time.
+    ///
+    /// Open documents are never evicted: with incremental sync, `file_cache`
+    /// holds the only copy of their text.
     pub(crate) fn evict_cache_if_needed(&self) {
         // Only evict if significantly over limit to avoid frequent eviction
         if self.file_cache.len() > MAX_FILE_CACHE_SIZE {
@@ -397,6 +702,7 @@ impl FixtureDatabase {
             let to_remove: Vec<PathBuf> = self
                 .file_cache
                 .iter()
+                .filter(|entry| !self.document_versions.contains_key(entry.key()))
                 .take(to_remove_count)
                 .map(|entry| entry.key().clone())
                 .collect();
@@ -0,0 +1,725 @@
+//! Fixture rename support.
+//!
+//! Computes every text range that has to change when a fixture is renamed.
+//! Override chains are followed so that child fixtures requesting the renamed
+//! fixture (`def db(db): ...`) keep overriding it after the rename.
+
+use super::decorators;
+use super::types::{FixtureDefinition, FixtureUsage, RenameLocation};
+use super::FixtureDatabase;
+use rustpython_parser::ast::{Arguments, Comprehension, Expr, Stmt};
+use rustpython_parser::text_size::TextRange;
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+/// Python keywords that can never be used as a fixture name.
+const PYTHON_KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// Check that `name` is a valid Python identifier and not a keyword.
+fn is_valid_fixture_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    (first == '_' || first.is_alphabetic())
+        && chars.all(|c| c == '_' || c.is_alphanumeric())
+        && !PYTHON_KEYWORDS.contains(&name)
+}
+
+impl FixtureDatabase {
+    /// Find the fixture targeted by a rename request at the given position.
+    ///
+    /// Works on parameter usages, usefixtures/indirect strings, fixture definition
+    /// names and the `name="..."` argument of `@pytest.fixture`. Returns the
+    /// resolved definition together with the range of the name under the cursor.
+    /// `line` is 0-based (LSP convention).
+    pub fn find_rename_target(
+        &self,
+        file_path: &Path,
+        line: u32,
+        character: u32,
+    ) -> Option<(FixtureDefinition, RenameLocation)> {
+        let target_line = (line + 1) as usize;
+        let cursor = character as usize;
+
+        if let Some(definition) =
+            self.find_fixture_or_definition_at_position(file_path, line, character)
+        {
+            // Prefer the usage under the cursor, narrowed to the fixture name
+            let usage_location = self.usages.get(file_path).and_then(|usages| {
+                usages
+                    .iter()
+                    .find(|u| {
+                        u.line == target_line
+                            && u.name == definition.name
+                            && cursor >= u.start_char
+                            && cursor < u.end_char
+                    })
+                    .and_then(|u| self.usage_rename_location(u))
+            });
+
+            let location = usage_location.or_else(|| self.fixture_name_location(&definition))?;
+            return Some((definition, location));
+        }
+
+        // The cursor may sit inside `@pytest.fixture(name="...")`
+        let names: Vec<String> = self
+            .file_definitions
+            .get(file_path)
+            .map(|names| names.iter().cloned().collect())
+            .unwrap_or_default();
+        for name in names {
+            let candidates: Vec<FixtureDefinition> = self
+                .definitions
+                .get(&name)
+                .map(|defs| {
+                    defs.iter()
+                        .filter(|d| d.file_path == file_path)
+                        .cloned()
+                        .collect()
+                })
+                .unwrap_or_default();
+            for definition in candidates {
+                if let Some(location) = self.fixture_name_location(&definition) {
+                    if location.line == target_line
+                        && cursor >= location.start_char
+                        && cursor <= location.end_char
+                    {
+                        return Some((definition, location));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Get the location where a definition spells its fixture name.
+    ///
+    /// This is the `name="..."` argument of the decorator when present, otherwise
+    /// the function name (or the assignment target for `x = pytest.fixture()(f)`).
+    pub(crate) fn fixture_name_location(
+        &self,
+        definition: &FixtureDefinition,
+    ) -> Option<RenameLocation> {
+        let content = self.get_file_content(&definition.file_path)?;
+        let line_text = content.lines().nth(definition.line.saturating_sub(1))?;
+
+        if line_text.get(definition.start_char..definition.end_char) == Some(&definition.name) {
+            return Some(RenameLocation {
+                file_path: definition.file_path.clone(),
+                line: definition.line,
+                start_char: definition.start_char,
+                end_char: definition.end_char,
+            });
+        }
+
+        // The function name differs from the fixture name: look for `name=`
+        let parsed = self.get_parsed_ast(&definition.file_path, &content)?;
+        let rustpython_parser::ast::Mod::Module(module) = parsed.as_ref() else {
+            return None;
+        };
+        let line_index = self.get_line_index(&definition.file_path, &content);
+
+        let decorator_list = find_decorators_at_line(&module.body, definition.line, &|offset| {
+            self.get_line_from_offset(offset, &line_index)
+        })?;
+
+        decorator_list.iter().find_map(|decorator| {
+            let range = decorators::extract_fixture_name_range(decorator)?;
+            let line = self.get_line_from_offset(range.start().to_usize(), &line_index);
+            let start_char =
+                self.get_char_position_from_offset(range.start().to_usize(), &line_index) + 1;
+            let end_char = start_char + definition.name.len();
+            let text = content.lines().nth(line.saturating_sub(1))?;
+            (text.get(start_char..end_char) == Some(&definition.name)).then(|| RenameLocation {
+                file_path: definition.file_path.clone(),
+                line,
+                start_char,
+                end_char,
+            })
+        })
+    }
+
+    /// Collect the override chain rooted at `definition`.
+    ///
+    /// The chain contains the definition itself plus every fixture with the same
+    /// name that requests it as a parameter, directly or through another override.
+    pub fn find_override_chain(&self, definition: &FixtureDefinition) -> Vec<FixtureDefinition> {
+        let mut chain = vec![definition.clone()];
+
+        let Some(candidates) = self
+            .definitions
+            .get(&definition.name)
+            .map(|defs| defs.clone())
+        else {
+            return chain;
+        };
+
+        // Iterate until no new overrides are found (chains can be several levels deep)
+        loop {
+            let mut added = false;
+            for candidate in &candidates {
+                if chain.contains(candidate) || !candidate.dependencies.contains(&candidate.name) {
+                    continue;
+                }
+                let parent = self.find_closest_definition_excluding(
+                    &candidate.file_path,
+                    &candidate.name,
+                    Some(candidate),
+                );
+                if parent.is_some_and(|p| chain.contains(&p)) {
+                    debug!(
+                        "Override of {} at {:?}:{} joins the rename chain",
+                        candidate.name, candidate.file_path, candidate.line
+                    );
+                    chain.push(candidate.clone());
+                    added = true;
+                }
+            }
+            if !added {
+                break;
+            }
+        }
+
+        chain
+    }
+
+    /// Compute every text range to rewrite when renaming `definition` to `new_name`.
+    ///
+    /// Covers the definition name (or its `name=` argument), parameters and
+    /// usefixtures/indirect/getfixturevalue strings that resolve to it,
+    /// references to those parameters inside function bodies, and child
+    /// overrides that request it.
+    /// Returns an error message when the rename would be invalid or would change
+    /// which fixture some test receives.
+    pub fn plan_fixture_rename(
+        &self,
+        definition: &FixtureDefinition,
+        new_name: &str,
+    ) -> Result<Vec<RenameLocation>, String> {
+        if !is_valid_fixture_name(new_name) {
+            return Err(format!("'{}' is not a valid fixture name", new_name));
+        }
+        if new_name == definition.name {
+            return Ok(Vec::new());
+        }
+
+        let chain = self.find_override_chain(definition);
+        if let Some(third_party) = chain.iter().find(|d| d.is_third_party) {
+            return Err(format!(
+                "Cannot rename third-party fixture '{}' defined in {}",
+                third_party.name,
+                third_party.file_path.display()
+            ));
+        }
+
+        info!(
+            "Planning rename of {} -> {} ({} definition(s) in override chain)",
+            definition.name,
+            new_name,
+            chain.len()
+        );
+
+        let mut locations: BTreeSet<RenameLocation> = BTreeSet::new();
+        for def in &chain {
+            let location = self.fixture_name_location(def).ok_or_else(|| {
+                format!(
+                    "Could not locate the name of fixture '{}' in {}",
+                    def.name,
+                    def.file_path.display()
+                )
+            })?;
+            locations.insert(location);
+
+            for usage in self.find_references_for_definition(def) {
+                let Some(location) = self.usage_rename_location(&usage) else {
+                    debug!(
+                        "Skipping usage of {} at {:?}:{} (name not found in range)",
+                        usage.name, usage.file_path, usage.line
+                    );
+                    continue;
+                };
+                locations.insert(location);
+
+                if usage.is_parameter {
+                    self.collect_parameter_body_references(&usage, new_name, &mut locations)?;
+                }
+            }
+        }
+
+        self.check_rename_conflicts(&chain, &locations, new_name)?;
+
+        Ok(locations.into_iter().collect())
+    }
+
+    /// Narrow a usage to the exact range of the fixture name.
+    ///
+    /// String usages from `indirect=True` cover the whole argnames string
+    /// (`"a, b"`), so the name has to be located inside that range.
+    fn usage_rename_location(&self, usage: &FixtureUsage) -> Option<RenameLocation> {
+        let content = self.get_file_content(&usage.file_path)?;
+        let line_text = content.lines().nth(usage.line.saturating_sub(1))?;
+        let slice = line_text.get(usage.start_char..usage.end_char)?;
+
+        let offset = find_identifier(slice, &usage.name)?;
+        let start_char = usage.start_char + offset;
+        Some(RenameLocation {
+            file_path: usage.file_path.clone(),
+            line: usage.line,
+            start_char,
+            end_char: start_char + usage.name.len(),
+        })
+    }
+
+    /// Add the references to a fixture parameter inside its function body.
+    fn collect_parameter_body_references(
+        &self,
+        usage: &FixtureUsage,
+        new_name: &str,
+        locations: &mut BTreeSet<RenameLocation>,
+    ) -> Result<(), String> {
+        let Some(content) = self.get_file_content(&usage.file_path) else {
+            return Ok(());
+        };
+        let Some(parsed) = self.get_parsed_ast(&usage.file_path, &content) else {
+            return Ok(());
+        };
+        let rustpython_parser::ast::Mod::Module(module) = parsed.as_ref() else {
+            return Ok(());
+        };
+        let line_index = self.get_line_index(&usage.file_path, &content);
+        let Some(line_start) = line_index.get(usage.line.saturating_sub(1)) else {
+            return Ok(());
+        };
+        let param_offset = line_start + usage.start_char;
+
+        let Some((func_name, args, body)) = find_function_with_param(&module.body, param_offset)
+        else {
+            return Ok(());
+        };
+
+        if arguments_bind(args, new_name) {
+            return Err(format!(
+                "Function '{}' already has a parameter named '{}'",
+                func_name, new_name
+            ));
+        }
+
+        let mut ranges = Vec::new();
+        collect_names_in_stmts(body, &usage.name, &mut ranges);
+        for range in ranges {
+            let line = self.get_line_from_offset(range.start().to_usize(), &line_index);
+            let start_char =
+                self.get_char_position_from_offset(range.start().to_usize(), &line_index);
+            locations.insert(RenameLocation {
+                file_path: usage.file_path.clone(),
+                line,
+                start_char,
+                end_char: start_char + usage.name.len(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Reject renames that would change fixture resolution somewhere.
+    ///
+    /// Fails when another non-third-party fixture called `new_name` is already
+    /// visible from an affected file, or when existing usages of `new_name`
+    /// would start resolving to the renamed fixture.
+    fn check_rename_conflicts(
+        &self,
+        chain: &[FixtureDefinition],
+        locations: &BTreeSet<RenameLocation>,
+        new_name: &str,
+    ) -> Result<(), String> {
+        let affected_files: HashSet<&PathBuf> = locations.iter().map(|l| &l.file_path).collect();
+        for file in affected_files {
+            if let Some(existing) = self.find_closest_definition(file, new_name) {
+                if !existing.is_third_party {
+                    return Err(format!(
+                        "A fixture named '{}' already exists at {}:{}",
+                        new_name,
+                        existing.file_path.display(),
+                        existing.line
+                    ));
+                }
+            }
+        }
+
+        if let Some(usages) = self.usage_by_fixture.get(new_name) {
+            for (file_path, usage) in usages.iter() {
+                if chain
+                    .iter()
+                    .any(|def| definition_scope_contains(def, file_path))
+                {
+                    return Err(format!(
+                        "Renaming to '{}' would shadow the fixture used at {}:{}",
+                        new_name,
+                        file_path.display(),
+                        usage.line
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Check whether a definition is visible from `file_path` under pytest's rules.
+fn definition_scope_contains(definition: &FixtureDefinition, file_path: &Path) -> bool {
+    if definition.file_path == file_path || definition.is_plugin {
+        return true;
+    }
+    let is_conftest = definition
+        .file_path
+        .file_name()
+        .is_some_and(|n| n == "conftest.py");
+    is_conftest
+        && definition
+            .file_path
+            .parent()
+            .is_some_and(|dir| file_path.starts_with(dir))
+}
+
+/// Find the byte offset of `name` as a standalone identifier within `text`.
+fn find_identifier(text: &str, name: &str) -> Option<usize> {
+    let is_ident_byte = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+    let bytes = text.as_bytes();
+    text.match_indices(name).map(|(i, _)| i).find(|&i| {
+        let end = i + name.len();
+        (i == 0 || !is_ident_byte(bytes[i - 1]))
+            && (end >= bytes.len() || !is_ident_byte(bytes[end]))
+    })
+}
+
+/// Find the decorator list of the function whose `def` sits on `line`.
+fn find_decorators_at_line<'a>(
+    stmts: &'a [Stmt],
+    line: usize,
+    line_of: &dyn Fn(usize) -> usize,
+) -> Option<&'a [Expr]> {
+    stmts.iter().find_map(|stmt| match stmt {
+        Stmt::FunctionDef(f) if line_of(f.range.start().to_usize()) == line => {
+            Some(f.decorator_list.as_slice())
+        }
+        Stmt::AsyncFunctionDef(f) if line_of(f.range.start().to_usize()) == line => {
+            Some(f.decorator_list.as_slice())
+        }
+        Stmt::ClassDef(c) => find_decorators_at_line(&c.body, line, line_of),
+        _ => None,
+    })
+}
+
+/// Find the function that declares a parameter starting at `param_offset`.
+fn find_function_with_param(
+    stmts: &[Stmt],
+    param_offset: usize,
+) -> Option<(&str, &Arguments, &[Stmt])> {
+    stmts.iter().find_map(|stmt| {
+        let (name, args, body) = match stmt {
+            Stmt::FunctionDef(f) => (f.name.as_str(), f.args.as_ref(), f.body.as_slice()),
+            Stmt::AsyncFunctionDef(f) => (f.name.as_str(), f.args.as_ref(), f.body.as_slice()),
+            Stmt::ClassDef(c) => return find_function_with_param(&c.body, param_offset),
+            _ => return None,
+        };
+        if FixtureDatabase::all_args(args)
+            .any(|arg| arg.def.range.start().to_usize() == param_offset)
+        {
+            Some((name, args, body))
+        } else {
+            find_function_with_param(body, param_offset)
+        }
+    })
+}
+
+/// Check whether a parameter list binds `name`.
+fn arguments_bind(args: &Arguments, name: &str) -> bool {
+    FixtureDatabase::all_args(args).any(|arg| arg.def.arg.as_str() == name)
+        || args.vararg.as_ref().is_some_and(|a| a.arg.as_str() == name)
+        || args.kwarg.as_ref().is_some_and(|a| a.arg.as_str() == name)
+}
+
+/// Visit the expressions evaluated in the enclosing scope when a parameter list is defined.
+fn collect_names_in_arguments(args: &Arguments, name: &str, out: &mut Vec<TextRange>) {
+    for arg in FixtureDatabase::all_args(args) {
+        if let Some(default) = &arg.default {
+            collect_names_in_expr(default, name, out);
+        }
+        if let Some(annotation) = &arg.def.annotation {
+            collect_names_in_expr(annotation, name, out);
+        }
+    }
+}
+
+fn collect_names_in_stmts(stmts: &[Stmt], name: &str, out: &mut Vec<TextRange>) {
+    for stmt in stmts {
+        collect_names_in_stmt(stmt, name, out);
+    }
+}
+
+/// Collect the ranges of every `Name` node called `name` in a statement.
+/// Nested functions and lambdas that rebind the name as a parameter are skipped.
+fn collect_names_in_stmt(stmt: &Stmt, name: &str, out: &mut Vec<TextRange>) {
+    match stmt {
+        Stmt::FunctionDef(f) => {
+            collect_names_in_exprs(&f.decorator_list, name, out);
+            collect_names_in_arguments(&f.args, name, out);
+            if !arguments_bind(&f.args, name) {
+                collect_names_in_stmts(&f.body, name, out);
+            }
+        }
+        Stmt::AsyncFunctionDef(f) => {
+            collect_names_in_exprs(&f.decorator_list, name, out);
+            collect_names_in_arguments(&f.args, name, out);
+            if !arguments_bind(&f.args, name) {
+                collect_names_in_stmts(&f.body, name, out);
+            }
+        }
+        Stmt::ClassDef(c) => {
+            collect_names_in_exprs(&c.decorator_list, name, out);
+            collect_names_in_exprs(&c.bases, name, out);
+            for keyword in &c.keywords {
+                collect_names_in_expr(&keyword.value, name, out);
+            }
+            collect_names_in_stmts(&c.body, name, out);
+        }
+        Stmt::Return(s) => {
+            if let Some(value) = &s.value {
+                collect_names_in_expr(value, name, out);
+            }
+        }
+        Stmt::Delete(s) => collect_names_in_exprs(&s.targets, name, out),
+        Stmt::Assign(s) => {
+            collect_names_in_exprs(&s.targets, name, out);
+            collect_names_in_expr(&s.value, name, out);
+        }
+        Stmt::AugAssign(s) => {
+            collect_names_in_expr(&s.target, name, out);
+            collect_names_in_expr(&s.value, name, out);
+        }
+        Stmt::AnnAssign(s) => {
+            collect_names_in_expr(&s.target, name, out);
+            collect_names_in_expr(&s.annotation, name, out);
+            if let Some(value) = &s.value {
+                collect_names_in_expr(value, name, out);
+            }
+        }
+        Stmt::For(s) => {
+            collect_names_in_expr(&s.target, name, out);
+            collect_names_in_expr(&s.iter, name, out);
+            collect_names_in_stmts(&s.body, name, out);
+            collect_names_in_stmts(&s.orelse, name, out);
+        }
+        Stmt::AsyncFor(s) => {
+            collect_names_in_expr(&s.target, name, out);
+            collect_names_in_expr(&s.iter, name, out);
+            collect_names_in_stmts(&s.body, name, out);
+            collect_names_in_stmts(&s.orelse, name, out);
+        }
+        Stmt::While(s) => {
+            collect_names_in_expr(&s.test, name, out);
+            collect_names_in_stmts(&s.body, name, out);
+            collect_names_in_stmts(&s.orelse, name, out);
+        }
+        Stmt::If(s) => {
+            collect_names_in_expr(&s.test, name, out);
+            collect_names_in_stmts(&s.body, name, out);
+            collect_names_in_stmts(&s.orelse, name, out);
+        }
+        Stmt::With(s) => {
+            for item in &s.items {
+                collect_names_in_expr(&item.context_expr, name, out);
+                if let Some(vars) = &item.optional_vars {
+                    collect_names_in_expr(vars, name, out);
+                }
+            }
+            collect_names_in_stmts(&s.body, name, out);
+        }
+        Stmt::AsyncWith(s) => {
+            for item in &s.items {
+                collect_names_in_expr(&item.context_expr, name, out);
+                if let Some(vars) = &item.optional_vars {
+                    collect_names_in_expr(vars, name, out);
+                }
+            }
+            collect_names_in_stmts(&s.body, name, out);
+        }
+        Stmt::Match(s) => {
+            collect_names_in_expr(&s.subject, name, out);
+            for case in &s.cases {
+                if let Some(guard) = &case.guard {
+                    collect_names_in_expr(guard, name, out);
+                }
+                collect_names_in_stmts(&case.body, name, out);
+            }
+        }
+        Stmt::Raise(s) => {
+            if let Some(exc) = &s.exc {
+                collect_names_in_expr(exc, name, out);
+            }
+            if let Some(cause) = &s.cause {
+                collect_names_in_expr(cause, name, out);
+            }
+        }
+        Stmt::Try(s) => {
+            collect_names_in_stmts(&s.body, name, out);
+            for handler in &s.handlers {
+                let rustpython_parser::ast::ExceptHandler::ExceptHandler(h) = handler;
+                if let Some(type_) = &h.type_ {
+                    collect_names_in_expr(type_, name, out);
+                }
+                collect_names_in_stmts(&h.body, name, out);
+            }
+            collect_names_in_stmts(&s.orelse, name, out);
+            collect_names_in_stmts(&s.finalbody, name, out);
+        }
+        Stmt::TryStar(s) => {
+            collect_names_in_stmts(&s.body, name, out);
+            for handler in &s.handlers {
+                let rustpython_parser::ast::ExceptHandler::ExceptHandler(h) = handler;
+                if let Some(type_) = &h.type_ {
+                    collect_names_in_expr(type_, name, out);
+                }
+                collect_names_in_stmts(&h.body, name, out);
+            }
+            collect_names_in_stmts(&s.orelse, name, out);
+            collect_names_in_stmts(&s.finalbody, name, out);
+        }
+        Stmt::Assert(s) => {
+            collect_names_in_expr(&s.test, name, out);
+            if let Some(msg) = &s.msg {
+                collect_names_in_expr(msg, name, out);
+            }
+        }
+        Stmt::Expr(s) => collect_names_in_expr(&s.value, name, out),
+        _ => {}
+    }
+}
+
+fn collect_names_in_exprs(exprs: &[Expr], name: &str, out: &mut Vec<TextRange>) {
+    for expr in exprs {
+        collect_names_in_expr(expr, name, out);
+    }
+}
+
+/// Visit a comprehension, skipping it entirely when a generator target rebinds `name`.
+fn collect_names_in_comprehension(
+    elts: &[&Expr],
+    generators: &[Comprehension],
+    name: &str,
+    out: &mut Vec<TextRange>,
+) {
+    let mut bound = Vec::new();
+    for generator in generators {
+        collect_names_in_expr(&generator.target, name, &mut bound);
+    }
+    if !bound.is_empty() {
+        // The outermost iterable is still evaluated in the enclosing scope
+        if let Some(first) = generators.first() {
+            collect_names_in_expr(&first.iter, name, out);
+        }
+        return;
+    }
+    for generator in generators {
+        collect_names_in_expr(&generator.iter, name, out);
+        collect_names_in_exprs(&generator.ifs, name, out);
+    }
+    for elt in elts {
+        collect_names_in_expr(elt, name, out);
+    }
+}
+
+/// Collect the ranges of every `Name` node called `name` in an expression.
+fn collect_names_in_expr(expr: &Expr, name: &str, out: &mut Vec<TextRange>) {
+    match expr {
+        Expr::Name(n) => {
+            if n.id.as_str() == name {
+                out.push(n.range);
+            }
+        }
+        Expr::BoolOp(e) => collect_names_in_exprs(&e.values, name, out),
+        Expr::NamedExpr(e) => {
+            collect_names_in_expr(&e.target, name, out);
+            collect_names_in_expr(&e.value, name, out);
+        }
+        Expr::BinOp(e) => {
+            collect_names_in_expr(&e.left, name, out);
+            collect_names_in_expr(&e.right, name, out);
+        }
+        Expr::UnaryOp(e) => collect_names_in_expr(&e.operand, name, out),
+        Expr::Lambda(e) => {
+            collect_names_in_arguments(&e.args, name, out);
+            if !arguments_bind(&e.args, name) {
+                collect_names_in_expr(&e.body, name, out);
+            }
+        }
+        Expr::IfExp(e) => {
+            collect_names_in_expr(&e.test, name, out);
+            collect_names_in_expr(&e.body, name, out);
+            collect_names_in_expr(&e.orelse, name, out);
+        }
+        Expr::Dict(e) => {
+            for key in e.keys.iter().flatten() {
+                collect_names_in_expr(key, name, out);
+            }
+            collect_names_in_exprs(&e.values, name, out);
+        }
+        Expr::Set(e) => collect_names_in_exprs(&e.elts, name, out),
+        Expr::ListComp(e) => collect_names_in_comprehension(&[&e.elt], &e.generators, name, out),
+        Expr::SetComp(e) => collect_names_in_comprehension(&[&e.elt], &e.generators, name, out),
+        Expr::GeneratorExp(e) => {
+            collect_names_in_comprehension(&[&e.elt], &e.generators, name, out)
+        }
+        Expr::DictComp(e) => {
+            collect_names_in_comprehension(&[&e.key, &e.value], &e.generators, name, out)
+        }
+        Expr::Await(e) => collect_names_in_expr(&e.value, name, out),
+        Expr::Yield(e) => {
+            if let Some(value) = &e.value {
+                collect_names_in_expr(value, name, out);
+            }
+        }
+        Expr::YieldFrom(e) => collect_names_in_expr(&e.value, name, out),
+        Expr::Compare(e) => {
+            collect_names_in_expr(&e.left, name, out);
+            collect_names_in_exprs(&e.comparators, name, out);
+        }
+        Expr::Call(e) => {
+            collect_names_in_expr(&e.func, name, out);
+            collect_names_in_exprs(&e.args, name, out);
+            for keyword in &e.keywords {
+                collect_names_in_expr(&keyword.value, name, out);
+            }
+        }
+        Expr::FormattedValue(e) => {
+            collect_names_in_expr(&e.value, name, out);
+            if let Some(spec) = &e.format_spec {
+                collect_names_in_expr(spec, name, out);
+            }
+        }
+        Expr::JoinedStr(e) => collect_names_in_exprs(&e.values, name, out),
+        Expr::Attribute(e) => collect_names_in_expr(&e.value, name, out),
+        Expr::Subscript(e) => {
+            collect_names_in_expr(&e.value, name, out);
+            collect_names_in_expr(&e.slice, name, out);
+        }
+        Expr::Starred(e) => collect_names_in_expr(&e.value, name, out),
+        Expr::List(e) => collect_names_in_exprs(&e.elts, name, out),
+        Expr::Tuple(e) => collect_names_in_exprs(&e.elts, name, out),
+        Expr::Slice(e) => {
+            for part in [&e.lower, &e.upper, &e.step].into_iter().flatten() {
+                collect_names_in_expr(part, name, out);
+            }
+        }
+        Expr::Constant(_) => {}
+    }
+}
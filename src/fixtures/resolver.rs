@@ -4,16 +4,45 @@
 //! references, and providing completion context.
 
 use super::decorators;
+use super::string_utils::levenshtein_distance;
 use super::types::{
-    CompletionContext, FixtureDefinition, FixtureScope, FixtureUsage, ParamInsertionInfo,
-    UndeclaredFixture,
+    ClassDefinition, CompletionContext, FixtureDefinition, FixtureScope, FixtureUsage,
+    ParamInsertionInfo, ParametrizedArgument, UndeclaredFixture,
 };
 use super::FixtureDatabase;
 use rustpython_parser::ast::{Arguments, Expr, Ranged, Stmt};
 use std::collections::HashSet;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tracing::{debug, info};
 
+/// Fixtures that pytest itself provides.  These are always considered known by
+/// the unknown-fixture check, even when no virtual environment was scanned.
+const PYTEST_BUILTIN_FIXTURES: &[&str] = &[
+    "cache",
+    "capfd",
+    "capfdbinary",
+    "caplog",
+    "capsys",
+    "capsysbinary",
+    "doctest_namespace",
+    "monkeypatch",
+    "pytestconfig",
+    "pytester",
+    "record_property",
+    "record_testsuite_property",
+    "record_xml_attribute",
+    "recwarn",
+    "request",
+    "testdir",
+    "tmp_path",
+    "tmp_path_factory",
+    "tmpdir",
+    "tmpdir_factory",
+];
+
+/// Maximum number of "did you mean" suggestions for an unknown fixture.
+const MAX_FIXTURE_SUGGESTIONS: usize = 3;
+
 impl FixtureDatabase {
     /// Find fixture definition for a given position in a file
     pub fn find_fixture_definition(
@@ -65,7 +94,11 @@ impl FixtureDatabase {
                             }
                         }
 
-                        return self.find_closest_definition(file_path, &usage.name);
+                        return self.find_closest_definition_at_line(
+                            file_path,
+                            &usage.name,
+                            usage.line,
+                        );
                     }
                 }
             }
@@ -144,23 +177,41 @@ impl FixtureDatabase {
         None
     }
 
-    /// Find the closest fixture definition based on pytest priority rules.
+    /// Find the closest fixture definition based on pytest priority rules,
+    /// as seen from module level (fixtures defined in classes are not visible).
     pub(crate) fn find_closest_definition(
         &self,
         file_path: &Path,
         fixture_name: &str,
     ) -> Option<FixtureDefinition> {
-        self.find_closest_definition_with_filter(file_path, fixture_name, |_| true)
+        self.find_closest_definition_with_filter(file_path, None, fixture_name, |_| true)
+    }
+
+    /// Find the closest fixture definition as seen from a line (1-based), so
+    /// that fixtures of the enclosing test class and its bases are visible.
+    pub(crate) fn find_closest_definition_at_line(
+        &self,
+        file_path: &Path,
+        fixture_name: &str,
+        line: usize,
+    ) -> Option<FixtureDefinition> {
+        self.find_closest_definition_with_filter(file_path, Some(line), fixture_name, |_| true)
     }
 
     /// Find the closest definition, excluding a specific definition.
+    ///
+    /// Resolution happens from the excluded definition's position, so a
+    /// fixture method overriding a fixture of the same name sees its class.
     pub(crate) fn find_closest_definition_excluding(
         &self,
         file_path: &Path,
         fixture_name: &str,
         exclude: Option<&FixtureDefinition>,
     ) -> Option<FixtureDefinition> {
-        self.find_closest_definition_with_filter(file_path, fixture_name, |def| {
+        let line = exclude
+            .filter(|excluded| excluded.file_path == file_path)
+            .map(|excluded| excluded.line);
+        self.find_closest_definition_with_filter(file_path, line, fixture_name, |def| {
             if let Some(excluded) = exclude {
                 def != excluded
             } else {
@@ -171,12 +222,16 @@ impl FixtureDatabase {
 
     /// Internal helper that implements pytest priority rules with a custom filter.
     /// Priority order:
+    /// 0. The class enclosing `line`, its base classes, then outer classes
     /// 1. Same file (highest priority, last definition wins)
     /// 2. Closest conftest.py in parent directories (including imported fixtures)
     /// 3. Third-party fixtures from site-packages
+    ///
+    /// Fixtures defined in classes are only found through priority 0.
     fn find_closest_definition_with_filter<F>(
         &self,
         file_path: &Path,
+        line: Option<usize>,
         fixture_name: &str,
         filter: F,
     ) -> Option<FixtureDefinition>
@@ -185,6 +240,28 @@ impl FixtureDatabase {
     {
         let definitions = self.definitions.get(fixture_name)?;
 
+        // Priority 0: Enclosing test classes (pytest scopes method fixtures to the class)
+        if let Some(line) = line {
+            for (class_file, class_name) in self.visible_classes(file_path, line) {
+                if let Some(def) = definitions
+                    .iter()
+                    .filter(|def| {
+                        def.file_path == class_file
+                            && def.class_name.as_deref() == Some(class_name.as_str())
+                            && filter(def)
+                    })
+                    .max_by_key(|def| def.line)
+                {
+                    info!(
+                        "Found fixture {} in class {} at line {}",
+                        fixture_name, class_name, def.line
+                    );
+                    return Some(def.clone());
+                }
+            }
+        }
+        let filter = |def: &FixtureDefinition| def.class_name.is_none() && filter(def);
+
         // Priority 1: Same file (highest priority)
         debug!(
             "Checking for fixture {} in same file: {:?}",
@@ -256,10 +333,15 @@ impl FixtureDatabase {
         // Priority 3: Plugin fixtures (discovered via pytest11 entry points)
         // These are globally available like third-party fixtures, but from workspace-local
         // editable installs that aren't in site-packages or conftest.py.
+        // Plugins and third-party fixtures only count for the root that provides them.
         debug!(
             "No fixture {} found in conftest hierarchy, checking plugins",
             fixture_name
         );
+        let requesting_root = self.innermost_workspace_root(file_path);
+        let filter = |def: &FixtureDefinition| {
+            filter(def) && self.is_from_root(def, requesting_root.as_deref())
+        };
         for def in definitions.iter() {
             if def.is_plugin && !def.is_third_party && filter(def) {
                 info!(
@@ -292,6 +374,120 @@ impl FixtureDatabase {
         None
     }
 
+    /// Whether a plugin or third-party definition is available in `root`, the
+    /// innermost workspace root of the requesting file. Without a root, or
+    /// when the origin of the definition is unknown, it is available.
+    pub(crate) fn is_from_root(&self, def: &FixtureDefinition, root: Option<&Path>) -> bool {
+        let Some(root) = root else {
+            return true;
+        };
+        let roots = self.definition_roots(def);
+        roots.is_empty() || roots.iter().any(|r| r == root)
+    }
+
+    /// Workspace roots a plugin or third-party definition comes from: the roots
+    /// listing its `fixture_paths` directory, the roots whose virtual
+    /// environment installs it (directly or as an editable install), or else
+    /// the root containing it.
+    fn definition_roots(&self, def: &FixtureDefinition) -> Vec<PathBuf> {
+        if let Some(dir) = &def.fixture_path {
+            return self
+                .root_fixture_paths
+                .read()
+                .unwrap()
+                .iter()
+                .filter(|(_, dirs)| dirs.contains(dir))
+                .map(|(root, _)| root.clone())
+                .collect();
+        }
+
+        let editable_site_packages: Vec<PathBuf> = self
+            .editable_install_roots
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|install| def.file_path.starts_with(&install.source_root))
+            .map(|install| install.site_packages.clone())
+            .collect();
+        let venv_roots: Vec<PathBuf> = self
+            .root_site_packages
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(_, site_packages)| {
+                def.file_path.starts_with(site_packages)
+                    || editable_site_packages.contains(site_packages)
+            })
+            .map(|(root, _)| root.clone())
+            .collect();
+        if !venv_roots.is_empty() {
+            return venv_roots;
+        }
+
+        self.innermost_workspace_root(&def.file_path)
+            .into_iter()
+            .collect()
+    }
+
+    /// Classes whose fixtures are visible at a line (1-based), nearest first:
+    /// the enclosing class and its base classes (depth-first), then the same
+    /// for each outer class. Base classes are looked up in the same file
+    /// first, then through the file's imports; unresolvable bases are skipped.
+    pub(crate) fn visible_classes(&self, file_path: &Path, line: usize) -> Vec<(PathBuf, String)> {
+        let mut chain = Vec::new();
+        let mut current = self.classes.get(file_path).and_then(|classes| {
+            classes
+                .iter()
+                .filter(|class| class.line <= line && line <= class.end_line)
+                .max_by_key(|class| class.line)
+                .cloned()
+        });
+
+        while let Some(class) = current {
+            self.push_class_and_bases(&class, &mut chain);
+            current = class.outer.as_ref().and_then(|outer| {
+                self.classes.get(file_path).and_then(|classes| {
+                    classes
+                        .iter()
+                        .filter(|c| &c.name == outer && c.line <= line && line <= c.end_line)
+                        .max_by_key(|c| c.line)
+                        .cloned()
+                })
+            });
+        }
+        chain
+    }
+
+    /// Append a class and, recursively, its base classes to a visibility chain.
+    fn push_class_and_bases(&self, class: &ClassDefinition, chain: &mut Vec<(PathBuf, String)>) {
+        let key = (class.file_path.clone(), class.name.clone());
+        if chain.contains(&key) {
+            return;
+        }
+        chain.push(key);
+
+        for base in &class.bases {
+            let same_file = self.classes.get(&class.file_path).and_then(|classes| {
+                classes
+                    .iter()
+                    .filter(|c| &c.name == base && c.line < class.line)
+                    .max_by_key(|c| c.line)
+                    .cloned()
+            });
+            let base_class = same_file.or_else(|| {
+                let (file_path, name) = self.resolve_imported_name(&class.file_path, base)?;
+                self.classes
+                    .get(&file_path)?
+                    .iter()
+                    .find(|c| c.name == name && c.outer.is_none())
+                    .cloned()
+            });
+            if let Some(base_class) = base_class {
+                self.push_class_and_bases(&base_class, chain);
+            }
+        }
+    }
+
     /// Find the fixture name at a given position (either definition or usage)
     pub fn find_fixture_at_position(
         &self,
@@ -420,10 +616,10 @@ impl FixtureDatabase {
                         Some(current_def),
                     )
                 } else {
-                    self.find_closest_definition(file_path, &usage.name)
+                    self.find_closest_definition_at_line(file_path, &usage.name, usage.line)
                 }
             } else {
-                self.find_closest_definition(file_path, &usage.name)
+                self.find_closest_definition_at_line(file_path, &usage.name, usage.line)
             };
 
             if let Some(resolved_def) = resolved_def {
@@ -491,6 +687,56 @@ impl FixtureDatabase {
         available_fixtures
     }
 
+    /// Get all fixtures available at a line (1-based): fixtures defined in the
+    /// enclosing test classes (and their bases) shadow those available to the
+    /// whole file.
+    pub fn get_available_fixtures_at_line(
+        &self,
+        file_path: &Path,
+        line: usize,
+    ) -> Vec<FixtureDefinition> {
+        let file_path = self.get_canonical_path(file_path.to_path_buf());
+        let classes = self.visible_classes(&file_path, line);
+        if classes.is_empty() {
+            return self.get_available_fixtures(&file_path);
+        }
+
+        let mut available_fixtures = Vec::new();
+        let mut seen_names = HashSet::new();
+        for (class_file, class_name) in classes {
+            let Some(names) = self.file_definitions.get(&class_file) else {
+                continue;
+            };
+            for name in names.iter() {
+                if seen_names.contains(name) {
+                    continue;
+                }
+                let Some(definitions) = self.definitions.get(name) else {
+                    continue;
+                };
+                if let Some(def) = definitions
+                    .iter()
+                    .filter(|def| {
+                        def.file_path == class_file
+                            && def.class_name.as_deref() == Some(class_name.as_str())
+                    })
+                    .max_by_key(|def| def.line)
+                {
+                    available_fixtures.push(def.clone());
+                    seen_names.insert(name.clone());
+                }
+            }
+        }
+
+        available_fixtures.extend(
+            self.get_available_fixtures(&file_path)
+                .into_iter()
+                .filter(|def| !seen_names.contains(&def.name)),
+        );
+        available_fixtures.sort_by(|a, b| a.name.cmp(&b.name));
+        available_fixtures
+    }
+
     /// Internal method to compute available fixtures without caching.
     fn compute_available_fixtures(&self, file_path: &Path) -> Vec<FixtureDefinition> {
         let mut available_fixtures = Vec::new();
@@ -500,7 +746,10 @@ impl FixtureDatabase {
         for entry in self.definitions.iter() {
             let fixture_name = entry.key();
             for def in entry.value().iter() {
-                if def.file_path == file_path && !seen_names.contains(fixture_name.as_str()) {
+                if def.file_path == file_path
+                    && def.class_name.is_none()
+                    && !seen_names.contains(fixture_name.as_str())
+                {
                     available_fixtures.push(def.clone());
                     seen_names.insert(fixture_name.clone());
                 }
@@ -517,6 +766,7 @@ impl FixtureDatabase {
                     let fixture_name = entry.key();
                     for def in entry.value().iter() {
                         if def.file_path == conftest_path
+                            && def.class_name.is_none()
                             && !seen_names.contains(fixture_name.as_str())
                         {
                             available_fixtures.push(def.clone());
@@ -534,7 +784,9 @@ impl FixtureDatabase {
                         if !seen_names.contains(&fixture_name) {
                             // Get the original definition for this imported fixture
                             if let Some(definitions) = self.definitions.get(&fixture_name) {
-                                if let Some(def) = definitions.first() {
+                                if let Some(def) =
+                                    definitions.iter().find(|def| def.class_name.is_none())
+                                {
                                     available_fixtures.push(def.clone());
                                     seen_names.insert(fixture_name);
                                 }
@@ -551,12 +803,15 @@ impl FixtureDatabase {
         }
 
         // Priority 3: Plugin fixtures (pytest11 entry points, e.g. workspace editable installs)
+        let requesting_root = self.innermost_workspace_root(file_path);
         for entry in self.definitions.iter() {
             let fixture_name = entry.key();
             for def in entry.value().iter() {
                 if def.is_plugin
                     && !def.is_third_party
+                    && def.class_name.is_none()
                     && !seen_names.contains(fixture_name.as_str())
+                    && self.is_from_root(def, requesting_root.as_deref())
                 {
                     available_fixtures.push(def.clone());
                     seen_names.insert(fixture_name.clone());
@@ -568,7 +823,11 @@ impl FixtureDatabase {
         for entry in self.definitions.iter() {
             let fixture_name = entry.key();
             for def in entry.value().iter() {
-                if def.is_third_party && !seen_names.contains(fixture_name.as_str()) {
+                if def.is_third_party
+                    && def.class_name.is_none()
+                    && !seen_names.contains(fixture_name.as_str())
+                    && self.is_from_root(def, requesting_root.as_deref())
+                {
                     available_fixtures.push(def.clone());
                     seen_names.insert(fixture_name.clone());
                 }
@@ -589,6 +848,12 @@ impl FixtureDatabase {
         let content = self.get_file_content(file_path)?;
         let target_line = (line + 1) as usize;
 
+        // Call arguments are usually typed before the file parses again, so
+        // they are detected from the text
+        if let Some(ctx) = Self::get_call_argument_context(&content, line, character) {
+            return Some(ctx);
+        }
+
         // Try AST-based analysis first
         let parsed = self.get_parsed_ast(file_path, &content);
 
@@ -605,6 +870,7 @@ impl FixtureDatabase {
 
                 // Then check for function context
                 if let Some(ctx) = self.get_function_completion_context(
+                    file_path,
                     &module.body,
                     &content,
                     target_line,
@@ -617,7 +883,7 @@ impl FixtureDatabase {
         }
 
         // Fallback: text-based analysis for incomplete/invalid Python
-        self.get_completion_context_from_text(&content, target_line)
+        self.get_completion_context_from_text(file_path, &content, target_line)
     }
 
     /// Check whether a `@pytest.fixture` decorator appears in the lines immediately
@@ -625,7 +891,7 @@ impl FixtureDatabase {
     ///
     /// Scans upward through decorator lines (lines starting with `@` after stripping
     /// whitespace) and blank lines, stopping at the first non-decorator, non-blank line.
-    fn has_fixture_decorator_above(lines: &[&str], def_line_idx: usize) -> bool {
+    pub(super) fn has_fixture_decorator_above(lines: &[&str], def_line_idx: usize) -> bool {
         if def_line_idx == 0 {
             return false;
         }
@@ -800,6 +1066,7 @@ impl FixtureDatabase {
     /// 2. Function signature contexts (def/async def lines)
     fn get_completion_context_from_text(
         &self,
+        file_path: &Path,
         content: &str,
         target_line: usize,
     ) -> Option<CompletionContext> {
@@ -864,7 +1131,7 @@ impl FixtureDatabase {
         }
 
         // Determine is_test / is_fixture
-        let is_test = func_name.starts_with("test_");
+        let is_test = self.is_test_function_name(file_path, &func_name);
         let is_fixture = Self::has_fixture_decorator_above(&lines, def_line_idx);
 
         // No completions for regular functions
@@ -1108,6 +1375,7 @@ impl FixtureDatabase {
     /// Get completion context when cursor is inside a function
     fn get_function_completion_context(
         &self,
+        file_path: &Path,
         stmts: &[Stmt],
         content: &str,
         target_line: usize,
@@ -1118,6 +1386,7 @@ impl FixtureDatabase {
             match stmt {
                 Stmt::FunctionDef(func_def) => {
                     if let Some(ctx) = self.get_func_context(
+                        file_path,
                         &func_def.name,
                         &func_def.decorator_list,
                         &func_def.args,
@@ -1134,6 +1403,7 @@ impl FixtureDatabase {
                 }
                 Stmt::AsyncFunctionDef(func_def) => {
                     if let Some(ctx) = self.get_func_context(
+                        file_path,
                         &func_def.name,
                         &func_def.decorator_list,
                         &func_def.args,
@@ -1150,6 +1420,7 @@ impl FixtureDatabase {
                 }
                 Stmt::ClassDef(class_def) => {
                     if let Some(ctx) = self.get_function_completion_context(
+                        file_path,
                         &class_def.body,
                         content,
                         target_line,
@@ -1246,6 +1517,7 @@ impl FixtureDatabase {
     #[allow(clippy::too_many_arguments)]
     fn get_func_context(
         &self,
+        file_path: &Path,
         func_name: &rustpython_parser::ast::Identifier,
         decorator_list: &[Expr],
         args: &rustpython_parser::ast::Arguments,
@@ -1265,7 +1537,7 @@ impl FixtureDatabase {
         }
 
         let is_fixture = decorator_list.iter().any(decorators::is_fixture_decorator);
-        let is_test = func_name.as_str().starts_with("test_");
+        let is_test = self.is_test_function_name(file_path, func_name.as_str());
 
         if !is_test && !is_fixture {
             return None;
@@ -1420,6 +1692,7 @@ impl FixtureDatabase {
 
         if let rustpython_parser::ast::Mod::Module(module) = parsed.as_ref() {
             return self.find_enclosing_function(
+                file_path,
                 &module.body,
                 &content,
                 target_line,
@@ -1433,6 +1706,7 @@ impl FixtureDatabase {
     #[allow(dead_code)]
     fn find_enclosing_function(
         &self,
+        file_path: &Path,
         stmts: &[Stmt],
         content: &str,
         target_line: usize,
@@ -1454,7 +1728,7 @@ impl FixtureDatabase {
                             .decorator_list
                             .iter()
                             .any(decorators::is_fixture_decorator);
-                        let is_test = func_def.name.starts_with("test_");
+                        let is_test = self.is_test_function_name(file_path, func_def.name.as_str());
 
                         // Only return if it's a test or fixture
                         if is_test || is_fixture {
@@ -1480,7 +1754,7 @@ impl FixtureDatabase {
                             .decorator_list
                             .iter()
                             .any(decorators::is_fixture_decorator);
-                        let is_test = func_def.name.starts_with("test_");
+                        let is_test = self.is_test_function_name(file_path, func_def.name.as_str());
 
                         if is_test || is_fixture {
                             let params: Vec<String> = func_def
@@ -1541,19 +1815,32 @@ impl FixtureDatabase {
         let mut dep_graph: HashMap<String, Vec<String>> = HashMap::new();
         let mut fixture_defs: HashMap<String, FixtureDefinition> = HashMap::new();
 
-        for entry in self.definitions.iter() {
-            let fixture_name = entry.key().clone();
-            if let Some(def) = entry.value().first() {
-                fixture_defs.insert(fixture_name.clone(), def.clone());
-                // Only include dependencies that are known fixtures
-                let valid_deps: Vec<String> = def
-                    .dependencies
-                    .iter()
-                    .filter(|d| self.definitions.contains_key(*d))
-                    .cloned()
-                    .collect();
-                dep_graph.insert(fixture_name, valid_deps);
-            }
+        // Snapshot the first definition of each fixture up front: resolving
+        // overrides below reads `self.definitions` again.
+        let first_defs: Vec<(String, FixtureDefinition)> = self
+            .definitions
+            .iter()
+            .filter_map(|entry| Some((entry.key().clone(), entry.value().first()?.clone())))
+            .collect();
+
+        for (fixture_name, def) in first_defs {
+            // A fixture requesting its own name overrides another definition
+            // of that name (e.g. in a parent conftest.py); it is only a cycle
+            // when the name does not resolve to a different definition.
+            let is_override = def.dependencies.contains(&fixture_name)
+                && self
+                    .find_closest_definition_excluding(&def.file_path, &fixture_name, Some(&def))
+                    .is_some();
+            // Only include dependencies that are known fixtures
+            let valid_deps: Vec<String> = def
+                .dependencies
+                .iter()
+                .filter(|d| self.definitions.contains_key(*d))
+                .filter(|d| !(is_override && **d == fixture_name))
+                .cloned()
+                .collect();
+            fixture_defs.insert(fixture_name.clone(), def);
+            dep_graph.insert(fixture_name, valid_deps);
         }
 
         let mut cycles = Vec::new();
@@ -1718,6 +2005,305 @@ impl FixtureDatabase {
         mismatches
     }
 
+    // ============ Fixture Overrides ============
+
+    /// The definition `definition` overrides: the one pytest would use for
+    /// its name if `definition` did not exist. Earlier definitions in the
+    /// same file and class are redefinitions, not overrides.
+    pub fn find_overridden_definition(
+        &self,
+        definition: &FixtureDefinition,
+    ) -> Option<FixtureDefinition> {
+        self.find_closest_definition_excluding(
+            &definition.file_path,
+            &definition.name,
+            Some(definition),
+        )
+        .filter(|overridden| {
+            overridden.file_path != definition.file_path
+                || overridden.class_name != definition.class_name
+        })
+    }
+
+    /// Detect fixtures defined in the given file that override a definition
+    /// from a parent conftest.py, a plugin, an enclosing module or a base class.
+    pub fn detect_fixture_overrides_in_file(
+        &self,
+        file_path: &Path,
+    ) -> Vec<super::types::FixtureOverride> {
+        use super::types::FixtureOverride;
+
+        let Some(fixture_names) = self.file_definitions.get(file_path) else {
+            return Vec::new();
+        };
+
+        let mut overrides = Vec::new();
+        for fixture_name in fixture_names.iter() {
+            let local: Vec<FixtureDefinition> = match self.definitions.get(fixture_name) {
+                Some(definitions) => definitions
+                    .iter()
+                    .filter(|def| def.file_path == file_path)
+                    .cloned()
+                    .collect(),
+                None => continue,
+            };
+            for fixture in local {
+                if let Some(overridden) = self.find_overridden_definition(&fixture) {
+                    overrides.push(FixtureOverride {
+                        fixture,
+                        overridden,
+                    });
+                }
+            }
+        }
+        overrides.sort_by_key(|o| (o.fixture.line, o.fixture.start_char));
+        overrides
+    }
+
+    // ============ Unknown Fixtures ============
+
+    /// Detect function parameters that do not resolve to any known fixture.
+    ///
+    /// `self`/`cls` and parameters with a default value are not fixture
+    /// requests and are never reported.  pytest's built-in
+    /// fixtures are always treated as known so that a missing virtual
+    /// environment does not flag `tmp_path`, `monkeypatch`, etc.
+    ///
+    /// Nothing is reported when the file's workspace root has no virtual
+    /// environment (plugin fixtures such as `mocker` would all be unknown), or
+    /// when the module or a conftest.py above it defines `pytest_generate_tests`,
+    /// which can supply any argument through `metafunc.parametrize`.
+    pub fn detect_unknown_fixtures_in_file(&self, file_path: &Path) -> Vec<FixtureUsage> {
+        if !self.has_virtual_environment(file_path) {
+            debug!(
+                "No virtual environment for {:?}, skipping unknown fixtures",
+                file_path
+            );
+            return Vec::new();
+        }
+        if self.has_generate_tests_hook(file_path) {
+            debug!(
+                "pytest_generate_tests applies to {:?}, skipping unknown fixtures",
+                file_path
+            );
+            return Vec::new();
+        }
+
+        let candidates: Vec<FixtureUsage> = match self.usages.get(file_path) {
+            Some(usages) => usages
+                .iter()
+                .filter(|u| u.is_parameter || u.is_dynamic)
+                .filter(|u| !PYTEST_BUILTIN_FIXTURES.contains(&u.name.as_str()))
+                .filter(|u| {
+                    self.resolve_fixture_for_file(file_path, &u.name).is_none()
+                        && self
+                            .find_closest_definition_at_line(file_path, &u.name, u.line)
+                            .is_none()
+                })
+                .cloned()
+                .collect(),
+            None => return Vec::new(),
+        };
+
+        if candidates.is_empty() {
+            return candidates;
+        }
+
+        let non_fixture_params = self.collect_non_fixture_parameters(file_path);
+        candidates
+            .into_iter()
+            .filter(|u| !non_fixture_params.contains(&(u.line, u.start_char)))
+            .collect()
+    }
+
+    /// Whether the virtual environment of the workspace root containing
+    /// `file_path` was found. Files outside every scanned root have nothing
+    /// to compare against and count as having one.
+    fn has_virtual_environment(&self, file_path: &Path) -> bool {
+        match self.innermost_workspace_root(file_path) {
+            Some(root) => self
+                .root_site_packages
+                .read()
+                .unwrap()
+                .iter()
+                .any(|(path, _)| *path == root),
+            None => true,
+        }
+    }
+
+    /// Whether `pytest_generate_tests` is defined in `file_path` or in a
+    /// conftest.py of one of its parent directories.
+    fn has_generate_tests_hook(&self, file_path: &Path) -> bool {
+        let conftests = file_path
+            .ancestors()
+            .skip(1)
+            .map(|dir| dir.join("conftest.py"));
+        std::iter::once(file_path.to_path_buf())
+            .chain(conftests)
+            .any(|path| {
+                let Some(content) = self.get_file_content(&path) else {
+                    return false;
+                };
+                let Some(parsed) = self.get_parsed_ast(&path, &content) else {
+                    return false;
+                };
+                let rustpython_parser::ast::Mod::Module(module) = parsed.as_ref() else {
+                    return false;
+                };
+                module.body.iter().any(|stmt| match stmt {
+                    Stmt::FunctionDef(f) => f.name.as_str() == "pytest_generate_tests",
+                    Stmt::AsyncFunctionDef(f) => f.name.as_str() == "pytest_generate_tests",
+                    _ => false,
+                })
+            })
+    }
+
+    /// Suggest the closest-named fixtures available in `file_path` for `name`,
+    /// best match first.
+    pub fn suggest_fixture_names(&self, file_path: &Path, name: &str) -> Vec<String> {
+        // Allow roughly one typo per three characters, but at least two.
+        let max_distance = (name.chars().count() / 3).max(2);
+
+        let mut candidates: Vec<(usize, String)> = self
+            .get_available_fixtures(file_path)
+            .into_iter()
+            .map(|def| def.name)
+            .chain(PYTEST_BUILTIN_FIXTURES.iter().map(|s| s.to_string()))
+            .filter(|candidate| candidate != name)
+            .map(|candidate| (levenshtein_distance(name, &candidate), candidate))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .collect();
+
+        candidates.sort();
+        candidates.dedup_by(|a, b| a.1 == b.1);
+        candidates
+            .into_iter()
+            .take(MAX_FIXTURE_SUGGESTIONS)
+            .map(|(_, candidate)| candidate)
+            .collect()
+    }
+
+    /// Collect the `(line, start_char)` positions of test/fixture parameters in
+    /// a file that pytest does not treat as fixture requests: `self`/`cls` and
+    /// parameters with a default value.  Parametrize argnames are never
+    /// recorded as usages in the first place.
+    fn collect_non_fixture_parameters(&self, file_path: &Path) -> HashSet<(usize, usize)> {
+        let mut positions = HashSet::new();
+
+        let Some(content) = self.get_file_content(file_path) else {
+            return positions;
+        };
+        let Some(parsed) = self.get_parsed_ast(file_path, &content) else {
+            return positions;
+        };
+        let line_index = self.get_line_index(file_path, &content);
+
+        if let rustpython_parser::ast::Mod::Module(module) = parsed.as_ref() {
+            self.collect_non_fixture_parameters_in_stmts(&module.body, &line_index, &mut positions);
+        }
+
+        positions
+    }
+
+    /// Walk module/class bodies recording non-fixture parameter positions.
+    fn collect_non_fixture_parameters_in_stmts(
+        &self,
+        stmts: &[Stmt],
+        line_index: &[usize],
+        positions: &mut HashSet<(usize, usize)>,
+    ) {
+        for stmt in stmts {
+            let args = match stmt {
+                Stmt::FunctionDef(f) => &f.args,
+                Stmt::AsyncFunctionDef(f) => &f.args,
+                Stmt::ClassDef(class_def) => {
+                    self.collect_non_fixture_parameters_in_stmts(
+                        &class_def.body,
+                        line_index,
+                        positions,
+                    );
+                    continue;
+                }
+                _ => continue,
+            };
+
+            for arg in Self::all_args(args) {
+                let name = arg.def.arg.as_str();
+                if name == "self" || name == "cls" || arg.default.is_some() {
+                    let offset = arg.def.range.start().to_usize();
+                    positions.insert((
+                        self.get_line_from_offset(offset, line_index),
+                        self.get_char_position_from_offset(offset, line_index),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Find the `@pytest.mark.parametrize` argument at a position (0-based line).
+    pub fn find_parametrized_argument_at_position(
+        &self,
+        file_path: &Path,
+        line: u32,
+        character: u32,
+    ) -> Option<ParametrizedArgument> {
+        let target_line = (line + 1) as usize; // Convert from 0-based to 1-based
+        let cursor = character as usize;
+
+        self.parametrized_args
+            .get(file_path)?
+            .iter()
+            .find_map(|arg| {
+                (arg.line == target_line && cursor >= arg.start_char && cursor < arg.end_char)
+                    .then(|| arg.clone())
+            })
+    }
+
+    /// Find the parametrized fixture whose `request.param` is at a position
+    /// (0-based line), i.e. the cursor is on `request.param` inside the body
+    /// of a fixture declared with `params=`.
+    pub fn find_request_param_at_position(
+        &self,
+        file_path: &Path,
+        line: u32,
+        character: u32,
+    ) -> Option<FixtureDefinition> {
+        let target_line = (line + 1) as usize; // Convert from 0-based to 1-based
+        let cursor = character as usize;
+
+        let content = self.get_file_content(file_path)?;
+        let line_content = content.lines().nth(target_line.saturating_sub(1))?;
+        let chars: Vec<char> = line_content.chars().collect();
+        let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+
+        let on_request_param =
+            line_content
+                .match_indices("request.param")
+                .any(|(byte_pos, matched)| {
+                    let start = line_content[..byte_pos].chars().count();
+                    let end = start + matched.len();
+                    let whole_word = (start == 0 || !is_identifier(chars[start - 1]))
+                        && chars.get(end).is_none_or(|c| !is_identifier(*c));
+                    whole_word && cursor >= start && cursor < end
+                });
+        if !on_request_param {
+            return None;
+        }
+
+        let names = self.file_definitions.get(file_path)?;
+        names
+            .iter()
+            .filter_map(|name| self.definitions.get(name))
+            .flat_map(|defs| defs.value().clone())
+            .filter(|def| {
+                def.file_path == file_path
+                    && !def.params.is_empty()
+                    && def.line <= target_line
+                    && target_line <= def.end_line
+            })
+            .max_by_key(|def| def.line)
+    }
+
     /// Resolve a fixture by name for a given file using priority rules.
     ///
     /// Returns the best matching FixtureDefinition based on pytest's
@@ -1728,10 +2314,15 @@ impl FixtureDatabase {
         fixture_name: &str,
     ) -> Option<FixtureDefinition> {
         let definitions = self.definitions.get(fixture_name)?;
+        // Fixtures defined in a test class are only visible inside it
+        let definitions: Vec<&FixtureDefinition> = definitions
+            .iter()
+            .filter(|d| d.class_name.is_none())
+            .collect();
 
         // Priority 1: Same file
         if let Some(def) = definitions.iter().find(|d| d.file_path == file_path) {
-            return Some(def.clone());
+            return Some((*def).clone());
         }
 
         // Priority 2: conftest.py in parent directories (closest first)
@@ -1739,7 +2330,7 @@ impl FixtureDatabase {
         let mut best_conftest: Option<&FixtureDefinition> = None;
         let mut best_depth = usize::MAX;
 
-        for def in definitions.iter() {
+        for &def in definitions.iter() {
             if def.is_third_party {
                 continue;
             }
@@ -1765,20 +2356,25 @@ impl FixtureDatabase {
         }
 
         // Priority 3: Plugin fixtures (pytest11 entry points)
+        let requesting_root = self.innermost_workspace_root(&file_path);
+        let from_root = |d: &FixtureDefinition| self.is_from_root(d, requesting_root.as_deref());
         if let Some(def) = definitions
             .iter()
-            .find(|d| d.is_plugin && !d.is_third_party)
+            .find(|d| d.is_plugin && !d.is_third_party && from_root(d))
         {
-            return Some(def.clone());
+            return Some((*def).clone());
         }
 
         // Priority 4: Third-party (site-packages)
-        if let Some(def) = definitions.iter().find(|d| d.is_third_party) {
-            return Some(def.clone());
+        if let Some(def) = definitions
+            .iter()
+            .find(|d| d.is_third_party && from_root(d))
+        {
+            return Some((*def).clone());
         }
 
         // Fallback: first definition
-        definitions.first().cloned()
+        definitions.first().map(|def| (*def).clone())
     }
 
     /// Find the name of the function/fixture containing a given line.
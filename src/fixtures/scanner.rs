@@ -2,7 +2,9 @@
 
 use super::imports::try_init_stdlib_from_python;
 use super::types::{FixtureDefinition, FixtureScope, TypeImportSpec};
+use super::venv;
 use super::FixtureDatabase;
+use crate::config::PytestIniOptions;
 use glob::Pattern;
 use rayon::prelude::*;
 use std::path::{Path, PathBuf};
@@ -70,8 +72,56 @@ impl FixtureDatabase {
         false
     }
 
+    /// Whether a scan picks up `path`: a conftest.py or a test module matching
+    /// `python_files`, restricted to `testpaths` when configured.
+    fn is_collected_file(
+        path: &Path,
+        filename: &str,
+        relative_path: &Path,
+        pytest_options: &PytestIniOptions,
+    ) -> bool {
+        if filename == "conftest.py" {
+            pytest_options.is_within_testpaths(relative_path)
+                || relative_path
+                    .parent()
+                    .is_some_and(|dir| pytest_options.is_testpaths_ancestor(dir))
+        } else {
+            pytest_options.is_test_file(path) && pytest_options.is_within_testpaths(relative_path)
+        }
+    }
+
+    /// Whether scanning `root_path` with `exclude_patterns` would analyze `path`.
+    /// Used for files that appear on disk after the workspace scan.
+    pub fn is_workspace_scan_target(
+        &self,
+        root_path: &Path,
+        path: &Path,
+        exclude_patterns: &[Pattern],
+    ) -> bool {
+        let Ok(relative_path) = path.strip_prefix(root_path) else {
+            return false;
+        };
+        let Some(filename) = path.file_name().and_then(|n| n.to_str()) else {
+            return false;
+        };
+
+        let in_skipped_directory = relative_path.components().any(|c| {
+            c.as_os_str()
+                .to_str()
+                .is_some_and(Self::should_skip_directory)
+        });
+        let relative_str = relative_path.to_string_lossy();
+        if in_skipped_directory || exclude_patterns.iter().any(|p| p.matches(&relative_str)) {
+            return false;
+        }
+
+        let pytest_options = self.pytest_options_for(root_path);
+        Self::is_collected_file(path, filename, relative_path, &pytest_options)
+    }
+
     /// Scan a workspace directory for test files and conftest.py files.
     /// Optionally accepts exclude patterns from configuration.
+    #[allow(dead_code)] // Library API; the CLI and server scan with excludes
     pub fn scan_workspace(&self, root_path: &Path) {
         self.scan_workspace_with_excludes(root_path, &[]);
     }
@@ -86,6 +136,12 @@ impl FixtureDatabase {
         info!("Scanning workspace: {:?}", root_path);
 
         *self.workspace_root.lock().unwrap() = Some(root_path.to_path_buf());
+        {
+            let mut roots = self.workspace_roots.lock().unwrap();
+            if !roots.iter().any(|root| root == root_path) {
+                roots.push(root_path.to_path_buf());
+            }
+        }
 
         if !root_path.exists() {
             warn!(
@@ -98,6 +154,7 @@ impl FixtureDatabase {
         // Phase 1: Collect all file paths (sequential, fast)
         let mut files_to_process: Vec<std::path::PathBuf> = Vec::new();
         let mut skipped_dirs = 0;
+        let pytest_options = self.pytest_options_for(root_path);
 
         // Use WalkDir with filter to skip large/irrelevant directories
         let walker = WalkDir::new(root_path).into_iter().filter_entry(|entry| {
@@ -158,12 +215,11 @@ impl FixtureDatabase {
                 }
             }
 
-            // Look for conftest.py or test_*.py or *_test.py files
+            // Look for conftest.py and test modules matching `python_files`,
+            // restricted to `testpaths` when configured
             if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-                if filename == "conftest.py"
-                    || filename.starts_with("test_") && filename.ends_with(".py")
-                    || filename.ends_with("_test.py")
-                {
+                let relative_path = path.strip_prefix(root_path).unwrap_or(path);
+                if Self::is_collected_file(path, filename, relative_path, &pytest_options) {
                     files_to_process.push(path.to_path_buf());
                 }
             }
@@ -177,15 +233,21 @@ impl FixtureDatabase {
         info!("Found {} test/conftest files to process", total_files);
 
         // Phase 2: Process files in parallel using rayon
-        // Use analyze_file_fresh since this is initial scan (no previous definitions to clean)
+        // Analyze fresh since this is initial scan (no previous definitions to clean);
+        // unchanged files are restored from the persistent index when enabled
         let error_count = AtomicUsize::new(0);
         let permission_denied_count = AtomicUsize::new(0);
 
         files_to_process.par_iter().for_each(|path| {
             debug!("Found test/conftest file: {:?}", path);
+            // Open documents are owned by the editor and may differ from disk
+            if self.document_versions.contains_key(path) {
+                debug!("Skipping open document: {:?}", path);
+                return;
+            }
             match std::fs::read_to_string(path) {
                 Ok(content) => {
-                    self.analyze_file_fresh(path.clone(), &content);
+                    self.analyze_scanned_file(path.clone(), &content, true);
                 }
                 Err(err) => {
                     if err.kind() == std::io::ErrorKind::PermissionDenied {
@@ -217,6 +279,9 @@ impl FixtureDatabase {
             total_files, permission_errors, errors
         );
 
+        // Phase 2b: Scan the configured `fixture_paths` directories
+        self.scan_fixture_paths(root_path);
+
         // Phase 3: Scan virtual environment for pytest plugins first
         // (must happen before import scanning so venv plugin files are in file_cache)
         self.scan_venv_fixtures(root_path);
@@ -259,12 +324,7 @@ impl FixtureDatabase {
                 let is_conftest_or_test = key
                     .file_name()
                     .and_then(|n| n.to_str())
-                    .map(|n| {
-                        n == "conftest.py"
-                            || (n.starts_with("test_") && n.ends_with(".py"))
-                            || n.ends_with("_test.py")
-                    })
-                    .unwrap_or(false);
+                    .is_some_and(|n| n == "conftest.py" || self.is_test_file_path(key));
                 let is_venv_plugin = site_packages_paths.iter().any(|sp| key.starts_with(sp));
                 let is_editable_plugin = editable_roots.iter().any(|er| key.starts_with(er));
                 let is_entry_point_plugin = self.plugin_fixture_files.contains_key(key);
@@ -315,77 +375,64 @@ impl FixtureDatabase {
                     continue;
                 };
 
-                // Parse the AST
-                let Some(parsed) = self.get_parsed_ast(file_path, &content) else {
+                // Extract imports and pytest_plugins (from the persistent index when possible)
+                let Some(references) = self.fixture_module_references(file_path, &content) else {
                     continue;
                 };
 
-                let line_index = self.get_line_index(file_path, &content);
+                for (module_path, is_star_import) in &references.imports {
+                    if let Some(resolved_path) = self.resolve_module_to_file(module_path, file_path)
+                    {
+                        let canonical = self.get_canonical_path(resolved_path);
 
-                // Extract imports and pytest_plugins
-                if let rustpython_parser::ast::Mod::Module(module) = parsed.as_ref() {
-                    let imports =
-                        self.extract_fixture_imports(&module.body, file_path, &line_index);
+                        // Only star imports propagate plugin status to the
+                        // whole imported module.  Explicit `from X import Y`
+                        // should not mark the entire module as a plugin
+                        // because only specific names are being pulled in.
+                        let should_mark_plugin = importer_is_plugin && *is_star_import;
 
-                    for import in imports {
-                        if let Some(resolved_path) =
-                            self.resolve_module_to_file(&import.module_path, file_path)
+                        if should_mark_plugin && !self.plugin_fixture_files.contains_key(&canonical)
                         {
-                            let canonical = self.get_canonical_path(resolved_path);
-
-                            // Only star imports propagate plugin status to the
-                            // whole imported module.  Explicit `from X import Y`
-                            // should not mark the entire module as a plugin
-                            // because only specific names are being pulled in.
-                            let should_mark_plugin = importer_is_plugin && import.is_star_import;
-
-                            if should_mark_plugin
-                                && !self.plugin_fixture_files.contains_key(&canonical)
-                            {
-                                self.plugin_fixture_files.insert(canonical.clone(), ());
-                                // If already cached, we need to re-analyze so
-                                // existing definitions get is_plugin=true.
-                                if self.file_cache.contains_key(&canonical) {
-                                    reanalyze_as_plugin.insert(canonical.clone());
-                                }
+                            self.plugin_fixture_files.insert(canonical.clone(), ());
+                            // If already cached, we need to re-analyze so
+                            // existing definitions get is_plugin=true.
+                            if self.file_cache.contains_key(&canonical) {
+                                reanalyze_as_plugin.insert(canonical.clone());
                             }
+                        }
 
-                            if !processed_files.contains(&canonical)
-                                && !self.file_cache.contains_key(&canonical)
-                            {
-                                new_modules.insert(canonical);
-                            }
+                        if !processed_files.contains(&canonical)
+                            && !self.file_cache.contains_key(&canonical)
+                        {
+                            new_modules.insert(canonical);
                         }
                     }
+                }
 
-                    // Also extract pytest_plugins variable declarations.
-                    // pytest_plugins is an explicit pytest mechanism for
-                    // declaring plugin modules, so the entire referenced module
-                    // should always be marked as a plugin when the importer is
-                    // a plugin file.
-                    let plugin_modules = self.extract_pytest_plugins(&module.body);
-                    for module_path in plugin_modules {
-                        if let Some(resolved_path) =
-                            self.resolve_module_to_file(&module_path, file_path)
+                // Also handle pytest_plugins variable declarations.
+                // pytest_plugins is an explicit pytest mechanism for
+                // declaring plugin modules, so the entire referenced module
+                // should always be marked as a plugin when the importer is
+                // a plugin file.
+                for module_path in &references.pytest_plugins {
+                    if let Some(resolved_path) = self.resolve_module_to_file(module_path, file_path)
+                    {
+                        let canonical = self.get_canonical_path(resolved_path);
+
+                        if importer_is_plugin && !self.plugin_fixture_files.contains_key(&canonical)
                         {
-                            let canonical = self.get_canonical_path(resolved_path);
-
-                            if importer_is_plugin
-                                && !self.plugin_fixture_files.contains_key(&canonical)
-                            {
-                                self.plugin_fixture_files.insert(canonical.clone(), ());
-                                // If already cached, we need to re-analyze so
-                                // existing definitions get is_plugin=true.
-                                if self.file_cache.contains_key(&canonical) {
-                                    reanalyze_as_plugin.insert(canonical.clone());
-                                }
+                            self.plugin_fixture_files.insert(canonical.clone(), ());
+                            // If already cached, we need to re-analyze so
+                            // existing definitions get is_plugin=true.
+                            if self.file_cache.contains_key(&canonical) {
+                                reanalyze_as_plugin.insert(canonical.clone());
                             }
+                        }
 
-                            if !processed_files.contains(&canonical)
-                                && !self.file_cache.contains_key(&canonical)
-                            {
-                                new_modules.insert(canonical);
-                            }
+                        if !processed_files.contains(&canonical)
+                            && !self.file_cache.contains_key(&canonical)
+                        {
+                            new_modules.insert(canonical);
                         }
                     }
                 }
@@ -408,7 +455,7 @@ impl FixtureDatabase {
                     debug!("Analyzing imported module: {:?}", module_path);
                     match std::fs::read_to_string(module_path) {
                         Ok(content) => {
-                            self.analyze_file_fresh(module_path.clone(), &content);
+                            self.analyze_scanned_file(module_path.clone(), &content, true);
                         }
                         Err(err) => {
                             debug!("Failed to read imported module {:?}: {}", module_path, err);
@@ -445,49 +492,101 @@ impl FixtureDatabase {
         );
     }
 
+    /// Analyze every Python file in the `fixture_paths` directories of a root.
+    /// Their fixtures are visible to every test, like plugin fixtures.
+    fn scan_fixture_paths(&self, root_path: &Path) {
+        let dirs = self
+            .root_fixture_paths
+            .read()
+            .unwrap()
+            .iter()
+            .find(|(root, _)| root == root_path)
+            .map(|(_, dirs)| dirs.clone())
+            .unwrap_or_default();
+
+        for dir in dirs {
+            info!("Scanning fixture path: {:?}", dir);
+            let walker = WalkDir::new(&dir).into_iter().filter_entry(|entry| {
+                entry.file_type().is_file()
+                    || entry
+                        .file_name()
+                        .to_str()
+                        .is_none_or(|name| !Self::should_skip_directory(name))
+            });
+            for entry in walker.flatten() {
+                let path = entry.path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some("py")
+                    || self.document_versions.contains_key(path)
+                {
+                    continue;
+                }
+                // Re-analyzed even when the workspace scan already restored the
+                // file, since the persisted definitions predate the setting
+                match std::fs::read_to_string(path) {
+                    Ok(content) => self.analyze_file(path.to_path_buf(), &content),
+                    Err(err) => debug!("Failed to read fixture path file {:?}: {}", path, err),
+                }
+            }
+        }
+    }
+
     /// Scan virtual environment for pytest plugin fixtures.
     fn scan_venv_fixtures(&self, root_path: &Path) {
         info!("Scanning for pytest plugins in virtual environment");
 
-        // Try to find virtual environment
-        let venv_paths = vec![
-            root_path.join(".venv"),
-            root_path.join("venv"),
-            root_path.join("env"),
-        ];
+        let settings = self
+            .root_venv_settings
+            .read()
+            .unwrap()
+            .iter()
+            .find(|(root, _)| root == root_path)
+            .map(|(_, settings)| settings.clone())
+            .unwrap_or_default();
 
         info!("Checking for venv in: {:?}", root_path);
-        for venv_path in &venv_paths {
-            debug!("Checking venv path: {:?}", venv_path);
-            if venv_path.exists() {
-                info!("Found virtual environment at: {:?}", venv_path);
-                self.scan_venv_site_packages(venv_path);
-                return;
-            } else {
-                debug!("  Does not exist: {:?}", venv_path);
+        match venv::discover_venv(root_path, &settings) {
+            Some(venv) => {
+                info!(
+                    "Found virtual environment at: {:?} (via {})",
+                    venv.path,
+                    venv.strategy.as_str()
+                );
+                let site_packages = self.scan_venv_site_packages(&venv.path, &settings);
+                self.set_root_site_packages(root_path, site_packages);
+            }
+            None => {
+                warn!("No virtual environment found - third-party fixtures will not be available");
+                self.set_root_site_packages(root_path, None);
             }
         }
+    }
 
-        // Also check for system-wide VIRTUAL_ENV
-        if let Ok(venv) = std::env::var("VIRTUAL_ENV") {
-            info!("Found VIRTUAL_ENV environment variable: {}", venv);
-            let venv_path = std::path::PathBuf::from(venv);
-            if venv_path.exists() {
-                let venv_path = venv_path.canonicalize().unwrap_or(venv_path);
-                info!("Using VIRTUAL_ENV: {:?}", venv_path);
-                self.scan_venv_site_packages(&venv_path);
-                return;
-            } else {
-                warn!("VIRTUAL_ENV path does not exist: {:?}", venv_path);
-            }
-        } else {
-            debug!("No VIRTUAL_ENV environment variable set");
+    /// Record which site-packages directory provides the plugin and
+    /// third-party fixtures of a workspace root.
+    fn set_root_site_packages(&self, root_path: &Path, site_packages: Option<PathBuf>) {
+        let mut roots = self.root_site_packages.write().unwrap();
+        roots.retain(|(root, _)| root != root_path);
+        if let Some(site_packages) = site_packages {
+            roots.push((root_path.to_path_buf(), site_packages));
         }
+    }
 
-        warn!("No virtual environment found - third-party fixtures will not be available");
+    /// Remember a discovered site-packages directory. Roots sharing a
+    /// virtual environment only record it once.
+    fn add_site_packages_path(&self, site_packages: &Path) {
+        let mut paths = self.site_packages_paths.lock().unwrap();
+        if !paths.iter().any(|path| path == site_packages) {
+            paths.push(site_packages.to_path_buf());
+        }
     }
 
-    fn scan_venv_site_packages(&self, venv_path: &Path) {
+    /// Scan the site-packages directory of a virtual environment for plugins.
+    /// Returns the site-packages directory that was scanned, if found.
+    fn scan_venv_site_packages(
+        &self,
+        venv_path: &Path,
+        settings: &venv::VenvSettings,
+    ) -> Option<PathBuf> {
         info!("Scanning venv site-packages in: {:?}", venv_path);
 
         // Attempt to replace the built-in stdlib list with the authoritative set
@@ -524,12 +623,9 @@ impl FixtureDatabase {
                             let site_packages =
                                 site_packages.canonicalize().unwrap_or(site_packages);
                             info!("Found site-packages: {:?}", site_packages);
-                            self.site_packages_paths
-                                .lock()
-                                .unwrap()
-                                .push(site_packages.clone());
-                            self.scan_pytest_plugins(&site_packages);
-                            return;
+                            self.add_site_packages_path(&site_packages);
+                            self.scan_pytest_plugins(&site_packages, settings);
+                            return Some(site_packages);
                         }
                     }
                 }
@@ -544,15 +640,13 @@ impl FixtureDatabase {
                 .canonicalize()
                 .unwrap_or(windows_site_packages);
             info!("Found site-packages (Windows): {:?}", windows_site_packages);
-            self.site_packages_paths
-                .lock()
-                .unwrap()
-                .push(windows_site_packages.clone());
-            self.scan_pytest_plugins(&windows_site_packages);
-            return;
+            self.add_site_packages_path(&windows_site_packages);
+            self.scan_pytest_plugins(&windows_site_packages, settings);
+            return Some(windows_site_packages);
         }
 
         warn!("Could not find site-packages in venv: {:?}", venv_path);
+        None
     }
 
     /// Parse `entry_points.txt` content and extract pytest11 entries.
@@ -664,17 +758,24 @@ impl FixtureDatabase {
         self.plugin_fixture_files.insert(canonical, ());
 
         if let Ok(content) = std::fs::read_to_string(file_path) {
-            self.analyze_file(file_path.to_path_buf(), &content);
+            self.analyze_scanned_file(file_path.to_path_buf(), &content, false);
         }
     }
 
     /// Load pytest plugins from a single dist-info directory's entry points.
     ///
     /// Reads entry_points.txt, parses [pytest11] section, resolves modules,
-    /// and scans discovered plugin files for fixtures.
+    /// and scans discovered plugin files for fixtures. Entry points skipped by
+    /// `settings` (by entry point or distribution name) are not loaded, and
+    /// fixtures loaded from them by an earlier scan are forgotten.
     ///
     /// Returns the number of plugin modules scanned.
-    fn load_plugin_from_entry_point(&self, dist_info_path: &Path, site_packages: &Path) -> usize {
+    fn load_plugin_from_entry_point(
+        &self,
+        dist_info_path: &Path,
+        site_packages: &Path,
+        settings: &venv::VenvSettings,
+    ) -> usize {
         let entry_points_file = dist_info_path.join("entry_points.txt");
 
         let content = match std::fs::read_to_string(&entry_points_file) {
@@ -688,6 +789,12 @@ impl FixtureDatabase {
             return 0; // No pytest11 plugins in this package
         }
 
+        let distribution = dist_info_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(Self::extract_package_name_from_dist_info)
+            .map(|(raw, _)| raw);
+
         let mut scanned_count = 0;
 
         for entry in entries {
@@ -700,6 +807,17 @@ impl FixtureDatabase {
                 Self::resolve_entry_point_module_to_path(site_packages, &entry.module_path)
                     .or_else(|| self.resolve_entry_point_in_editable_installs(&entry.module_path));
 
+            if settings.skips_plugin(&entry.name, distribution.as_deref()) {
+                info!(
+                    "Skipping pytest plugin {} from {:?} (skip_plugins or -p no:)",
+                    entry.name, distribution
+                );
+                if let Some(path) = resolved {
+                    self.forget_plugin_module(&path);
+                }
+                continue;
+            }
+
             if let Some(path) = resolved {
                 let scanned = if path.file_name().and_then(|n| n.to_str()) == Some("__init__.py") {
                     let package_dir = path.parent().expect("__init__.py must have parent");
@@ -735,9 +853,36 @@ impl FixtureDatabase {
         scanned_count
     }
 
+    /// Forget the fixtures of a plugin module (a `.py` file, or the package of
+    /// an `__init__.py`) that is no longer loaded.
+    fn forget_plugin_module(&self, module_path: &Path) {
+        let module = if module_path.file_name().and_then(|n| n.to_str()) == Some("__init__.py") {
+            module_path.parent().unwrap_or(module_path)
+        } else {
+            module_path
+        };
+        let module = module
+            .canonicalize()
+            .unwrap_or_else(|_| module.to_path_buf());
+
+        let files: Vec<PathBuf> = self
+            .file_definitions
+            .iter()
+            .map(|entry| entry.key().clone())
+            .filter(|file| file.starts_with(&module))
+            .collect();
+        for file in &files {
+            debug!("Forgetting fixtures of skipped plugin file {:?}", file);
+            self.plugin_fixture_files.remove(file);
+            self.forget_file(file);
+        }
+    }
+
     /// Scan pytest's internal _pytest package for built-in fixtures.
     /// This handles fixtures like tmp_path, capsys, monkeypatch, etc.
-    fn scan_pytest_internal_fixtures(&self, site_packages: &Path) {
+    /// Built-in plugins disabled with `-p no:name` (e.g. `cacheprovider`) are
+    /// left out.
+    fn scan_pytest_internal_fixtures(&self, site_packages: &Path, settings: &venv::VenvSettings) {
         let pytest_internal = site_packages.join("_pytest");
 
         if !pytest_internal.exists() || !pytest_internal.is_dir() {
@@ -750,6 +895,16 @@ impl FixtureDatabase {
             pytest_internal
         );
         self.scan_plugin_directory(&pytest_internal);
+        for skipped in &settings.skip_plugins {
+            let module = pytest_internal.join(format!("{}.py", skipped));
+            let is_module_name = skipped
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if is_module_name && skipped != "fixtures" && module.is_file() {
+                info!("Skipping built-in pytest plugin {}", skipped);
+                self.forget_plugin_module(&module);
+            }
+        }
 
         // `request` is not defined via @pytest.fixture anywhere in _pytest/ —
         // pytest injects it programmatically via FixtureManager.  Register a
@@ -822,6 +977,10 @@ impl FixtureDatabase {
             scope: FixtureScope::Function,
             yield_line: None,
             autouse: false,
+            params: vec![],
+            param_ids: vec![],
+            class_name: None,
+            fixture_path: None,
         };
 
         info!("Registering synthetic 'request' fixture definition");
@@ -867,8 +1026,12 @@ impl FixtureDatabase {
             return;
         }
 
-        // Clear previous editable installs to avoid duplicates on re-scan
-        self.editable_install_roots.lock().unwrap().clear();
+        // Drop editable installs previously found in this site-packages to avoid
+        // duplicates on re-scan; other roots' environments are kept
+        self.editable_install_roots
+            .lock()
+            .unwrap()
+            .retain(|install| install.site_packages != site_packages);
 
         // Index all .pth files once (stem → full path) to avoid re-reading site-packages per package
         let pth_index = Self::build_pth_index(site_packages);
@@ -1065,7 +1228,7 @@ impl FixtureDatabase {
         None
     }
 
-    fn scan_pytest_plugins(&self, site_packages: &Path) {
+    fn scan_pytest_plugins(&self, site_packages: &Path, settings: &venv::VenvSettings) {
         info!(
             "Scanning for pytest plugins via entry points in: {:?}",
             site_packages
@@ -1077,7 +1240,7 @@ impl FixtureDatabase {
         let mut plugin_count = 0;
 
         // First, scan pytest's internal fixtures (special case)
-        self.scan_pytest_internal_fixtures(site_packages);
+        self.scan_pytest_internal_fixtures(site_packages, settings);
 
         // Iterate over ALL dist-info directories and check for pytest11 entry points
         for entry in std::fs::read_dir(site_packages).into_iter().flatten() {
@@ -1095,7 +1258,7 @@ impl FixtureDatabase {
             }
 
             // Try to load plugins from this package's entry points
-            let scanned = self.load_plugin_from_entry_point(&path, site_packages);
+            let scanned = self.load_plugin_from_entry_point(&path, site_packages, settings);
             if scanned > 0 {
                 plugin_count += scanned;
                 debug!("Loaded {} plugin module(s) from {}", scanned, filename);
@@ -1132,7 +1295,7 @@ impl FixtureDatabase {
                     self.plugin_fixture_files.insert(canonical, ());
 
                     if let Ok(content) = std::fs::read_to_string(path) {
-                        self.analyze_file(path.to_path_buf(), &content);
+                        self.analyze_scanned_file(path.to_path_buf(), &content, false);
                     }
                 }
             }
@@ -1444,7 +1607,7 @@ def another_dynamic_fixture():
 
         // Scan and verify
         let db = FixtureDatabase::new();
-        db.scan_pytest_plugins(site_packages);
+        db.scan_pytest_plugins(site_packages, &venv::VenvSettings::default());
 
         assert!(
             db.definitions.contains_key("my_dynamic_fixture"),
@@ -1484,7 +1647,7 @@ def submodule_fixture():
 
         // Scan and verify
         let db = FixtureDatabase::new();
-        db.scan_pytest_plugins(site_packages);
+        db.scan_pytest_plugins(site_packages, &venv::VenvSettings::default());
 
         assert!(
             db.definitions.contains_key("submodule_fixture"),
@@ -1520,7 +1683,7 @@ def package_submodule_fixture():
 
         // Scan and verify submodule fixtures are discovered
         let db = FixtureDatabase::new();
-        db.scan_pytest_plugins(site_packages);
+        db.scan_pytest_plugins(site_packages, &venv::VenvSettings::default());
 
         assert!(
             db.definitions.contains_key("package_submodule_fixture"),
@@ -1555,7 +1718,7 @@ def should_not_be_found():
 
         // Scan and verify
         let db = FixtureDatabase::new();
-        db.scan_pytest_plugins(site_packages);
+        db.scan_pytest_plugins(site_packages, &venv::VenvSettings::default());
 
         assert!(
             !db.definitions.contains_key("should_not_be_found"),
@@ -1588,7 +1751,7 @@ def should_not_be_found():
 
         // Scan and verify
         let db = FixtureDatabase::new();
-        db.scan_pytest_plugins(site_packages);
+        db.scan_pytest_plugins(site_packages, &venv::VenvSettings::default());
 
         assert!(
             !db.definitions.contains_key("should_not_be_found"),
@@ -1624,7 +1787,7 @@ def legacy_plugin_fixture():
 
         // Scan and verify
         let db = FixtureDatabase::new();
-        db.scan_pytest_plugins(site_packages);
+        db.scan_pytest_plugins(site_packages, &venv::VenvSettings::default());
 
         assert!(
             db.definitions.contains_key("legacy_plugin_fixture"),
@@ -1685,7 +1848,7 @@ def fixture_from_plugin_two():
 
         // Scan and verify both are discovered
         let db = FixtureDatabase::new();
-        db.scan_pytest_plugins(site_packages);
+        db.scan_pytest_plugins(site_packages, &venv::VenvSettings::default());
 
         assert!(
             db.definitions.contains_key("fixture_from_plugin_one"),
@@ -1745,7 +1908,7 @@ fixtures_b = multi_plugin.fixtures_b
 
         // Scan and verify both modules are scanned
         let db = FixtureDatabase::new();
-        db.scan_pytest_plugins(site_packages);
+        db.scan_pytest_plugins(site_packages, &venv::VenvSettings::default());
 
         assert!(
             db.definitions.contains_key("fixture_a"),
@@ -1757,6 +1920,85 @@ fixtures_b = multi_plugin.fixtures_b
         );
     }
 
+    #[test]
+    fn test_entry_point_discovery_honours_skip_plugins() {
+        let temp = tempdir().unwrap();
+        let site_packages = temp.path();
+
+        for (package, fixture) in [
+            ("django_plugin", "django_db"),
+            ("xdist_plugin", "worker_id"),
+        ] {
+            fs::create_dir_all(site_packages.join(package)).unwrap();
+            fs::write(
+                site_packages.join(package).join("__init__.py"),
+                format!(
+                    "import pytest\n\n@pytest.fixture\ndef {}():\n    pass\n",
+                    fixture
+                ),
+            )
+            .unwrap();
+        }
+        let django_info = site_packages.join("pytest_django-4.8.0.dist-info");
+        fs::create_dir_all(&django_info).unwrap();
+        fs::write(
+            django_info.join("entry_points.txt"),
+            "[pytest11]\ndjango = django_plugin\n",
+        )
+        .unwrap();
+        let xdist_info = site_packages.join("pytest_xdist-3.5.0.dist-info");
+        fs::create_dir_all(&xdist_info).unwrap();
+        fs::write(
+            xdist_info.join("entry_points.txt"),
+            "[pytest11]\nxdist.plugin = xdist_plugin\n",
+        )
+        .unwrap();
+
+        let db = FixtureDatabase::new();
+        db.scan_pytest_plugins(site_packages, &venv::VenvSettings::default());
+        assert!(db.definitions.contains_key("django_db"));
+        assert!(db.definitions.contains_key("worker_id"));
+
+        // By entry point name (`-p no:django`) and by distribution name;
+        // fixtures of the earlier scan are forgotten
+        let settings = venv::VenvSettings {
+            skip_plugins: vec!["django".to_string(), "pytest-xdist".to_string()],
+            ..Default::default()
+        };
+        db.scan_pytest_plugins(site_packages, &settings);
+        assert!(!db.definitions.contains_key("django_db"));
+        assert!(!db.definitions.contains_key("worker_id"));
+    }
+
+    #[test]
+    fn test_pytest_internal_fixtures_skip_disabled_builtin_plugins() {
+        let temp = tempdir().unwrap();
+        let site_packages = temp.path();
+        let pytest_internal = site_packages.join("_pytest");
+        fs::create_dir_all(&pytest_internal).unwrap();
+        fs::write(
+            pytest_internal.join("cacheprovider.py"),
+            "import pytest\n\n@pytest.fixture\ndef cache(request):\n    pass\n",
+        )
+        .unwrap();
+        fs::write(
+            pytest_internal.join("tmpdir.py"),
+            "import pytest\n\n@pytest.fixture\ndef tmp_path():\n    pass\n",
+        )
+        .unwrap();
+
+        let db = FixtureDatabase::new();
+        let settings = venv::VenvSettings {
+            skip_plugins: vec!["cacheprovider".to_string()],
+            ..Default::default()
+        };
+        db.scan_pytest_plugins(site_packages, &settings);
+
+        assert!(!db.definitions.contains_key("cache"));
+        assert!(db.definitions.contains_key("tmp_path"));
+        assert!(db.definitions.contains_key("request"));
+    }
+
     #[test]
     fn test_pytest_internal_fixtures_scanned() {
         let temp = tempdir().unwrap();
@@ -1783,7 +2025,7 @@ def capsys():
 
         // Scan and verify internal fixtures are discovered
         let db = FixtureDatabase::new();
-        db.scan_pytest_plugins(site_packages);
+        db.scan_pytest_plugins(site_packages, &venv::VenvSettings::default());
 
         // Note: We're checking that _pytest is scanned as a special case
         // even without entry points
@@ -2052,7 +2294,7 @@ def editable_fixture():
         .unwrap();
 
         let db = FixtureDatabase::new();
-        db.scan_pytest_plugins(site_packages);
+        db.scan_pytest_plugins(site_packages, &venv::VenvSettings::default());
 
         assert!(
             db.definitions.contains_key("editable_fixture"),
@@ -0,0 +1,444 @@
+//! Call context for signature help and call argument completion.
+//!
+//! Both are requested while a call is being typed, so the source usually
+//! does not parse yet. The enclosing call is found by scanning the text up to
+//! the cursor instead of walking the AST.
+
+use super::types::CompletionContext;
+use super::FixtureDatabase;
+use std::path::Path;
+
+/// The call whose arguments are being typed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureCallee {
+    /// `@pytest.fixture(...)`
+    Fixture,
+    /// `@pytest.mark.parametrize(...)`
+    Parametrize,
+    /// `@pytest.mark.usefixtures(...)`
+    Usefixtures,
+    /// `pytest.param(...)`
+    Param,
+    /// The parameter list of a test or fixture function.
+    Function {
+        name: String,
+        line: usize, // 1-based line of the `def`
+        is_fixture: bool,
+        /// Parameter names, in order.
+        params: Vec<String>,
+    },
+}
+
+/// Where the cursor is within a call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureContext {
+    pub callee: SignatureCallee,
+    /// Index of the argument under the cursor.
+    pub argument_index: usize,
+    /// Keyword of the argument under the cursor, once `name=` is typed.
+    pub keyword: Option<String>,
+}
+
+/// An unclosed call enclosing the cursor, found by [`find_enclosing_call`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct EnclosingCall {
+    /// Dotted name before the opening parenthesis, e.g. `pytest.mark.parametrize`.
+    pub callee: String,
+    /// Whether the parenthesis opens the parameter list of a `def`.
+    pub is_def: bool,
+    /// Byte offset of the opening parenthesis.
+    pub open_offset: usize,
+    pub argument_index: usize,
+    /// Text of the argument under the cursor, up to the cursor.
+    pub argument_prefix: String,
+}
+
+impl EnclosingCall {
+    /// The keyword of the argument under the cursor, once `name=` is typed.
+    pub fn keyword(&self) -> Option<String> {
+        let text = self.argument_prefix.trim_start();
+        let name_len = text
+            .find(|c: char| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(text.len());
+        let rest = text[name_len..].trim_start();
+        (name_len > 0 && rest.starts_with('=') && !rest.starts_with("=="))
+            .then(|| text[..name_len].to_string())
+    }
+}
+
+impl FixtureDatabase {
+    /// Get the signature help context at a position (0-based line and
+    /// character), or `None` outside a pytest call or test/fixture signature.
+    pub fn get_signature_context(
+        &self,
+        file_path: &Path,
+        line: u32,
+        character: u32,
+    ) -> Option<SignatureContext> {
+        let content = self.get_file_content(file_path)?;
+        let call = find_enclosing_call(&content, line as usize, character as usize)?;
+        let keyword = call.keyword();
+
+        let callee = if call.is_def {
+            let def_line_idx = content[..call.open_offset].matches('\n').count();
+            let lines: Vec<&str> = content.lines().collect();
+            let is_fixture = Self::has_fixture_decorator_above(&lines, def_line_idx);
+            if !is_fixture && !self.is_test_function_name(file_path, &call.callee) {
+                return None;
+            }
+            SignatureCallee::Function {
+                name: call.callee.clone(),
+                line: def_line_idx + 1,
+                is_fixture,
+                params: parameter_names(&content[call.open_offset + 1..]),
+            }
+        } else {
+            match call.callee.as_str() {
+                "pytest.fixture" | "fixture" => SignatureCallee::Fixture,
+                "pytest.mark.parametrize" | "mark.parametrize" => SignatureCallee::Parametrize,
+                "pytest.mark.usefixtures" | "mark.usefixtures" => SignatureCallee::Usefixtures,
+                "pytest.param" => SignatureCallee::Param,
+                _ => return None,
+            }
+        };
+
+        Some(SignatureContext {
+            callee,
+            argument_index: call.argument_index,
+            keyword,
+        })
+    }
+}
+
+impl FixtureDatabase {
+    /// Completion context for the value of `scope=` / `autouse=` in
+    /// `@pytest.fixture(...)`, for `indirect=` and the argnames string of
+    /// `@pytest.mark.parametrize(...)`, and for the fixture name of
+    /// `request.getfixturevalue(...)`. Positions are 0-based.
+    pub(crate) fn get_call_argument_context(
+        content: &str,
+        line: u32,
+        character: u32,
+    ) -> Option<CompletionContext> {
+        let call = find_enclosing_call(content, line as usize, character as usize)?;
+        let argument = scan(&call.argument_prefix);
+        let in_string = argument.open_string.is_some();
+        // Only bare words (or an opening quote for scope) may follow `keyword=`
+        let value = call
+            .argument_prefix
+            .split_once('=')
+            .map(|(_, value)| value.trim_start());
+        let is_bare_value =
+            value.is_some_and(|value| value.chars().all(|c| c.is_alphanumeric() || c == '_'));
+
+        match (call.callee.as_str(), call.keyword().as_deref()) {
+            ("pytest.fixture" | "fixture", Some("scope")) if in_string || is_bare_value => {
+                Some(CompletionContext::FixtureScopeValue {
+                    in_string,
+                    callables: if in_string {
+                        vec![]
+                    } else {
+                        dynamic_scope_callables(content)
+                    },
+                })
+            }
+            ("pytest.fixture" | "fixture", Some("autouse")) if is_bare_value => {
+                Some(CompletionContext::FixtureAutouseValue)
+            }
+            ("pytest.mark.parametrize" | "mark.parametrize", Some("indirect")) if is_bare_value => {
+                Some(CompletionContext::ParametrizeIndirectValue {
+                    argnames: parametrize_argnames(&content[call.open_offset + 1..]),
+                })
+            }
+            ("pytest.mark.parametrize" | "mark.parametrize", None)
+                if call.argument_index == 0 && in_string =>
+            {
+                let cursor = call.open_offset + 1 + call.argument_prefix.len();
+                let (function_name, function_line, params) = decorated_function(content, cursor)?;
+                let listed: Vec<String> = argument
+                    .strings
+                    .iter()
+                    .map(|range| &call.argument_prefix[range.clone()])
+                    .chain(
+                        argument
+                            .open_string
+                            .map(|start| &call.argument_prefix[start..]),
+                    )
+                    .flat_map(|text| text.split(','))
+                    .map(|name| name.trim().to_string())
+                    .collect();
+                let params = params
+                    .into_iter()
+                    .filter(|param| param != "self" && param != "cls" && !listed.contains(param))
+                    .collect();
+                Some(CompletionContext::ParametrizeArgnames {
+                    function_name,
+                    function_line,
+                    params,
+                })
+            }
+            (callee, None)
+                if callee.ends_with(".getfixturevalue")
+                    && call.argument_index == 0
+                    && in_string =>
+            {
+                Some(CompletionContext::FixtureValueLookup)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Argnames of a parametrize call, given the text after its opening
+/// parenthesis: a comma-separated string or a list/tuple of strings.
+fn parametrize_argnames(text: &str) -> Vec<String> {
+    let scanned = scan(text);
+    // The first argument ends at the first comma outside brackets
+    let mut depth = 0usize;
+    let mut end = text.len();
+    for &(offset, c) in &scanned.code {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' if depth > 0 => depth -= 1,
+            ',' | ')' if depth == 0 => {
+                end = offset;
+                break;
+            }
+            _ => {}
+        }
+    }
+    scanned
+        .strings
+        .iter()
+        .filter(|range| range.end <= end)
+        .flat_map(|range| text[range.clone()].split(','))
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// The first function defined after `offset`: its name, 1-based line and
+/// parameter names.
+fn decorated_function(content: &str, offset: usize) -> Option<(String, usize, Vec<String>)> {
+    let mut line_start = content[..offset].rfind('\n').map_or(0, |i| i + 1);
+    while line_start < content.len() {
+        let line_end = content[line_start..]
+            .find('\n')
+            .map_or(content.len(), |i| line_start + i);
+        let line = content[line_start..line_end].trim_start();
+        let def = line
+            .strip_prefix("async ")
+            .unwrap_or(line)
+            .strip_prefix("def ");
+        if let Some(rest) = def {
+            let (name, _) = rest.split_once('(')?;
+            let open = line_start + content[line_start..line_end].find('(')?;
+            return Some((
+                name.trim().to_string(),
+                content[..line_start].matches('\n').count() + 1,
+                parameter_names(&content[open + 1..]),
+            ));
+        }
+        line_start = line_end + 1;
+    }
+    None
+}
+
+/// Module-level functions usable as a dynamic fixture scope, i.e. taking
+/// `(fixture_name, config)`.
+fn dynamic_scope_callables(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|line| line.strip_prefix("def "))
+        .filter_map(|rest| {
+            let (name, params) = rest.split_once('(')?;
+            (parameter_names(params) == ["fixture_name", "config"]).then(|| name.trim().to_string())
+        })
+        .collect()
+}
+
+/// An open bracket while scanning.
+struct Frame {
+    bracket: char,
+    offset: usize,
+    commas: usize,
+    argument_start: usize,
+}
+
+/// Find the innermost unclosed `(` before the cursor (0-based line and
+/// character). Brackets inside strings and comments are ignored.
+pub(crate) fn find_enclosing_call(
+    content: &str,
+    line: usize,
+    character: usize,
+) -> Option<EnclosingCall> {
+    let cursor = byte_offset(content, line, character)?;
+    let prefix = &content[..cursor];
+
+    let mut frames: Vec<Frame> = Vec::new();
+    for (offset, c) in code_chars(prefix) {
+        match c {
+            '(' | '[' | '{' => frames.push(Frame {
+                bracket: c,
+                offset,
+                commas: 0,
+                argument_start: offset + 1,
+            }),
+            ')' | ']' | '}' => {
+                frames.pop();
+            }
+            ',' => {
+                if let Some(frame) = frames.last_mut() {
+                    frame.commas += 1;
+                    frame.argument_start = offset + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Commas inside a nested list or dict belong to that bracket, so the
+    // call's own frame still knows which argument the cursor is in
+    let frame = frames.iter().rev().find(|frame| frame.bracket == '(')?;
+
+    let before = prefix[..frame.offset].trim_end();
+    let callee_start = before
+        .rfind(|c: char| !c.is_alphanumeric() && c != '_' && c != '.')
+        .map_or(0, |i| i + 1);
+    let callee = &before[callee_start..];
+    if callee.is_empty() {
+        return None;
+    }
+    let is_def = before[..callee_start]
+        .trim_end()
+        .rsplit(|c: char| c.is_whitespace())
+        .next()
+        .is_some_and(|word| word == "def");
+
+    Some(EnclosingCall {
+        callee: callee.to_string(),
+        is_def,
+        open_offset: frame.offset,
+        argument_index: frame.commas,
+        argument_prefix: prefix[frame.argument_start..].to_string(),
+    })
+}
+
+/// Byte offset of a 0-based line and character, clamped to the line's end.
+fn byte_offset(content: &str, line: usize, character: usize) -> Option<usize> {
+    let mut line_start = 0;
+    for _ in 0..line {
+        line_start += content[line_start..].find('\n')? + 1;
+    }
+    let line_text = content[line_start..].split('\n').next().unwrap_or("");
+    let column = line_text
+        .char_indices()
+        .nth(character)
+        .map_or(line_text.len(), |(i, _)| i);
+    Some(line_start + column)
+}
+
+/// The result of scanning Python source for code, strings and comments.
+struct Scan {
+    /// Characters outside string literals and comments, with their byte offsets.
+    code: Vec<(usize, char)>,
+    /// Byte ranges of the contents of complete string literals.
+    strings: Vec<std::ops::Range<usize>>,
+    /// Start of the contents of a string literal still open at the end.
+    open_string: Option<usize>,
+}
+
+fn scan(text: &str) -> Scan {
+    let mut result = Scan {
+        code: Vec::new(),
+        strings: Vec::new(),
+        open_string: None,
+    };
+    let mut iter = text.char_indices().peekable();
+    while let Some((offset, c)) = iter.next() {
+        match c {
+            '#' => while iter.next_if(|&(_, c)| c != '\n').is_some() {},
+            '"' | '\'' => {
+                let triple = text[offset..].starts_with(&format!("{c}{c}{c}"));
+                let quote_len = if triple { 3 } else { 1 };
+                if triple {
+                    iter.next();
+                    iter.next();
+                }
+                let content_start = offset + quote_len;
+                let mut run = 0;
+                let mut content_end = None;
+                while let Some((i, s)) = iter.next() {
+                    if s == '\\' {
+                        iter.next();
+                        run = 0;
+                    } else if s == c {
+                        run += 1;
+                        if run == quote_len {
+                            content_end = Some(i + 1 - quote_len);
+                            break;
+                        }
+                    } else if s == '\n' && !triple {
+                        content_end = Some(i);
+                        break;
+                    } else {
+                        run = 0;
+                    }
+                }
+                match content_end {
+                    Some(end) => result.strings.push(content_start..end),
+                    None => result.open_string = Some(content_start.min(text.len())),
+                }
+            }
+            _ => result.code.push((offset, c)),
+        }
+    }
+    result
+}
+
+/// Characters outside string literals and comments, with their byte offsets.
+fn code_chars(text: &str) -> Vec<(usize, char)> {
+    scan(text).code
+}
+
+/// Names of the parameters in a `def` parameter list, given the text after
+/// its opening parenthesis. Stops at the closing parenthesis, or at the end
+/// of a line that does not continue the list while it is still being typed.
+fn parameter_names(text: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut depth = 0usize;
+    let mut argument_start = 0;
+    let mut push_name = |argument: &str| {
+        let name = argument
+            .trim()
+            .trim_start_matches('*')
+            .split([':', '='])
+            .next()
+            .unwrap_or("")
+            .trim();
+        if !name.is_empty() && name != "/" {
+            names.push(name.to_string());
+        }
+    };
+    for (offset, c) in code_chars(text) {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' if depth > 0 => depth -= 1,
+            ')' => {
+                push_name(&text[argument_start..offset]);
+                return names;
+            }
+            ',' if depth == 0 => {
+                push_name(&text[argument_start..offset]);
+                argument_start = offset + 1;
+            }
+            '\n' if depth == 0 && !text[argument_start..offset].trim().is_empty() => {
+                push_name(&text[argument_start..offset]);
+                return names;
+            }
+            _ => {}
+        }
+    }
+    push_name(&text[argument_start..]);
+    names
+}
@@ -229,10 +229,72 @@ pub(crate) fn replace_identifier(text: &str, old: &str, new: &str) -> String {
     result
 }
 
+/// Compute the Levenshtein edit distance between two strings (by `char`).
+///
+/// Used to suggest the closest-named fixtures for an unknown fixture name.
+pub(crate) fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b_chars.len()).collect();
+    let mut curr = vec![0; b_chars.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b_chars.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            curr[j + 1] = (prev[j + 1] + 1).min(curr[j] + 1).min(prev[j] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b_chars.len()]
+}
+
+/// Convert a 0-based line and UTF-16 code unit offset into a byte offset in `text`.
+///
+/// Positions past the end of a line clamp to the end of that line (before its
+/// line terminator), and lines past the end of the text clamp to its end.
+pub(crate) fn utf16_position_to_offset(text: &str, line: u32, character: u32) -> usize {
+    let mut line_start = 0;
+    for _ in 0..line {
+        match text[line_start..].find('\n') {
+            Some(newline) => line_start += newline + 1,
+            None => return text.len(),
+        }
+    }
+
+    let line_text = &text[line_start..];
+    let line_text = line_text
+        .find('\n')
+        .map_or(line_text, |newline| &line_text[..newline]);
+    let line_text = line_text.strip_suffix('\r').unwrap_or(line_text);
+
+    let mut units = 0;
+    for (byte_idx, ch) in line_text.char_indices() {
+        if units >= character as usize {
+            return line_start + byte_idx;
+        }
+        units += ch.len_utf16();
+    }
+    line_start + line_text.len()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_utf16_position_to_offset() {
+        let text = "a = 1\r\nb = \"é😀x\"\nc";
+        assert_eq!(utf16_position_to_offset(text, 0, 2), 2);
+        // Past the end of the line clamps before "\r\n"
+        assert_eq!(utf16_position_to_offset(text, 0, 99), 5);
+        // "é" is one UTF-16 unit (2 bytes), "😀" is two units (4 bytes)
+        assert_eq!(utf16_position_to_offset(text, 1, 6), 7 + 7);
+        assert_eq!(utf16_position_to_offset(text, 1, 8), 7 + 11);
+        assert_eq!(utf16_position_to_offset(text, 2, 1), text.len());
+        assert_eq!(utf16_position_to_offset(text, 5, 0), text.len());
+    }
+
     #[test]
     fn test_format_docstring_simple() {
         let input = "Simple docstring".to_string();
@@ -411,4 +473,13 @@ mod tests {
             "pathlib.Path"
         );
     }
+
+    #[test]
+    fn test_levenshtein_distance() {
+        assert_eq!(levenshtein_distance("database", "database"), 0);
+        assert_eq!(levenshtein_distance("databse", "database"), 1);
+        assert_eq!(levenshtein_distance("kitten", "sitting"), 3);
+        assert_eq!(levenshtein_distance("", "abc"), 3);
+        assert_eq!(levenshtein_distance("abc", ""), 3);
+    }
 }
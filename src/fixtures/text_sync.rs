@@ -0,0 +1,165 @@
+//! Incremental document synchronization.
+//!
+//! Applies the range edits sent by the client (`TextDocumentSyncKind::INCREMENTAL`)
+//! to the content cached in `file_cache`, so that only the changed text has to
+//! travel over the wire on every keystroke.
+
+use super::string_utils::utf16_position_to_offset;
+use super::types::ContentChange;
+use super::FixtureDatabase;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// Apply a sequence of content changes to `content`, in order.
+///
+/// Each change's range refers to the document as it is after the previous
+/// changes have been applied, as specified by LSP.
+pub(crate) fn apply_content_changes(content: &str, changes: &[ContentChange]) -> String {
+    let mut result = content.to_string();
+
+    for change in changes {
+        match change.range {
+            Some((start, end)) => {
+                let start_offset = utf16_position_to_offset(&result, start.line, start.character);
+                let end_offset = utf16_position_to_offset(&result, end.line, end.character);
+                // Tolerate reversed ranges instead of panicking
+                let (from, to) = if start_offset <= end_offset {
+                    (start_offset, end_offset)
+                } else {
+                    (end_offset, start_offset)
+                };
+                result.replace_range(from..to, &change.text);
+            }
+            None => result = change.text.clone(),
+        }
+    }
+
+    result
+}
+
+impl FixtureDatabase {
+    /// Record the version of a document the client has just opened.
+    pub fn set_document_version(&self, file_path: PathBuf, version: i32) {
+        let file_path = self.get_canonical_path(file_path);
+        self.document_versions.insert(file_path, version);
+    }
+
+    /// Apply the changes of a `didChange` notification to the cached content of
+    /// an open document and re-analyze it.
+    ///
+    /// Returns `false` (leaving the cache untouched) if there are no changes, if `version` is not newer
+    /// than the last applied version, or if a range edit arrives for a document
+    /// whose content is not cached.
+    pub fn update_document(
+        &self,
+        file_path: PathBuf,
+        version: i32,
+        changes: &[ContentChange],
+    ) -> bool {
+        if changes.is_empty() {
+            return false;
+        }
+        let file_path = self.get_canonical_path(file_path);
+
+        if let Some(previous) = self.document_versions.get(&file_path).map(|v| *v) {
+            if version <= previous {
+                warn!(
+                    "Ignoring stale change for {:?}: version {} <= {}",
+                    file_path, version, previous
+                );
+                return false;
+            }
+        }
+
+        // A full-document change does not need the previous content
+        let starts_with_full_text = changes.first().is_some_and(|c| c.range.is_none());
+        let current: Arc<String> = match self.file_cache.get(&file_path) {
+            Some(content) => Arc::clone(content.value()),
+            None if starts_with_full_text => Arc::new(String::new()),
+            None => {
+                warn!(
+                    "Received incremental change for {:?} without cached content",
+                    file_path
+                );
+                return false;
+            }
+        };
+
+        let updated = apply_content_changes(&current, changes);
+        debug!(
+            "Applied {} change(s) to {:?} (version {})",
+            changes.len(),
+            file_path,
+            version
+        );
+
+        self.document_versions.insert(file_path.clone(), version);
+        self.analyze_file(file_path, &updated);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::types::DocumentPosition;
+
+    fn edit(start: (u32, u32), end: (u32, u32), text: &str) -> ContentChange {
+        ContentChange {
+            range: Some((
+                DocumentPosition {
+                    line: start.0,
+                    character: start.1,
+                },
+                DocumentPosition {
+                    line: end.0,
+                    character: end.1,
+                },
+            )),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_apply_sequential_edits() {
+        let content = "def test_a(db):\n    pass\n";
+        let changes = [
+            // Insert a parameter, then edit the body relative to the new text
+            edit((0, 13), (0, 13), ", client"),
+            edit((1, 4), (1, 8), "assert db"),
+        ];
+        assert_eq!(
+            apply_content_changes(content, &changes),
+            "def test_a(db, client):\n    assert db\n"
+        );
+    }
+
+    #[test]
+    fn test_apply_multiline_deletion_and_full_replace() {
+        let content = "a\nb\nc\n";
+        assert_eq!(
+            apply_content_changes(content, &[edit((0, 1), (2, 0), " ")]),
+            "a c\n"
+        );
+
+        let full = ContentChange {
+            range: None,
+            text: "x = 1\n".to_string(),
+        };
+        assert_eq!(
+            apply_content_changes(content, &[full, edit((0, 4), (0, 5), "2")]),
+            "x = 2\n"
+        );
+    }
+
+    #[test]
+    fn test_apply_edit_after_non_ascii() {
+        // "ü" and "😀" take 1 and 2 UTF-16 units respectively
+        let content = "s = \"ü😀\"; t = 1\n";
+        assert_eq!(
+            apply_content_changes(content, &[edit((0, 15), (0, 16), "2")]),
+            "s = \"ü😀\"; t = 2\n"
+        );
+    }
+}
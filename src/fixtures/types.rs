@@ -1,5 +1,6 @@
 //! Data structures for fixture definitions, usages, and related types.
 
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 /// Specifies how to import a type referenced in a fixture's return annotation.
@@ -7,7 +8,7 @@ use std::path::PathBuf;
 /// Resolved at analysis time from the fixture file's own imports, this struct
 /// encodes everything needed to add the correct import statement to a consumer
 /// file (e.g. a test file that declares the fixture as a parameter).
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TypeImportSpec {
     /// The name to look for in the target file's module-level names set.
     ///
@@ -26,7 +27,9 @@ pub struct TypeImportSpec {
 
 /// Pytest fixture scope, ordered from narrowest to broadest.
 /// A fixture with a broader scope cannot depend on a fixture with a narrower scope.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
 pub enum FixtureScope {
     /// Function scope (default) - created once per test function
     #[default]
@@ -78,7 +81,7 @@ impl FixtureScope {
 ///     ..Default::default()
 /// };
 /// ```
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct FixtureDefinition {
     pub name: String,
     pub file_path: PathBuf,
@@ -95,6 +98,16 @@ pub struct FixtureDefinition {
     pub scope: FixtureScope, // The fixture's scope (function, class, module, package, session)
     pub yield_line: Option<usize>, // Line number of the yield statement (for generator fixtures)
     pub autouse: bool,   // Whether this fixture has autouse=True
+    /// Source text of each value in `params=`, for parametrized fixtures.
+    pub params: Vec<String>,
+    /// Explicit id of each param (`ids=` or `pytest.param(id=...)`), if any.
+    pub param_ids: Vec<Option<String>>,
+    /// Class the fixture is defined in, if it is a method. Such fixtures are
+    /// only visible to tests in that class, its subclasses and nested classes.
+    pub class_name: Option<String>,
+    /// `fixture_paths` directory the fixture was loaded from. Such fixtures
+    /// are visible to every test, like plugin fixtures (`is_plugin` is set).
+    pub fixture_path: Option<PathBuf>,
 }
 
 /// A fixture usage (reference) in a Python file.
@@ -102,7 +115,7 @@ pub struct FixtureDefinition {
 /// This struct is `#[non_exhaustive]`: new fields may be added in future versions
 /// without a semver-major bump.
 #[non_exhaustive]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FixtureUsage {
     pub name: String,
     pub file_path: PathBuf,
@@ -113,10 +126,42 @@ pub struct FixtureUsage {
     /// `false` for string-based usages inside `@pytest.mark.usefixtures(...)`,
     /// `pytestmark = pytest.mark.usefixtures(...)`, or `@pytest.mark.parametrize(..., indirect=...)`.
     pub is_parameter: bool,
+    /// `true` for string lookups through `request.getfixturevalue("...")`.
+    /// pytest resolves them when the call runs, so unknown names are reported
+    /// like unknown parameters.
+    pub is_dynamic: bool,
+}
+
+/// A test parameter supplied directly by `@pytest.mark.parametrize` rather than
+/// by a fixture.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParametrizedArgument {
+    pub name: String,
+    pub file_path: PathBuf,
+    pub line: usize,
+    pub start_char: usize, // Character position where the parameter name starts (on the line)
+    pub end_char: usize,   // Character position where the parameter name ends (on the line)
+    /// Source text of the value in each parameter set, in declaration order.
+    pub values: Vec<String>,
+    /// Explicit id of each parameter set (`ids=` or `pytest.param(id=...)`), if any.
+    pub ids: Vec<Option<String>>,
+}
+
+/// A class in a Python file, tracked to scope fixtures defined as methods.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassDefinition {
+    pub name: String,
+    pub file_path: PathBuf,
+    pub line: usize,     // First line of the class, including decorators
+    pub end_line: usize, // Last line of the class body
+    /// Base class names as written, dotted for attributes (`module.Base`).
+    pub bases: Vec<String>,
+    /// Enclosing class, for nested classes.
+    pub outer: Option<String>,
 }
 
 /// An undeclared fixture used in a function body without being declared as a parameter.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)] // Fields used for debugging and future features
 pub struct UndeclaredFixture {
     pub name: String,
@@ -146,6 +191,41 @@ pub struct ScopeMismatch {
     pub dependency: FixtureDefinition,
 }
 
+/// A fixture definition that shadows another definition of the same name,
+/// e.g. a child conftest.py redefining a fixture of a parent conftest.py or plugin.
+#[derive(Debug, Clone)]
+pub struct FixtureOverride {
+    /// The overriding definition.
+    pub fixture: FixtureDefinition,
+    /// The definition it shadows.
+    pub overridden: FixtureDefinition,
+}
+
+/// A text range that must be rewritten when a fixture is renamed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RenameLocation {
+    pub file_path: PathBuf,
+    pub line: usize,       // Line number (1-based)
+    pub start_char: usize, // Character position where the old name starts (on the line)
+    pub end_char: usize,   // Character position where the old name ends (on the line)
+}
+
+/// A position in a document as sent by the client: 0-based line and
+/// UTF-16 code unit offset within that line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DocumentPosition {
+    pub line: u32,
+    pub character: u32,
+}
+
+/// A single change to an open document (incremental text sync).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentChange {
+    /// The replaced range as `(start, end)`; `None` replaces the whole document.
+    pub range: Option<(DocumentPosition, DocumentPosition)>,
+    pub text: String,
+}
+
 /// Context for code completion.
 #[derive(Debug, Clone, PartialEq)]
 pub enum CompletionContext {
@@ -171,6 +251,28 @@ pub enum CompletionContext {
     UsefixturesDecorator,
     /// Inside @pytest.mark.parametrize(..., indirect=...) - suggest fixture names as strings.
     ParametrizeIndirect,
+    /// Inside the string of `request.getfixturevalue("...")` - suggest fixture
+    /// names as strings.
+    FixtureValueLookup,
+    /// After `scope=` in @pytest.fixture(...) - suggest scope names and
+    /// dynamic scope callables defined in the file.
+    FixtureScopeValue {
+        /// Whether the cursor is inside the opening quote already.
+        in_string: bool,
+        callables: Vec<String>,
+    },
+    /// After `autouse=` in @pytest.fixture(...) - suggest `True` / `False`.
+    FixtureAutouseValue,
+    /// After `indirect=` in @pytest.mark.parametrize(...) - suggest `True` / `False`
+    /// or a list of the parametrize argnames.
+    ParametrizeIndirectValue { argnames: Vec<String> },
+    /// Inside the argnames string of @pytest.mark.parametrize(...) - suggest the
+    /// parameters of the decorated function that are not listed yet.
+    ParametrizeArgnames {
+        function_name: String,
+        function_line: usize,
+        params: Vec<String>,
+    },
 }
 
 /// Information about where to insert a new parameter in a function signature.
@@ -1,11 +1,12 @@
 //! Undeclared fixture detection in function bodies.
 //!
 //! This module scans function bodies for references to fixtures that
-//! are not declared as function parameters.
+//! are not declared as function parameters, and for fixtures looked up
+//! dynamically through `request.getfixturevalue("name")`.
 
-use super::types::UndeclaredFixture;
+use super::types::{FixtureUsage, UndeclaredFixture};
 use super::FixtureDatabase;
-use rustpython_parser::ast::{Expr, Stmt};
+use rustpython_parser::ast::{Constant, Expr, ExprCall, Stmt};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use tracing::info;
@@ -15,8 +16,12 @@ use tracing::info;
 pub(crate) struct BodyScanContext<'a> {
     pub file_path: &'a PathBuf,
     pub line_index: &'a [usize],
-    pub declared_params: &'a HashSet<String>,
+    /// Parameters of the function, or `None` when only `getfixturevalue`
+    /// lookups are recorded (hooks and helper functions take no fixtures).
+    pub declared_params: Option<&'a HashSet<String>>,
     pub local_vars: &'a HashMap<String, usize>,
+    /// Local names bound to the `request` object (`req = request`).
+    pub request_aliases: &'a HashSet<String>,
     pub function_name: &'a str,
     pub function_line: usize,
 }
@@ -33,6 +38,45 @@ impl FixtureDatabase {
         declared_params: &HashSet<String>,
         function_name: &str,
         function_line: usize,
+    ) {
+        self.scan_function_body(
+            body,
+            file_path,
+            line_index,
+            Some(declared_params),
+            function_name,
+            function_line,
+        );
+    }
+
+    /// Scan the body of a function that is neither a test nor a fixture, such
+    /// as a hook (`pytest_runtest_setup(item)`), for `getfixturevalue` lookups.
+    pub(crate) fn scan_function_body_for_getfixturevalue(
+        &self,
+        body: &[Stmt],
+        file_path: &PathBuf,
+        line_index: &[usize],
+        function_name: &str,
+        function_line: usize,
+    ) {
+        self.scan_function_body(
+            body,
+            file_path,
+            line_index,
+            None,
+            function_name,
+            function_line,
+        );
+    }
+
+    fn scan_function_body(
+        &self,
+        body: &[Stmt],
+        file_path: &PathBuf,
+        line_index: &[usize],
+        declared_params: Option<&HashSet<String>>,
+        function_name: &str,
+        function_line: usize,
     ) {
         // First, collect all local variable names with their definition line numbers
         let mut local_vars = HashMap::new();
@@ -45,11 +89,15 @@ impl FixtureDatabase {
             }
         }
 
+        let mut request_aliases = HashSet::new();
+        Self::collect_request_aliases(body, &mut request_aliases);
+
         let ctx = BodyScanContext {
             file_path,
             line_index,
             declared_params,
             local_vars: &local_vars,
+            request_aliases: &request_aliases,
             function_name,
             function_line,
         };
@@ -165,6 +213,83 @@ impl FixtureDatabase {
         }
     }
 
+    /// Collect local names assigned a `request` object, e.g. `req = request`
+    /// or `req = item._request`.
+    fn collect_request_aliases(body: &[Stmt], aliases: &mut HashSet<String>) {
+        for stmt in body {
+            match stmt {
+                Stmt::Assign(assign) if Self::is_request_object(&assign.value, aliases) => {
+                    for target in &assign.targets {
+                        if let Expr::Name(name) = target {
+                            aliases.insert(name.id.to_string());
+                        }
+                    }
+                }
+                Stmt::If(if_stmt) => {
+                    Self::collect_request_aliases(&if_stmt.body, aliases);
+                    Self::collect_request_aliases(&if_stmt.orelse, aliases);
+                }
+                Stmt::With(with_stmt) => Self::collect_request_aliases(&with_stmt.body, aliases),
+                Stmt::Try(try_stmt) => {
+                    Self::collect_request_aliases(&try_stmt.body, aliases);
+                    Self::collect_request_aliases(&try_stmt.finalbody, aliases);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Check whether an expression is statically a `request` object: the
+    /// `request` parameter, a `._request` attribute (`item._request`) or a
+    /// local alias of either.
+    fn is_request_object(expr: &Expr, aliases: &HashSet<String>) -> bool {
+        match expr {
+            Expr::Name(name) => name.id.as_str() == "request" || aliases.contains(name.id.as_str()),
+            Expr::Attribute(attr) => attr.attr.as_str() == "_request",
+            _ => false,
+        }
+    }
+
+    /// Record `request.getfixturevalue("name")` as a usage of `name`.
+    fn record_getfixturevalue_usage(&self, call: &ExprCall, ctx: &BodyScanContext) {
+        let Expr::Attribute(method) = call.func.as_ref() else {
+            return;
+        };
+        if method.attr.as_str() != "getfixturevalue"
+            || !Self::is_request_object(&method.value, ctx.request_aliases)
+        {
+            return;
+        }
+        let Some(Expr::Constant(constant)) = call.args.first() else {
+            return;
+        };
+        let Constant::Str(name) = &constant.value else {
+            return;
+        };
+
+        let start = constant.range.start().to_usize();
+        let end = constant.range.end().to_usize();
+        let line = self.get_line_from_offset(start, ctx.line_index);
+        // Usages cover the name only, not the quotes
+        let start_char = self.get_char_position_from_offset(start, ctx.line_index) + 1;
+        let end_char = self.get_char_position_from_offset(end, ctx.line_index) - 1;
+
+        info!(
+            "Found getfixturevalue usage: {} at {:?}:{}:{} in function {}",
+            name, ctx.file_path, line, start_char, ctx.function_name
+        );
+
+        self.record_usage(FixtureUsage {
+            name: name.clone(),
+            file_path: ctx.file_path.clone(),
+            line,
+            start_char,
+            end_char,
+            is_parameter: false,
+            is_dynamic: true,
+        });
+    }
+
     /// Visit a statement and check for undeclared fixture references.
     fn visit_stmt_for_names(&self, stmt: &Stmt, ctx: &BodyScanContext) {
         match stmt {
@@ -240,6 +365,9 @@ impl FixtureDatabase {
     fn visit_expr_for_names(&self, expr: &Expr, ctx: &BodyScanContext) {
         match expr {
             Expr::Name(name) => {
+                let Some(declared_params) = ctx.declared_params else {
+                    return;
+                };
                 let name_str = name.id.as_str();
                 let line = self.get_line_from_offset(name.range.start().to_usize(), ctx.line_index);
 
@@ -249,7 +377,7 @@ impl FixtureDatabase {
                     .map(|def_line| *def_line < line)
                     .unwrap_or(false);
 
-                if !ctx.declared_params.contains(name_str)
+                if !declared_params.contains(name_str)
                     && !is_local_var_in_scope
                     && self.is_available_fixture(ctx.file_path, name_str)
                 {
@@ -282,6 +410,7 @@ impl FixtureDatabase {
                 }
             }
             Expr::Call(call) => {
+                self.record_getfixturevalue_usage(call, ctx);
                 self.visit_expr_for_names(&call.func, ctx);
                 for arg in &call.args {
                     self.visit_expr_for_names(arg, ctx);
@@ -0,0 +1,186 @@
+//! Test variants generated by parametrized fixtures.
+//!
+//! A test runs once per combination of its `@pytest.mark.parametrize` cases
+//! and the `params=` of every parametrized fixture it requests, directly,
+//! through other fixtures or via autouse.
+
+use super::decorators;
+use super::types::FixtureDefinition;
+use super::FixtureDatabase;
+use rustpython_parser::ast::{Arguments, Expr, Mod, Stmt};
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// The number of variants pytest generates for a test function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestVariants {
+    pub test_name: String,
+    pub line: usize, // 1-based line of the `def`
+    /// Total number of variants (parametrize cases times fixture params).
+    pub count: usize,
+    /// Names of the parametrized fixtures contributing to the count.
+    pub fixtures: Vec<String>,
+}
+
+impl FixtureDatabase {
+    /// Count the variants of every test in a file that requests a
+    /// parametrized fixture. Tests without one are omitted.
+    pub fn find_test_variants(&self, file_path: &Path) -> Vec<TestVariants> {
+        let Some(content) = self.get_file_content(file_path) else {
+            return vec![];
+        };
+        let Some(parsed) = self.get_parsed_ast(file_path, &content) else {
+            return vec![];
+        };
+        let Mod::Module(module) = parsed.as_ref() else {
+            return vec![];
+        };
+        let line_index = self.get_line_index(file_path, &content);
+
+        let mut variants = Vec::new();
+        let mut visit_function = |name: &str,
+                                  args: &Arguments,
+                                  decorator_list: &[Expr],
+                                  class_decorators: &[Expr],
+                                  offset: usize| {
+            if !self.is_test_function_name(file_path, name) {
+                return;
+            }
+            let decorators: Vec<&Expr> = class_decorators.iter().chain(decorator_list).collect();
+            let line = self.get_line_from_offset(offset, &line_index);
+            // Parametrized autouse fixtures multiply every test in scope
+            let autouse: Vec<FixtureDefinition> = self
+                .get_autouse_fixtures_at_line(file_path, line)
+                .into_iter()
+                .filter(|def| !def.params.is_empty())
+                .collect();
+            if let Some((count, fixtures)) =
+                self.count_variants(file_path, line, args, &decorators, &content, &autouse)
+            {
+                variants.push(TestVariants {
+                    test_name: name.to_string(),
+                    line,
+                    count,
+                    fixtures,
+                });
+            }
+        };
+
+        for stmt in &module.body {
+            match stmt {
+                Stmt::FunctionDef(func) => visit_function(
+                    func.name.as_str(),
+                    &func.args,
+                    &func.decorator_list,
+                    &[],
+                    func.range.start().to_usize(),
+                ),
+                Stmt::AsyncFunctionDef(func) => visit_function(
+                    func.name.as_str(),
+                    &func.args,
+                    &func.decorator_list,
+                    &[],
+                    func.range.start().to_usize(),
+                ),
+                Stmt::ClassDef(class)
+                    if self.is_test_class_name(file_path, class.name.as_str()) =>
+                {
+                    for method in &class.body {
+                        match method {
+                            Stmt::FunctionDef(func) => visit_function(
+                                func.name.as_str(),
+                                &func.args,
+                                &func.decorator_list,
+                                &class.decorator_list,
+                                func.range.start().to_usize(),
+                            ),
+                            Stmt::AsyncFunctionDef(func) => visit_function(
+                                func.name.as_str(),
+                                &func.args,
+                                &func.decorator_list,
+                                &class.decorator_list,
+                                func.range.start().to_usize(),
+                            ),
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        variants
+    }
+
+    /// Count the variants of one test, or `None` if no parametrized fixture
+    /// is involved.
+    fn count_variants(
+        &self,
+        file_path: &Path,
+        line: usize,
+        args: &Arguments,
+        decorators: &[&Expr],
+        content: &str,
+        autouse: &[FixtureDefinition],
+    ) -> Option<(usize, Vec<String>)> {
+        let marks: Vec<decorators::ParametrizeInfo> = decorators
+            .iter()
+            .filter_map(|dec| decorators::extract_parametrize(dec, content))
+            .collect();
+        // Argnames bound by parametrize (directly or indirectly) replace the
+        // fixture's own params
+        let parametrized: HashSet<&str> = marks
+            .iter()
+            .flat_map(|mark| mark.argnames.iter().map(String::as_str))
+            .collect();
+
+        let mut queue: VecDeque<FixtureDefinition> = VecDeque::new();
+        for arg in Self::all_args(args) {
+            let name = arg.def.arg.as_str();
+            if name == "self" || name == "request" || parametrized.contains(name) {
+                continue;
+            }
+            if let Some(def) = self.find_closest_definition_at_line(file_path, name, line) {
+                queue.push_back(def);
+            }
+        }
+        queue.extend(
+            autouse
+                .iter()
+                .filter(|def| !parametrized.contains(def.name.as_str()))
+                .cloned(),
+        );
+
+        let mut seen: HashSet<(PathBuf, usize)> = HashSet::new();
+        let mut count = 1usize;
+        let mut fixtures = Vec::new();
+        while let Some(def) = queue.pop_front() {
+            if !seen.insert((def.file_path.clone(), def.line)) {
+                continue;
+            }
+            if !def.params.is_empty() {
+                count = count.saturating_mul(def.params.len());
+                fixtures.push(def.name.clone());
+            }
+            for dep_name in &def.dependencies {
+                // A fixture requesting its own name gets the definition it overrides
+                let resolved = if *dep_name == def.name {
+                    self.find_closest_definition_excluding(&def.file_path, dep_name, Some(&def))
+                } else {
+                    self.find_closest_definition_at_line(&def.file_path, dep_name, def.line)
+                };
+                queue.extend(resolved);
+            }
+        }
+
+        if fixtures.is_empty() {
+            return None;
+        }
+        for mark in &marks {
+            if !mark.cases.is_empty() {
+                count = count.saturating_mul(mark.cases.len());
+            }
+        }
+        Some((count, fixtures))
+    }
+}
@@ -0,0 +1,599 @@
+//! Virtual environment discovery.
+//!
+//! Third-party fixtures live in the site-packages of the project's virtual
+//! environment, which each Python tool keeps in a different place: in the
+//! project (`.venv`), wherever the active shell points (`VIRTUAL_ENV`,
+//! `CONDA_PREFIX`), in a tool cache (Poetry, Pipenv) or next to an interpreter
+//! (pyenv, PDM). Strategies are tried in a fixed order and the first existing
+//! environment wins. An active conda environment only counts after the
+//! project tools, and conda's `base` environment not at all, since conda
+//! users often keep `base` activated in every shell.
+
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// Directories in the project root checked as virtual environments.
+const IN_PROJECT_VENV_DIRS: &[&str] = &[".venv", "venv", "env"];
+
+/// Directories under the home directory where conda is commonly installed.
+const CONDA_INSTALL_DIRS: &[&str] = &[
+    "miniconda3",
+    "anaconda3",
+    "miniforge3",
+    "mambaforge",
+    ".conda",
+];
+
+/// Explicit environment settings from the configuration. Relative paths are
+/// already resolved against the workspace root.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VenvSettings {
+    /// Python interpreter of the environment, e.g. `.venv/bin/python`.
+    pub python: Option<PathBuf>,
+    /// Root directory of the environment.
+    pub venv: Option<PathBuf>,
+    /// Plugins whose fixtures are not loaded from the environment, by pytest11
+    /// entry point name or distribution name.
+    pub skip_plugins: Vec<String>,
+}
+
+impl VenvSettings {
+    /// Whether the fixtures of a pytest11 entry point must not be loaded.
+    pub fn skips_plugin(&self, entry_point: &str, distribution: Option<&str>) -> bool {
+        self.skip_plugins.iter().any(|skipped| {
+            plugin_name_matches(skipped, entry_point)
+                || distribution.is_some_and(|name| plugin_name_matches(skipped, name))
+        })
+    }
+}
+
+/// Compare plugin names like distribution names (PEP 503): case-insensitive,
+/// with `-`, `_` and `.` treated alike.
+pub fn plugin_name_matches(configured: &str, name: &str) -> bool {
+    let normalize = |name: &str| name.replace(['-', '.'], "_").to_lowercase();
+    normalize(configured) == normalize(name)
+}
+
+/// How a virtual environment was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VenvStrategy {
+    /// The `venv` setting.
+    ConfiguredVenv,
+    /// The `python` setting.
+    ConfiguredPython,
+    /// `.venv`, `venv` or `env` in the project root.
+    InProject,
+    /// `VIRTUAL_ENV`.
+    VirtualEnv,
+    /// `UV_PROJECT_ENVIRONMENT`.
+    Uv,
+    /// The interpreter recorded in `.pdm-python`.
+    Pdm,
+    /// Poetry's `virtualenvs.path` or cache directory.
+    Poetry,
+    /// Pipenv's `WORKON_HOME` directory.
+    Pipenv,
+    /// `CONDA_PREFIX`, unless it is the `base` environment.
+    CondaPrefix,
+    /// A pyenv version or pyenv-virtualenv named in `.python-version`.
+    Pyenv,
+    /// Another directory in the project root containing `pyvenv.cfg`.
+    PyvenvCfg,
+    /// The conda environment named in `environment.yml`.
+    CondaEnvironmentFile,
+}
+
+impl VenvStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VenvStrategy::ConfiguredVenv => "venv setting",
+            VenvStrategy::ConfiguredPython => "python setting",
+            VenvStrategy::InProject => "in-project directory",
+            VenvStrategy::VirtualEnv => "VIRTUAL_ENV",
+            VenvStrategy::Uv => "UV_PROJECT_ENVIRONMENT",
+            VenvStrategy::Pdm => ".pdm-python",
+            VenvStrategy::Poetry => "Poetry virtualenvs directory",
+            VenvStrategy::Pipenv => "Pipenv virtualenvs directory",
+            VenvStrategy::CondaPrefix => "CONDA_PREFIX",
+            VenvStrategy::Pyenv => ".python-version (pyenv)",
+            VenvStrategy::PyvenvCfg => "pyvenv.cfg",
+            VenvStrategy::CondaEnvironmentFile => "environment.yml (conda)",
+        }
+    }
+}
+
+/// A discovered virtual environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredVenv {
+    pub path: PathBuf,
+    pub strategy: VenvStrategy,
+}
+
+/// Find the virtual environment of a project, reading the process environment.
+pub fn discover_venv(root: &Path, settings: &VenvSettings) -> Option<DiscoveredVenv> {
+    discover_venv_with_env(root, settings, &|name| std::env::var(name).ok())
+}
+
+/// Find the virtual environment of a project, reading environment variables
+/// through `env`.
+pub fn discover_venv_with_env(
+    root: &Path,
+    settings: &VenvSettings,
+    env: &dyn Fn(&str) -> Option<String>,
+) -> Option<DiscoveredVenv> {
+    let strategies: [(VenvStrategy, &dyn Fn() -> Option<PathBuf>); 12] = [
+        (VenvStrategy::ConfiguredVenv, &|| {
+            let venv = settings.venv.clone()?;
+            if !venv.is_dir() {
+                warn!("Configured venv does not exist: {:?}", venv);
+                return None;
+            }
+            Some(venv)
+        }),
+        (VenvStrategy::ConfiguredPython, &|| {
+            let python = settings.python.as_deref()?;
+            if !python.exists() {
+                warn!("Configured python does not exist: {:?}", python);
+                return None;
+            }
+            venv_from_interpreter(python)
+        }),
+        (VenvStrategy::InProject, &|| {
+            IN_PROJECT_VENV_DIRS
+                .iter()
+                .map(|dir| root.join(dir))
+                .find(|path| path.exists())
+        }),
+        (VenvStrategy::VirtualEnv, &|| {
+            env("VIRTUAL_ENV").map(PathBuf::from)
+        }),
+        (VenvStrategy::Uv, &|| {
+            Some(root.join(env("UV_PROJECT_ENVIRONMENT")?))
+        }),
+        (VenvStrategy::Pdm, &|| {
+            let python = std::fs::read_to_string(root.join(".pdm-python")).ok()?;
+            venv_from_interpreter(&root.join(python.trim()))
+        }),
+        (VenvStrategy::Poetry, &|| poetry_venv(root, env)),
+        (VenvStrategy::Pipenv, &|| pipenv_venv(root, env)),
+        (VenvStrategy::CondaPrefix, &|| {
+            if env("CONDA_DEFAULT_ENV").as_deref() == Some("base") {
+                return None;
+            }
+            env("CONDA_PREFIX").map(PathBuf::from)
+        }),
+        (VenvStrategy::Pyenv, &|| pyenv_venv(root, env)),
+        (VenvStrategy::PyvenvCfg, &|| {
+            let mut candidates: Vec<PathBuf> = std::fs::read_dir(root)
+                .ok()?
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.join("pyvenv.cfg").is_file())
+                .collect();
+            candidates.sort();
+            candidates.into_iter().next()
+        }),
+        (VenvStrategy::CondaEnvironmentFile, &|| {
+            conda_environment_file_venv(root, env)
+        }),
+    ];
+
+    for (strategy, find) in strategies {
+        match find() {
+            Some(path) if path.exists() => {
+                let path = path.canonicalize().unwrap_or(path);
+                return Some(DiscoveredVenv { path, strategy });
+            }
+            Some(path) => debug!(
+                "Virtual environment from {} does not exist: {:?}",
+                strategy.as_str(),
+                path
+            ),
+            None => debug!("No virtual environment from {}", strategy.as_str()),
+        }
+    }
+    None
+}
+
+/// The environment an interpreter belongs to: the parent of its `bin`
+/// (or `Scripts`) directory, or the directory itself for conda on Windows.
+/// Symlinked interpreters are not followed, so a venv's `python` pointing
+/// at the base installation still resolves to the venv.
+fn venv_from_interpreter(python: &Path) -> Option<PathBuf> {
+    let dir = python.parent()?;
+    match dir.file_name().and_then(|name| name.to_str()) {
+        Some("bin" | "Scripts") => dir.parent().map(Path::to_path_buf),
+        _ => Some(dir.to_path_buf()),
+    }
+}
+
+fn home_dir(env: &dyn Fn(&str) -> Option<String>) -> Option<PathBuf> {
+    env("HOME")
+        .or_else(|| env("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+/// Read a TOML file from the project root.
+fn read_toml(path: &Path) -> Option<toml::Table> {
+    let content = std::fs::read_to_string(path).ok()?;
+    match content.parse() {
+        Ok(table) => Some(table),
+        Err(e) => {
+            warn!("Failed to parse {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Poetry's environment: `.venv` with `virtualenvs.in-project`, otherwise
+/// `<name>-<hash>-py<version>` in `virtualenvs.path` (default: Poetry's cache
+/// directory). The hash cannot be recomputed, so the most recently modified
+/// environment of the project name wins.
+fn poetry_venv(root: &Path, env: &dyn Fn(&str) -> Option<String>) -> Option<PathBuf> {
+    let pyproject = read_toml(&root.join("pyproject.toml"));
+    let poetry_section = pyproject
+        .as_ref()
+        .and_then(|pyproject| pyproject.get("tool")?.get("poetry"));
+    let poetry_toml = read_toml(&root.join("poetry.toml"));
+    if poetry_section.is_none() && poetry_toml.is_none() && !root.join("poetry.lock").exists() {
+        return None;
+    }
+
+    let virtualenvs = poetry_toml
+        .as_ref()
+        .and_then(|config| config.get("virtualenvs"));
+    let in_project = env("POETRY_VIRTUALENVS_IN_PROJECT")
+        .map(|value| value == "true" || value == "1")
+        .or_else(|| virtualenvs?.get("in-project")?.as_bool());
+    if in_project == Some(true) {
+        return Some(root.join(".venv"));
+    }
+
+    let venvs_dir = env("POETRY_VIRTUALENVS_PATH")
+        .or_else(|| Some(virtualenvs?.get("path")?.as_str()?.to_string()))
+        .map(|path| root.join(path))
+        .or_else(|| {
+            env("POETRY_CACHE_DIR")
+                .map(PathBuf::from)
+                .or_else(|| poetry_cache_dir(env))
+                .map(|cache| cache.join("virtualenvs"))
+        })?;
+
+    let name = poetry_section
+        .and_then(|poetry| poetry.get("name"))
+        .or_else(|| pyproject.as_ref()?.get("project")?.get("name"))
+        .and_then(|name| name.as_str())?;
+    // Poetry's env name: lowercased, shell-unsafe characters replaced, at most 42 chars
+    let sanitized: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if " $`!*@\"\\\r\n\t".contains(c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .take(42)
+        .collect();
+    let prefix = format!("{}-", sanitized);
+
+    std::fs::read_dir(&venvs_dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_prefix(&prefix))
+                .is_some_and(|rest| rest.contains("-py"))
+        })
+        .max_by_key(|entry| entry.metadata().and_then(|meta| meta.modified()).ok())
+        .map(|entry| entry.path())
+}
+
+/// Poetry's platform cache directory.
+fn poetry_cache_dir(env: &dyn Fn(&str) -> Option<String>) -> Option<PathBuf> {
+    if cfg!(windows) {
+        return env("LOCALAPPDATA").map(|dir| PathBuf::from(dir).join("pypoetry").join("Cache"));
+    }
+    if cfg!(target_os = "macos") {
+        return home_dir(env).map(|home| home.join("Library/Caches/pypoetry"));
+    }
+    env("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home_dir(env).map(|home| home.join(".cache")))
+        .map(|cache| cache.join("pypoetry"))
+}
+
+/// Pipenv's environment in `WORKON_HOME`: the one whose `.project` file
+/// points back at the project root.
+fn pipenv_venv(root: &Path, env: &dyn Fn(&str) -> Option<String>) -> Option<PathBuf> {
+    if !root.join("Pipfile").exists() {
+        return None;
+    }
+    let workon_home = env("WORKON_HOME").map(PathBuf::from).or_else(|| {
+        let home = home_dir(env)?;
+        Some(if cfg!(windows) {
+            home.join(".virtualenvs")
+        } else {
+            home.join(".local/share/virtualenvs")
+        })
+    })?;
+
+    let canonical_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    std::fs::read_dir(&workon_home)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|venv| {
+            std::fs::read_to_string(venv.join(".project")).is_ok_and(|project| {
+                let project = PathBuf::from(project.trim());
+                project.canonicalize().unwrap_or(project) == canonical_root
+            })
+        })
+}
+
+/// The first version named in the project's `.python-version` file that
+/// exists under `$PYENV_ROOT/versions` (pyenv versions and pyenv-virtualenv
+/// envs).
+fn pyenv_venv(root: &Path, env: &dyn Fn(&str) -> Option<String>) -> Option<PathBuf> {
+    let content = std::fs::read_to_string(root.join(".python-version")).ok()?;
+    let versions_dir = env("PYENV_ROOT")
+        .map(PathBuf::from)
+        .or_else(|| home_dir(env).map(|home| home.join(".pyenv")))?
+        .join("versions");
+
+    content
+        .split_whitespace()
+        .filter(|version| !version.starts_with('#') && *version != "system")
+        .map(|version| versions_dir.join(version))
+        .find(|path| path.is_dir())
+}
+
+/// The conda environment named in `environment.yml`, looked up in the
+/// `envs` directory of the active or a commonly installed conda.
+fn conda_environment_file_venv(
+    root: &Path,
+    env: &dyn Fn(&str) -> Option<String>,
+) -> Option<PathBuf> {
+    let content = ["environment.yml", "environment.yaml"]
+        .iter()
+        .find_map(|file| std::fs::read_to_string(root.join(file)).ok())?;
+    let name = content.lines().find_map(|line| {
+        let value = line.strip_prefix("name:")?.trim();
+        Some(value.trim_matches(|c| c == '"' || c == '\'').to_string())
+    })?;
+
+    // `CONDA_EXE` is `<conda root>/bin/conda` (or `Scripts\conda.exe`)
+    let mut conda_roots: Vec<PathBuf> = env("CONDA_EXE")
+        .and_then(|exe| venv_from_interpreter(Path::new(&exe)))
+        .into_iter()
+        .collect();
+    if let Some(home) = home_dir(env) {
+        conda_roots.extend(CONDA_INSTALL_DIRS.iter().map(|dir| home.join(dir)));
+    }
+    conda_roots
+        .into_iter()
+        .map(|conda_root| conda_root.join("envs").join(&name))
+        .find(|path| path.is_dir())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::fs;
+
+    fn discover(root: &Path, settings: &VenvSettings, vars: &[(&str, &Path)]) -> DiscoveredVenv {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string_lossy().to_string()))
+            .collect();
+        discover_venv_with_env(root, settings, &|name| vars.get(name).cloned())
+            .expect("virtual environment")
+    }
+
+    fn canonical(path: &Path) -> PathBuf {
+        path.canonicalize().unwrap()
+    }
+
+    #[test]
+    fn test_configured_settings_win() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().join("project");
+        fs::create_dir_all(root.join(".venv")).unwrap();
+        let custom = temp.path().join("envs/custom");
+        fs::create_dir_all(custom.join("bin")).unwrap();
+        fs::write(custom.join("bin/python"), "").unwrap();
+
+        let settings = VenvSettings {
+            python: Some(custom.join("bin/python")),
+            ..Default::default()
+        };
+        let venv = discover(&root, &settings, &[]);
+        assert_eq!(venv.path, canonical(&custom));
+        assert_eq!(venv.strategy, VenvStrategy::ConfiguredPython);
+
+        // Missing configured environments fall back to discovery
+        let settings = VenvSettings {
+            venv: Some(temp.path().join("missing")),
+            ..Default::default()
+        };
+        let venv = discover(&root, &settings, &[]);
+        assert_eq!(venv.strategy, VenvStrategy::InProject);
+    }
+
+    #[test]
+    fn test_poetry_cache_venv() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().join("project");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(
+            root.join("pyproject.toml"),
+            "[tool.poetry]\nname = \"My App\"\n",
+        )
+        .unwrap();
+        let cache = temp.path().join("cache");
+        let venv_path = cache.join("virtualenvs/my_app-AbCd1234-py3.12");
+        fs::create_dir_all(&venv_path).unwrap();
+        fs::create_dir_all(cache.join("virtualenvs/other-AbCd1234-py3.12")).unwrap();
+
+        let venv = discover(
+            &root,
+            &VenvSettings::default(),
+            &[("POETRY_CACHE_DIR", &cache)],
+        );
+        assert_eq!(venv.path, canonical(&venv_path));
+        assert_eq!(venv.strategy, VenvStrategy::Poetry);
+
+        // The active environment wins over tool caches, not over the project's own
+        let active = temp.path().join("active");
+        fs::create_dir_all(&active).unwrap();
+        let venv = discover(
+            &root,
+            &VenvSettings::default(),
+            &[("POETRY_CACHE_DIR", &cache), ("VIRTUAL_ENV", &active)],
+        );
+        assert_eq!(venv.path, canonical(&active));
+        assert_eq!(venv.strategy, VenvStrategy::VirtualEnv);
+
+        fs::create_dir_all(root.join(".venv")).unwrap();
+        let venv = discover(&root, &VenvSettings::default(), &[("VIRTUAL_ENV", &active)]);
+        assert_eq!(venv.strategy, VenvStrategy::InProject);
+    }
+
+    #[test]
+    fn test_pipenv_venv_matches_project_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().join("project");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("Pipfile"), "").unwrap();
+        let workon_home = temp.path().join("virtualenvs");
+        let other = workon_home.join("project-11111111");
+        let venv_path = workon_home.join("project-22222222");
+        fs::create_dir_all(&other).unwrap();
+        fs::create_dir_all(&venv_path).unwrap();
+        fs::write(other.join(".project"), "/somewhere/else/project").unwrap();
+        fs::write(venv_path.join(".project"), root.to_string_lossy().as_ref()).unwrap();
+
+        let venv = discover(
+            &root,
+            &VenvSettings::default(),
+            &[("WORKON_HOME", &workon_home)],
+        );
+        assert_eq!(venv.path, canonical(&venv_path));
+        assert_eq!(venv.strategy, VenvStrategy::Pipenv);
+    }
+
+    #[test]
+    fn test_pyenv_and_pdm_and_pyvenv_cfg() {
+        let temp = tempfile::tempdir().unwrap();
+        let pyenv_root = temp.path().join("pyenv");
+        fs::create_dir_all(pyenv_root.join("versions/project-env")).unwrap();
+
+        let root = temp.path().join("pyenv_project");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join(".python-version"), "missing\nproject-env\n").unwrap();
+        let venv = discover(
+            &root,
+            &VenvSettings::default(),
+            &[("PYENV_ROOT", &pyenv_root)],
+        );
+        assert_eq!(
+            venv.path,
+            canonical(&pyenv_root.join("versions/project-env"))
+        );
+        assert_eq!(venv.strategy, VenvStrategy::Pyenv);
+
+        // `.python-version` files above the project root are not read
+        let nested = root.join("nested");
+        fs::create_dir_all(&nested).unwrap();
+        let vars: HashMap<String, String> = [("PYENV_ROOT", &pyenv_root)]
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string_lossy().to_string()))
+            .collect();
+        assert_eq!(
+            discover_venv_with_env(&nested, &VenvSettings::default(), &|name| vars
+                .get(name)
+                .cloned()),
+            None
+        );
+
+        let root = temp.path().join("pdm_project");
+        fs::create_dir_all(root.join(".pdm-env/bin")).unwrap();
+        fs::write(root.join(".pdm-python"), ".pdm-env/bin/python\n").unwrap();
+        let venv = discover(&root, &VenvSettings::default(), &[]);
+        assert_eq!(venv.path, canonical(&root.join(".pdm-env")));
+        assert_eq!(venv.strategy, VenvStrategy::Pdm);
+
+        let root = temp.path().join("custom_project");
+        fs::create_dir_all(root.join(".venv-3.12")).unwrap();
+        fs::write(root.join(".venv-3.12/pyvenv.cfg"), "home = /usr/bin\n").unwrap();
+        let venv = discover(&root, &VenvSettings::default(), &[]);
+        assert_eq!(venv.path, canonical(&root.join(".venv-3.12")));
+        assert_eq!(venv.strategy, VenvStrategy::PyvenvCfg);
+    }
+
+    #[test]
+    fn test_conda_environments() {
+        let temp = tempfile::tempdir().unwrap();
+        let conda_root = temp.path().join("miniconda3");
+        fs::create_dir_all(conda_root.join("envs/analysis")).unwrap();
+        let root = temp.path().join("project");
+        fs::create_dir_all(&root).unwrap();
+
+        // The active environment is used when the project names none
+        let venv = discover(
+            &root,
+            &VenvSettings::default(),
+            &[("CONDA_PREFIX", &conda_root)],
+        );
+        assert_eq!(venv.path, canonical(&conda_root));
+        assert_eq!(venv.strategy, VenvStrategy::CondaPrefix);
+
+        fs::write(
+            root.join("environment.yml"),
+            "name: analysis\ndependencies:\n  - pytest\n",
+        )
+        .unwrap();
+        let venv = discover(&root, &VenvSettings::default(), &[("HOME", temp.path())]);
+        assert_eq!(venv.path, canonical(&conda_root.join("envs/analysis")));
+        assert_eq!(venv.strategy, VenvStrategy::CondaEnvironmentFile);
+
+        // An activated environment takes precedence over `environment.yml`
+        let venv = discover(
+            &root,
+            &VenvSettings::default(),
+            &[("CONDA_PREFIX", &conda_root), ("HOME", temp.path())],
+        );
+        assert_eq!(venv.strategy, VenvStrategy::CondaPrefix);
+
+        // ...but the `base` environment, active in every shell, does not
+        let venv = discover(
+            &root,
+            &VenvSettings::default(),
+            &[
+                ("CONDA_PREFIX", &conda_root),
+                ("CONDA_DEFAULT_ENV", Path::new("base")),
+                ("HOME", temp.path()),
+            ],
+        );
+        assert_eq!(venv.path, canonical(&conda_root.join("envs/analysis")));
+        assert_eq!(venv.strategy, VenvStrategy::CondaEnvironmentFile);
+
+        // Project tools take precedence over an activated environment
+        fs::create_dir_all(root.join(".pdm-env/bin")).unwrap();
+        fs::write(root.join(".pdm-python"), ".pdm-env/bin/python\n").unwrap();
+        let venv = discover(
+            &root,
+            &VenvSettings::default(),
+            &[
+                ("CONDA_PREFIX", &conda_root.join("envs/analysis")),
+                ("CONDA_DEFAULT_ENV", Path::new("analysis")),
+            ],
+        );
+        assert_eq!(venv.path, canonical(&root.join(".pdm-env")));
+        assert_eq!(venv.strategy, VenvStrategy::Pdm);
+    }
+}
@@ -8,18 +8,19 @@
 //! - **Find References**: Find all usages of a fixture across the codebase
 //! - **Hover Documentation**: View fixture docstrings and signatures
 //! - **Code Completion**: Auto-complete fixture names in function signatures
-//! - **Diagnostics**: Detect undeclared fixtures, scope mismatches, and circular dependencies
+//! - **Diagnostics**: Detect undeclared and unknown fixtures, scope mismatches, and circular dependencies
 //! - **Code Actions**: Quick fixes to add missing fixture parameters
 //! - **Code Lens**: Usage counts above fixture definitions
 //! - **Inlay Hints**: Show fixture return types inline
 //! - **Call Hierarchy**: Navigate fixture dependency graphs
+//! - **Rename**: Rename fixtures across definitions, parameters, and usefixtures strings
 //!
 //! ## Architecture
 //!
 //! The crate is organized into two main modules:
 //!
 //! - [`fixtures`]: Core fixture analysis engine with [`FixtureDatabase`] as the central data structure
-//! - [`config`]: Configuration file support for `pyproject.toml` settings
+//! - [`config`]: Configuration file support for `pyproject.toml` settings and pytest ini options
 //!
 //! ## Usage
 //!
@@ -49,16 +50,19 @@
 //!
 //! 1. **Same file**: Fixtures defined in the same file have highest priority
 //! 2. **Closest conftest.py**: Walk up directory tree looking for conftest.py
-//! 3. **Third-party**: Fixtures from site-packages (50+ plugins supported)
+//! 3. **Plugins and `fixture_paths`**: Modules loaded like pytest plugins
+//! 4. **Third-party**: Fixtures from site-packages (50+ plugins supported)
 
 pub mod config;
 pub mod fixtures;
 mod providers;
+pub mod report;
 
-pub use config::Config;
+pub use config::{Config, PytestIniOptions};
 pub use fixtures::{
-    CompletionContext, FixtureCycle, FixtureDatabase, FixtureDefinition, FixtureScope,
-    FixtureUsage, ParamInsertionInfo, ScopeMismatch, TypeImportSpec, UndeclaredFixture,
+    CompletionContext, ContentChange, DocumentPosition, FixtureCycle, FixtureDatabase,
+    FixtureDefinition, FixtureOverride, FixtureScope, FixtureUsage, ParamInsertionInfo,
+    ParametrizedArgument, RenameLocation, ScopeMismatch, TypeImportSpec, UndeclaredFixture,
 };
 
 // Expose decorators module for testing
@@ -1,13 +1,16 @@
 mod config;
 mod fixtures;
 mod providers;
+mod report;
 
 use clap::{Parser, Subcommand};
 use fixtures::FixtureDatabase;
 use providers::Backend;
+use report::{relative_path, report_root, Finding, FindingLevel};
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use tower_lsp_server::ls_types::{DiagnosticSeverity, NumberOrString};
 use tower_lsp_server::{LspService, Server};
 use tracing::info;
 
@@ -28,6 +31,15 @@ enum Commands {
         #[command(subcommand)]
         command: FixtureCommands,
     },
+    /// Run fixture diagnostics on all test and conftest files (exits with code 1 if problems are found)
+    Check {
+        /// Path to the directory containing test files
+        path: PathBuf,
+
+        /// Output format: "text" (default), "sarif" or "github"
+        #[arg(long, default_value = "text", value_parser = ["text", "sarif", "github"])]
+        format: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -50,8 +62,42 @@ enum FixtureCommands {
         /// Path to the directory containing test files
         path: PathBuf,
 
+        /// Output format: "text" (default), "json", "sarif" or "github"
+        #[arg(long, default_value = "text", value_parser = ["text", "json", "sarif", "github"])]
+        format: String,
+    },
+    /// Export the fixture dependency graph (one node per fixture definition)
+    Graph {
+        /// Path to the directory containing test files
+        path: PathBuf,
+
+        /// Output format: "dot" (default), "mermaid" or "json"
+        #[arg(long, default_value = "dot", value_parser = ["dot", "mermaid", "json"])]
+        format: String,
+
+        /// Only include the given fixture and the fixtures it depends on
+        #[arg(long)]
+        root: Option<String>,
+
+        /// Maximum number of dependency levels to follow
+        #[arg(long)]
+        max_depth: Option<usize>,
+
+        /// Colour nodes by fixture scope
+        #[arg(long)]
+        color_scopes: bool,
+    },
+    /// Show the fixtures pytest sets up for a test, in setup order
+    Closure {
+        /// Test to inspect, as `<file>::<test>` or `<file>::<TestClass>::<test>`
+        test: String,
+
+        /// Directory to scan for conftest.py files (default: current directory)
+        #[arg(long, default_value = ".")]
+        root: PathBuf,
+
         /// Output format: "text" (default) or "json"
-        #[arg(long, default_value = "text")]
+        #[arg(long, default_value = "text", value_parser = ["text", "json"])]
         format: String,
     },
 }
@@ -72,7 +118,22 @@ async fn main() {
             FixtureCommands::Unused { path, format } => {
                 handle_fixtures_unused(path, &format);
             }
+            FixtureCommands::Graph {
+                path,
+                format,
+                root,
+                max_depth,
+                color_scopes,
+            } => {
+                handle_fixtures_graph(path, &format, root.as_deref(), max_depth, color_scopes);
+            }
+            FixtureCommands::Closure { test, root, format } => {
+                handle_fixtures_closure(&test, root, &format);
+            }
         },
+        Some(Commands::Check { path, format }) => {
+            handle_check(path, &format);
+        }
         None => {
             // No subcommand provided - start LSP server
             start_lsp_server().await;
@@ -80,7 +141,9 @@ async fn main() {
     }
 }
 
-fn handle_fixtures_list(path: PathBuf, skip_unused: bool, only_unused: bool) {
+/// Resolve a CLI path argument to a canonical directory, exiting with an
+/// error message if it does not exist or is not a directory.
+fn resolve_directory(path: PathBuf) -> PathBuf {
     // Convert to absolute path
     let absolute_path = if path.is_absolute() {
         path
@@ -104,47 +167,120 @@ fn handle_fixtures_list(path: PathBuf, skip_unused: bool, only_unused: bool) {
     }
 
     // Canonicalize the path to resolve symlinks and relative components
-    let canonical_path = absolute_path.canonicalize().unwrap_or(absolute_path);
+    absolute_path.canonicalize().unwrap_or(absolute_path)
+}
 
-    // Create a fixture database and scan the directory
+/// Create a fixture database and scan `root` with the project's configuration
+/// (pytest options, virtual environment, `fixture_paths` and excludes), so that
+/// every command sees the same fixtures.
+fn scan_directory(root: &Path) -> (config::Config, FixtureDatabase) {
+    let config = config::Config::load(root);
     let fixture_db = FixtureDatabase::new();
-    fixture_db.scan_workspace(&canonical_path);
+    fixture_db.set_pytest_options(config.pytest.clone());
+    fixture_db.set_root_venv_settings(root, config.venv_settings(root));
+    fixture_db.set_root_fixture_paths(root, config.fixture_path_dirs(root));
+    fixture_db.scan_workspace_with_excludes(root, &config.exclude);
+    (config, fixture_db)
+}
+
+fn handle_fixtures_list(path: PathBuf, skip_unused: bool, only_unused: bool) {
+    let canonical_path = resolve_directory(path);
+    let (_, fixture_db) = scan_directory(&canonical_path);
 
     // Print the tree
     fixture_db.print_fixtures_tree(&canonical_path, skip_unused, only_unused);
 }
 
-fn handle_fixtures_unused(path: PathBuf, format: &str) {
-    use colored::Colorize;
+fn handle_fixtures_graph(
+    path: PathBuf,
+    format: &str,
+    root: Option<&str>,
+    max_depth: Option<usize>,
+    color_scopes: bool,
+) {
+    let canonical_path = resolve_directory(path);
+    let (_, fixture_db) = scan_directory(&canonical_path);
+
+    let graph = fixture_db.build_fixture_graph(root, max_depth);
+    if let Some(name) = root {
+        if graph.nodes.is_empty() {
+            eprintln!("Error: Fixture not found: {}", name);
+            std::process::exit(1);
+        }
+    }
 
-    // Convert to absolute path
-    let absolute_path = if path.is_absolute() {
-        path
-    } else {
-        std::env::current_dir()
-            .unwrap_or_else(|_| PathBuf::from("."))
-            .join(&path)
-    };
+    match format {
+        "mermaid" => print!("{}", graph.to_mermaid(&canonical_path, color_scopes)),
+        "json" => println!(
+            "{}",
+            serde_json::to_string_pretty(&graph.to_json(&canonical_path)).unwrap()
+        ),
+        _ => print!("{}", graph.to_dot(&canonical_path, color_scopes)),
+    }
+}
 
-    if !absolute_path.exists() {
-        eprintln!("Error: Path does not exist: {}", absolute_path.display());
+fn handle_fixtures_closure(test: &str, root: PathBuf, format: &str) {
+    let Some((file, test_name)) = test.split_once("::") else {
+        eprintln!("Error: Expected <file>::<test>, got: {}", test);
         std::process::exit(1);
+    };
+    let file_path = match PathBuf::from(file).canonicalize() {
+        Ok(path) => path,
+        Err(_) => {
+            eprintln!("Error: File does not exist: {}", file);
+            std::process::exit(1);
+        }
+    };
+    let canonical_path = resolve_directory(root);
+
+    let (_, fixture_db) = scan_directory(&canonical_path);
+    // The file may not match the test file patterns (or lie outside the root)
+    if !fixture_db.has_file(&file_path) {
+        if let Ok(content) = std::fs::read_to_string(&file_path) {
+            fixture_db.analyze_file(file_path.clone(), &content);
+        }
     }
 
-    if !absolute_path.is_dir() {
-        eprintln!(
-            "Error: Path is not a directory: {}",
-            absolute_path.display()
-        );
+    let Some(closure) = fixture_db.compute_fixture_closure(&file_path, test_name) else {
+        eprintln!("Error: Test not found: {}", test);
         std::process::exit(1);
+    };
+
+    match format {
+        "json" => println!(
+            "{}",
+            serde_json::to_string_pretty(&closure.to_json(&canonical_path)).unwrap()
+        ),
+        _ => print!("{}", closure.to_text(&canonical_path)),
     }
+}
 
-    // Canonicalize the path to resolve symlinks and relative components
-    let canonical_path = absolute_path.canonicalize().unwrap_or(absolute_path);
+fn handle_fixtures_unused(path: PathBuf, format: &str) {
+    use colored::Colorize;
 
-    // Create a fixture database and scan the directory
-    let fixture_db = FixtureDatabase::new();
-    fixture_db.scan_workspace(&canonical_path);
+    let canonical_path = resolve_directory(path);
+    let (_, fixture_db) = scan_directory(&canonical_path);
+
+    // SARIF and GitHub annotations need the precise definition regions
+    if format == "sarif" || format == "github" {
+        let report_root = report_root(&canonical_path);
+        let findings: Vec<Finding> = fixture_db
+            .get_unused_fixture_definitions()
+            .iter()
+            .map(|def| Finding {
+                rule_id: "unused-fixture".to_string(),
+                message: format!("Fixture '{}' is never used", def.name),
+                level: FindingLevel::Warning,
+                file: relative_path(&def.file_path, &report_root),
+                line: def.line,
+                column: def.start_char + 1,
+                end_line: def.line,
+                end_column: def.end_char + 1,
+            })
+            .collect();
+        print_findings(&findings, format);
+        std::process::exit(if findings.is_empty() { 0 } else { 1 });
+    }
 
     // Get unused fixtures
     let unused = fixture_db.get_unused_fixtures();
@@ -205,6 +341,96 @@ fn handle_fixtures_unused(path: PathBuf, format: &str) {
     std::process::exit(1);
 }
 
+fn handle_check(path: PathBuf, format: &str) {
+    use colored::Colorize;
+
+    let canonical_path = resolve_directory(path);
+
+    // The configuration also decides which diagnostics are reported
+    let (config, fixture_db) = scan_directory(&canonical_path);
+
+    // Machine-readable locations are relative to the repository, plain text to the checked directory
+    let machine_readable = format == "sarif" || format == "github";
+    let base = if machine_readable {
+        report_root(&canonical_path)
+    } else {
+        canonical_path.clone()
+    };
+
+    let mut findings: Vec<Finding> = Vec::new();
+    let mut file_count = 0;
+    for file_path in fixture_db.workspace_files() {
+        // Informational diagnostics (fixture overrides) are not problems
+        let diagnostics: Vec<_> =
+            providers::diagnostics::collect_file_diagnostics(&fixture_db, &file_path, &config)
+                .into_iter()
+                .filter(|diagnostic| diagnostic.severity != Some(DiagnosticSeverity::INFORMATION))
+                .collect();
+        if diagnostics.is_empty() {
+            continue;
+        }
+
+        let file = relative_path(&file_path, &base);
+        for diagnostic in diagnostics {
+            let rule_id = match diagnostic.code {
+                Some(NumberOrString::String(code)) => code,
+                Some(NumberOrString::Number(code)) => code.to_string(),
+                None => String::new(),
+            };
+            let level = match diagnostic.severity {
+                Some(DiagnosticSeverity::ERROR) => FindingLevel::Error,
+                Some(DiagnosticSeverity::WARNING) | None => FindingLevel::Warning,
+                Some(_) => FindingLevel::Note,
+            };
+            findings.push(Finding {
+                rule_id,
+                message: diagnostic.message,
+                level,
+                file: file.clone(),
+                line: diagnostic.range.start.line as usize + 1,
+                column: diagnostic.range.start.character as usize + 1,
+                end_line: diagnostic.range.end.line as usize + 1,
+                end_column: diagnostic.range.end.character as usize + 1,
+            });
+        }
+        file_count += 1;
+    }
+
+    if machine_readable {
+        print_findings(&findings, format);
+    } else if findings.is_empty() {
+        println!("{}", "No problems found.".green());
+    } else {
+        for finding in &findings {
+            println!(
+                "{}:{}:{}: {} {}",
+                finding.file, finding.line, finding.column, finding.rule_id, finding.message
+            );
+        }
+        println!(
+            "\n{} {} problem(s) in {} file(s)",
+            "Found".red().bold(),
+            findings.len(),
+            file_count
+        );
+    }
+
+    // Exit with code 1 to signal problems found (useful for CI)
+    std::process::exit(if findings.is_empty() { 0 } else { 1 });
+}
+
+/// Print findings in a machine-readable format ("sarif" or "github").
+fn print_findings(findings: &[Finding], format: &str) {
+    if format == "sarif" {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report::to_sarif(findings)).unwrap()
+        );
+    } else if !findings.is_empty() {
+        println!("{}", report::to_github_annotations(findings));
+    }
+}
+
 async fn start_lsp_server() {
     // Set up stderr logging with env-filter support
     // Users can control verbosity with RUST_LOG env var:
@@ -227,7 +453,12 @@ async fn start_lsp_server() {
 
     let fixture_db = Arc::new(FixtureDatabase::new());
 
-    let (service, socket) = LspService::new(|client| Backend::new(client, fixture_db.clone()));
+    let (service, socket) = LspService::build(|client| Backend::new(client, fixture_db.clone()))
+        .custom_method(
+            providers::fixture_closure::FIXTURE_CLOSURE_METHOD,
+            Backend::handle_fixture_closure,
+        )
+        .finish();
 
     info!("LSP server ready");
     Server::new(stdin, stdout, socket).serve(service).await;
@@ -8,6 +8,9 @@
 //!    `import` statement needed to use the fixture's return type annotation in
 //!    the consumer file.
 //!
+//!    When a diagnostic with code `"unknown-fixture"` is present, offers to
+//!    replace the parameter with each of the closest-named available fixtures.
+//!
 //! 2. **`source.pytest-ls`** (cursor-based) – when the cursor is on a fixture
 //!    parameter that already exists but lacks a type annotation, offers to
 //!    insert `: ReturnType` (mirroring the inlay-hint text) and any necessary
@@ -442,7 +445,7 @@ impl Backend {
         let mut actions: Vec<CodeActionOrCommand> = Vec::new();
 
         // ════════════════════════════════════════════════════════════════════
-        // Pass 1: diagnostic-driven actions (undeclared fixtures) — QUICKFIX
+        // Pass 1: diagnostic-driven actions (undeclared/unknown fixtures) — QUICKFIX
         // ════════════════════════════════════════════════════════════════════
 
         if kind_requested(&context.only, &CodeActionKind::QUICKFIX) {
@@ -458,12 +461,70 @@ impl Backend {
                 let Some(NumberOrString::String(code)) = &diagnostic.code else {
                     continue;
                 };
-                if code != "undeclared-fixture" {
+                let diag_line = Self::lsp_line_to_internal(diagnostic.range.start.line);
+                let diag_char = diagnostic.range.start.character as usize;
+
+                // ── Unknown fixture: suggest the closest-named fixtures ──────
+                if code == "unknown-fixture" {
+                    let Some(name) = self.fixture_db.usages.get(&file_path).and_then(|usages| {
+                        usages
+                            .iter()
+                            .find(|u| {
+                                (u.is_parameter || u.is_dynamic)
+                                    && u.line == diag_line
+                                    && u.start_char == diag_char
+                            })
+                            .map(|u| u.name.clone())
+                    }) else {
+                        continue;
+                    };
+
+                    let suggestions = self.fixture_db.suggest_fixture_names(&file_path, &name);
+                    info!(
+                        "Suggestions for unknown fixture '{}': {:?}",
+                        name, suggestions
+                    );
+
+                    for (i, suggestion) in suggestions.into_iter().enumerate() {
+                        let edit = WorkspaceEdit {
+                            changes: Some(
+                                vec![(
+                                    uri.clone(),
+                                    vec![TextEdit {
+                                        range: diagnostic.range,
+                                        new_text: suggestion.clone(),
+                                    }],
+                                )]
+                                .into_iter()
+                                .collect(),
+                            ),
+                            document_changes: None,
+                            change_annotations: None,
+                        };
+
+                        let action = CodeAction {
+                            title: format!(
+                                "{}: Replace '{}' with '{}'",
+                                TITLE_PREFIX, name, suggestion
+                            ),
+                            kind: Some(CodeActionKind::QUICKFIX),
+                            diagnostics: Some(vec![diagnostic.clone()]),
+                            edit: Some(edit),
+                            command: None,
+                            is_preferred: Some(i == 0),
+                            disabled: None,
+                            data: None,
+                        };
+
+                        info!("Created code action: {}", action.title);
+                        actions.push(CodeActionOrCommand::CodeAction(action));
+                    }
                     continue;
                 }
 
-                let diag_line = Self::lsp_line_to_internal(diagnostic.range.start.line);
-                let diag_char = diagnostic.range.start.character as usize;
+                if code != "undeclared-fixture" {
+                    continue;
+                }
 
                 info!(
                     "Looking for undeclared fixture at line={}, char={}",
@@ -1,6 +1,8 @@
 //! Code Lens provider for pytest fixtures.
 //!
-//! Shows "N usages" above fixture definitions.
+//! Shows "N usages" above fixture definitions, the number of variants above
+//! tests that request parametrized fixtures, and the autouse fixtures each
+//! test runs.
 
 use super::Backend;
 use tower_lsp_server::jsonrpc::Result;
@@ -74,6 +76,47 @@ impl Backend {
             }
         }
 
+        // Show how many variants parametrized fixtures generate for each test
+        for variants in self.fixture_db.find_test_variants(&file_path) {
+            let line = Self::internal_line_to_lsp(variants.line);
+            let title = format!(
+                "{} variant{} ({})",
+                variants.count,
+                if variants.count == 1 { "" } else { "s" },
+                variants.fixtures.join(", ")
+            );
+
+            lenses.push(CodeLens {
+                range: Self::create_range(line, 0, line, 0),
+                command: Some(Command {
+                    title,
+                    // Informational only: there is nothing to run on click
+                    command: String::new(),
+                    arguments: None,
+                }),
+                data: None,
+            });
+        }
+
+        // List the autouse fixtures pytest runs for each test; hovering the
+        // test name shows where they are defined
+        for test in self.fixture_db.find_test_autouse_fixtures(&file_path) {
+            let line = Self::internal_line_to_lsp(test.line);
+            let names: Vec<&str> = test.fixtures.iter().map(|f| f.name.as_str()).collect();
+            let title = format!("{} autouse ({})", names.len(), names.join(", "));
+
+            lenses.push(CodeLens {
+                range: Self::create_range(line, 0, line, 0),
+                command: Some(Command {
+                    title,
+                    // Informational only: there is nothing to run on click
+                    command: String::new(),
+                    arguments: None,
+                }),
+                data: None,
+            });
+        }
+
         info!("Returning {} code lenses for {:?}", lenses.len(), file_path);
 
         if lenses.is_empty() {
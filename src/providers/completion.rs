@@ -98,8 +98,9 @@ fn make_sort_text(priority: u8, fixture_name: &str) -> String {
 }
 
 /// Build a detail string for a fixture completion item.
-/// Format: `(scope) [origin]`
+/// Format: `(scope) N params [origin]`
 /// - scope is omitted when it's the default "function"
+/// - the param count is only added for parametrized fixtures
 /// - origin tag is only added for plugin or third-party fixtures
 fn make_fixture_detail(fixture: &FixtureDefinition) -> String {
     let mut parts = Vec::new();
@@ -109,6 +110,15 @@ fn make_fixture_detail(fixture: &FixtureDefinition) -> String {
         parts.push(format!("({})", fixture.scope.as_str()));
     }
 
+    // Add the number of params for parametrized fixtures
+    if !fixture.params.is_empty() {
+        parts.push(format!(
+            "{} param{}",
+            fixture.params.len(),
+            if fixture.params.len() == 1 { "" } else { "s" }
+        ));
+    }
+
     // Add origin tag
     if fixture.is_third_party {
         parts.push("[third-party]".to_string());
@@ -183,11 +193,12 @@ impl Backend {
                 info!("Completion context: {:?}", ctx);
 
                 // Get workspace root for formatting documentation
-                let workspace_root = self.workspace_root.read().await.clone();
+                let workspace_root = self.workspace_root_for(&file_path).await;
 
                 match ctx {
                     CompletionContext::FunctionSignature {
                         function_name,
+                        function_line,
                         is_fixture,
                         declared_params,
                         fixture_scope,
@@ -207,6 +218,7 @@ impl Backend {
                         return Ok(Some(self.create_fixture_completions(
                             &file_path,
                             &declared_params,
+                            function_line,
                             workspace_root.as_ref(),
                             &opts,
                         )));
@@ -238,14 +250,47 @@ impl Backend {
                         )));
                     }
                     CompletionContext::UsefixturesDecorator
-                    | CompletionContext::ParametrizeIndirect => {
-                        // In decorator - suggest fixture names as strings
+                    | CompletionContext::ParametrizeIndirect
+                    | CompletionContext::FixtureValueLookup => {
+                        // In decorator or getfixturevalue - suggest fixture names as strings
                         return Ok(Some(self.create_string_fixture_completions(
                             &file_path,
+                            Self::lsp_line_to_internal(position.line),
                             workspace_root.as_ref(),
                             insert_prefix,
                         )));
                     }
+                    CompletionContext::FixtureScopeValue {
+                        in_string,
+                        callables,
+                    } => {
+                        return Ok(Some(create_scope_value_completions(in_string, &callables)));
+                    }
+                    CompletionContext::FixtureAutouseValue => {
+                        return Ok(Some(CompletionResponse::Array(bool_value_completions(&[
+                            (
+                                "True",
+                                "Run the fixture for every test that can see it, without being requested",
+                            ),
+                            ("False", "Only run the fixture when requested (default)"),
+                        ]))));
+                    }
+                    CompletionContext::ParametrizeIndirectValue { argnames } => {
+                        return Ok(Some(create_indirect_value_completions(&argnames)));
+                    }
+                    CompletionContext::ParametrizeArgnames {
+                        function_name,
+                        function_line,
+                        params,
+                    } => {
+                        return Ok(Some(self.create_argnames_completions(
+                            &file_path,
+                            &function_name,
+                            function_line,
+                            &params,
+                            insert_prefix,
+                        )));
+                    }
                 }
             } else {
                 info!("No completion context found");
@@ -261,10 +306,13 @@ impl Backend {
         &self,
         file_path: &std::path::Path,
         declared_params: &[String],
+        function_line: usize,
         workspace_root: Option<&PathBuf>,
         opts: &CompletionOpts<'_>,
     ) -> CompletionResponse {
-        let available = self.fixture_db.get_available_fixtures(file_path);
+        let available = self
+            .fixture_db
+            .get_available_fixtures_at_line(file_path, function_line);
         let enriched =
             filter_and_enrich_fixtures(available, file_path, Some(declared_params), opts);
 
@@ -302,7 +350,9 @@ impl Backend {
         workspace_root: Option<&PathBuf>,
         opts: &CompletionOpts<'_>,
     ) -> CompletionResponse {
-        let available = self.fixture_db.get_available_fixtures(file_path);
+        let available = self
+            .fixture_db
+            .get_available_fixtures_at_line(file_path, function_line);
         let enriched =
             filter_and_enrich_fixtures(available, file_path, Some(declared_params), opts);
 
@@ -365,16 +415,67 @@ impl Backend {
         CompletionResponse::Array(items)
     }
 
+    /// Create completion items for the argnames string of
+    /// `@pytest.mark.parametrize`: the parameters of the decorated function
+    /// not listed yet.
+    pub(crate) fn create_argnames_completions(
+        &self,
+        file_path: &std::path::Path,
+        function_name: &str,
+        function_line: usize,
+        params: &[String],
+        insert_prefix: &str,
+    ) -> CompletionResponse {
+        let items = params
+            .iter()
+            .enumerate()
+            .map(|(i, param)| {
+                let overridden = self
+                    .fixture_db
+                    .find_closest_definition_at_line(file_path, param, function_line);
+                let documentation = match overridden {
+                    Some(def) => format!(
+                        "Parameter of `{}`. Parametrizing it overrides the fixture `{}` ({} scope), unless listed in `indirect`.",
+                        function_name,
+                        def.name,
+                        def.scope.as_str()
+                    ),
+                    None => format!("Parameter of `{}`.", function_name),
+                };
+                CompletionItem {
+                    label: param.clone(),
+                    kind: Some(CompletionItemKind::VARIABLE),
+                    detail: Some(format!("parameter of {}", function_name)),
+                    documentation: Some(Documentation::MarkupContent(MarkupContent {
+                        kind: MarkupKind::Markdown,
+                        value: documentation,
+                    })),
+                    insert_text: Some(format!("{}{}", insert_prefix, param)),
+                    insert_text_format: Some(InsertTextFormat::PLAIN_TEXT),
+                    // Keep the order of the signature
+                    sort_text: Some(format!("{:03}", i)),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        CompletionResponse::Array(items)
+    }
+
     /// Create completion items for fixture names as strings (for decorators)
-    /// Used in @pytest.mark.usefixtures("...") and @pytest.mark.parametrize(..., indirect=["..."])
+    /// Used in @pytest.mark.usefixtures("..."), @pytest.mark.parametrize(..., indirect=["..."])
+    /// and request.getfixturevalue("...")
     /// No scope filtering applied here (decision #3).
     pub(crate) fn create_string_fixture_completions(
         &self,
         file_path: &std::path::Path,
+        line: usize,
         workspace_root: Option<&PathBuf>,
         insert_prefix: &str,
     ) -> CompletionResponse {
-        let available = self.fixture_db.get_available_fixtures(file_path);
+        let available = self
+            .fixture_db
+            .get_available_fixtures_at_line(file_path, line);
         let no_filter_opts = CompletionOpts {
             fixture_scope: None,
             current_fixture_name: None,
@@ -407,6 +508,100 @@ impl Backend {
     }
 }
 
+/// Scopes accepted by `@pytest.fixture(scope=...)`, narrowest first.
+const SCOPE_VALUES: &[(&str, &str)] = &[
+    ("function", "Default: set up and torn down for each test"),
+    ("class", "Shared by the tests of a class"),
+    ("module", "Shared by the tests of a module"),
+    ("package", "Shared by the tests of a package"),
+    ("session", "Shared by every test of the session"),
+];
+
+/// A completion item for a keyword argument value, kept in the given order.
+fn value_completion(
+    label: String,
+    insert_text: String,
+    description: &str,
+    index: usize,
+) -> CompletionItem {
+    CompletionItem {
+        label,
+        kind: Some(CompletionItemKind::VALUE),
+        detail: Some(description.to_string()),
+        insert_text: Some(insert_text),
+        insert_text_format: Some(InsertTextFormat::PLAIN_TEXT),
+        sort_text: Some(format!("{:03}", index)),
+        ..Default::default()
+    }
+}
+
+/// Create completion items for `scope=`. Scope names are quoted unless the
+/// string is already open; callables in the file taking
+/// `(fixture_name, config)` are offered as dynamic scopes.
+fn create_scope_value_completions(in_string: bool, callables: &[String]) -> CompletionResponse {
+    let scopes = SCOPE_VALUES.iter().map(|(scope, description)| {
+        let text = if in_string {
+            scope.to_string()
+        } else {
+            format!("\"{}\"", scope)
+        };
+        (text.clone(), text, description.to_string())
+    });
+    let callables = callables.iter().map(|name| {
+        (
+            name.clone(),
+            name.clone(),
+            "Dynamic scope: called with `fixture_name` and `config`".to_string(),
+        )
+    });
+
+    let items = scopes
+        .chain(callables)
+        .enumerate()
+        .map(|(i, (label, insert_text, description))| {
+            value_completion(label, insert_text, &description, i)
+        })
+        .collect();
+    CompletionResponse::Array(items)
+}
+
+/// Create completion items for a boolean keyword argument.
+fn bool_value_completions(values: &[(&str, &str)]) -> Vec<CompletionItem> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, (value, description))| {
+            value_completion(value.to_string(), value.to_string(), description, i)
+        })
+        .collect()
+}
+
+/// Create completion items for `indirect=`: `True`, `False` and a list with
+/// each argname.
+fn create_indirect_value_completions(argnames: &[String]) -> CompletionResponse {
+    let mut items = bool_value_completions(&[
+        (
+            "True",
+            "Pass every argname to the fixture of the same name as `request.param`",
+        ),
+        ("False", "Pass the values directly to the test (default)"),
+    ]);
+    for argname in argnames {
+        let list = format!("[\"{}\"]", argname);
+        let description = format!(
+            "Pass `{}` to the fixture of the same name as `request.param`",
+            argname
+        );
+        items.push(value_completion(
+            list.clone(),
+            list,
+            &description,
+            items.len(),
+        ));
+    }
+    CompletionResponse::Array(items)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -432,6 +627,10 @@ mod tests {
             scope,
             yield_line: None,
             autouse: false,
+            params: vec![],
+            param_ids: vec![],
+            class_name: None,
+            fixture_path: None,
         }
     }
 
@@ -815,6 +1014,15 @@ mod tests {
         assert_eq!(detail, "(session) [third-party]");
     }
 
+    #[test]
+    fn test_make_fixture_detail_parametrized() {
+        let mut fixture = make_fixture("f", FixtureScope::Module);
+        fixture.params = vec!["1".to_string(), "2".to_string()];
+        fixture.param_ids = vec![None, None];
+        let detail = make_fixture_detail(&fixture);
+        assert_eq!(detail, "(module) 2 params");
+    }
+
     // =========================================================================
     // Unit tests for make_sort_text
     // =========================================================================
@@ -862,6 +1070,9 @@ mod tests {
                 scan_task: b.scan_task.clone(),
                 uri_cache: b.uri_cache.clone(),
                 config: b.config.clone(),
+                workspace_roots: b.workspace_roots.clone(),
+                client_settings: b.client_settings.clone(),
+                pull_diagnostics: b.pull_diagnostics.clone(),
             });
             b
         });
@@ -935,7 +1146,7 @@ def test_something(func_fixture):
             current_fixture_name: None,
             insert_prefix: "",
         };
-        let response = backend.create_fixture_completions(&test_path, &declared, None, &opts);
+        let response = backend.create_fixture_completions(&test_path, &declared, 1, None, &opts);
         let items = extract_items(&response);
         assert!(!items.is_empty(), "Should return completion items");
         // All items should have VARIABLE kind
@@ -956,7 +1167,7 @@ def test_something(func_fixture):
             current_fixture_name: None,
             insert_prefix: "",
         };
-        let response = backend.create_fixture_completions(&test_path, &declared, None, &opts);
+        let response = backend.create_fixture_completions(&test_path, &declared, 1, None, &opts);
         let items = extract_items(&response);
         let labels: Vec<&str> = items.iter().map(|i| i.label.as_str()).collect();
         assert!(
@@ -975,7 +1186,7 @@ def test_something(func_fixture):
             current_fixture_name: None,
             insert_prefix: "",
         };
-        let response = backend.create_fixture_completions(&test_path, &declared, None, &opts);
+        let response = backend.create_fixture_completions(&test_path, &declared, 1, None, &opts);
         let items = extract_items(&response);
         let labels: Vec<&str> = items.iter().map(|i| i.label.as_str()).collect();
         assert!(
@@ -998,7 +1209,7 @@ def test_something(func_fixture):
             current_fixture_name: None,
             insert_prefix: "",
         };
-        let response = backend.create_fixture_completions(&test_path, &declared, None, &opts);
+        let response = backend.create_fixture_completions(&test_path, &declared, 1, None, &opts);
         let items = extract_items(&response);
 
         // Find the session_fixture — it should have scope in detail
@@ -1031,7 +1242,7 @@ def test_something(func_fixture):
             current_fixture_name: None,
             insert_prefix: "",
         };
-        let response = backend.create_fixture_completions(&test_path, &declared, None, &opts);
+        let response = backend.create_fixture_completions(&test_path, &declared, 1, None, &opts);
         let items = extract_items(&response);
 
         // All items should have documentation
@@ -1054,8 +1265,13 @@ def test_something(func_fixture):
             current_fixture_name: None,
             insert_prefix: "",
         };
-        let response =
-            backend.create_fixture_completions(&test_path, &declared, Some(&workspace_root), &opts);
+        let response = backend.create_fixture_completions(
+            &test_path,
+            &declared,
+            1,
+            Some(&workspace_root),
+            &opts,
+        );
         let items = extract_items(&response);
         assert!(!items.is_empty());
     }
@@ -1162,7 +1378,7 @@ def test_something(func_fixture):
             current_fixture_name: Some("func_fixture"),
             insert_prefix: "",
         };
-        let response = backend.create_fixture_completions(&file_path, &[], None, &opts);
+        let response = backend.create_fixture_completions(&file_path, &[], 1, None, &opts);
         let items = extract_items(&response);
         let labels: Vec<&str> = items.iter().map(|i| i.label.as_str()).collect();
         assert!(
@@ -1186,7 +1402,7 @@ def test_something(func_fixture):
             current_fixture_name: None,
             insert_prefix: " ",
         };
-        let response = backend.create_fixture_completions(&test_path, &declared, None, &opts);
+        let response = backend.create_fixture_completions(&test_path, &declared, 1, None, &opts);
         let items = extract_items(&response);
         assert!(!items.is_empty());
         for item in items {
@@ -1209,7 +1425,7 @@ def test_something(func_fixture):
             current_fixture_name: None,
             insert_prefix: "",
         };
-        let response = backend.create_fixture_completions(&test_path, &declared, None, &opts);
+        let response = backend.create_fixture_completions(&test_path, &declared, 1, None, &opts);
         let items = extract_items(&response);
         assert!(!items.is_empty());
         for item in items {
@@ -1279,7 +1495,7 @@ def test_empty_params():
     #[test]
     fn test_create_string_fixture_completions_returns_items() {
         let (backend, test_path) = setup_backend_with_fixtures();
-        let response = backend.create_string_fixture_completions(&test_path, None, "");
+        let response = backend.create_string_fixture_completions(&test_path, 1, None, "");
         let items = extract_items(&response);
         assert!(!items.is_empty(), "Should return string completion items");
         // String completions use TEXT kind
@@ -1299,7 +1515,7 @@ def test_empty_params():
     fn test_create_string_fixture_completions_no_scope_filtering() {
         let (backend, test_path) = setup_backend_with_fixtures();
         // String completions should NOT filter by scope
-        let response = backend.create_string_fixture_completions(&test_path, None, "");
+        let response = backend.create_string_fixture_completions(&test_path, 1, None, "");
         let items = extract_items(&response);
         let labels: Vec<&str> = items.iter().map(|i| i.label.as_str()).collect();
         // Both function and session scoped fixtures should be present
@@ -1320,7 +1536,7 @@ def test_empty_params():
         let (backend, test_path) = setup_backend_with_fixtures();
         let workspace_root = PathBuf::from("/tmp/test_backend");
         let response =
-            backend.create_string_fixture_completions(&test_path, Some(&workspace_root), "");
+            backend.create_string_fixture_completions(&test_path, 1, Some(&workspace_root), "");
         let items = extract_items(&response);
         assert!(!items.is_empty());
     }
@@ -1328,7 +1544,7 @@ def test_empty_params():
     #[test]
     fn test_create_string_fixture_completions_has_detail_and_sort() {
         let (backend, test_path) = setup_backend_with_fixtures();
-        let response = backend.create_string_fixture_completions(&test_path, None, "");
+        let response = backend.create_string_fixture_completions(&test_path, 1, None, "");
         let items = extract_items(&response);
 
         let session_item = items.iter().find(|i| i.label == "session_fixture");
@@ -1361,7 +1577,7 @@ def test_empty_params():
             current_fixture_name: None,
             insert_prefix: "",
         };
-        let response = backend.create_fixture_completions(&path, &[], None, &opts);
+        let response = backend.create_fixture_completions(&path, &[], 1, None, &opts);
         let items = extract_items(&response);
         assert!(items.is_empty(), "Empty DB should return no completions");
     }
@@ -1386,7 +1602,7 @@ def test_empty_params():
         let db = Arc::new(FixtureDatabase::new());
         let backend = make_backend_with_db(db);
         let path = PathBuf::from("/tmp/empty/test_file.py");
-        let response = backend.create_string_fixture_completions(&path, None, "");
+        let response = backend.create_string_fixture_completions(&path, 1, None, "");
         let items = extract_items(&response);
         assert!(items.is_empty(), "Empty DB should return no completions");
     }
@@ -0,0 +1,110 @@
+//! Live configuration reload.
+//!
+//! Settings come from each folder's pyproject.toml and pytest configuration
+//! files (watched via `workspace/didChangeWatchedFiles`) and from the client
+//! (`workspace/didChangeConfiguration`). Changes are applied without
+//! restarting the server.
+
+use super::Backend;
+use crate::config::Config;
+use std::path::{Path, PathBuf};
+use tower_lsp_server::ls_types::*;
+use tracing::info;
+
+/// Files that configuration is read from, in a workspace folder's root.
+pub(crate) const CONFIG_FILE_NAMES: &[&str] = &[
+    "pyproject.toml",
+    "pytest.ini",
+    ".pytest.ini",
+    "tox.ini",
+    "setup.cfg",
+];
+
+/// Whether `path` is a file configuration is read from.
+pub(crate) fn is_config_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| CONFIG_FILE_NAMES.contains(&name))
+}
+
+impl Backend {
+    /// Handle `workspace/didChangeConfiguration`: store the client's settings
+    /// and re-apply the configuration of every workspace folder.
+    pub async fn handle_did_change_configuration(&self, params: DidChangeConfigurationParams) {
+        info!("did_change_configuration: {:?}", params.settings);
+        *self.client_settings.write().await = params.settings;
+        self.reload_configuration(None).await;
+    }
+
+    /// Reload the configuration of the given workspace folders (canonical
+    /// roots), or of all folders when `roots` is `None`.
+    ///
+    /// Folders whose exclude patterns or pytest collection options changed are
+    /// rescanned; diagnostics are re-published either way.
+    pub async fn reload_configuration(&self, roots: Option<&[PathBuf]>) {
+        let settings = self.client_settings.read().await.clone();
+
+        let mut rescan = Vec::new();
+        {
+            let mut tracked = self.workspace_roots.write().await;
+            for root in tracked.iter_mut() {
+                if roots.is_some_and(|roots| !roots.contains(&root.path)) {
+                    continue;
+                }
+                let config = Config::load_with_settings(&root.original_path, &settings);
+                info!("Reloaded config for {:?}: {:?}", root.original_path, config);
+                let needs_rescan = config.scan_settings_differ(&root.config);
+                root.config = config;
+                if needs_rescan {
+                    rescan.push(root.clone());
+                }
+            }
+        }
+
+        // Keep the primary folder's mirror in sync
+        let primary = self.workspace_roots.read().await.first().cloned();
+        if primary.is_some() {
+            self.set_primary_root(primary.as_ref()).await;
+        }
+
+        for root in &rescan {
+            info!("Scan settings changed, rescanning {:?}", root.original_path);
+            self.forget_root_keeping_open_documents(&root.path);
+            self.fixture_db
+                .set_root_pytest_options(&root.path, root.config.pytest.clone());
+            self.fixture_db
+                .set_root_venv_settings(&root.path, root.config.venv_settings(&root.path));
+            self.fixture_db
+                .set_root_fixture_paths(&root.path, root.config.fixture_path_dirs(&root.path));
+        }
+        if !rescan.is_empty() {
+            self.spawn_workspace_scan(rescan).await;
+        }
+
+        self.publish_diagnostics_for_open_documents().await;
+        self.refresh_pull_diagnostics().await;
+    }
+
+    /// Forget everything found under a workspace folder, except that open
+    /// documents are re-analyzed from the editor's content right away.
+    fn forget_root_keeping_open_documents(&self, root: &Path) {
+        let open_documents: Vec<(PathBuf, i32, std::sync::Arc<String>)> = self
+            .fixture_db
+            .document_versions
+            .iter()
+            .filter(|entry| entry.key().starts_with(root))
+            .filter_map(|entry| {
+                let content = self.fixture_db.file_cache.get(entry.key())?.clone();
+                Some((entry.key().clone(), *entry.value(), content))
+            })
+            .collect();
+
+        self.fixture_db.remove_workspace_root(root);
+
+        for (file_path, version, content) in open_documents {
+            self.fixture_db
+                .set_document_version(file_path.clone(), version);
+            self.fixture_db.analyze_file(file_path, &content);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Go-to-declaration provider for pytest fixtures.
+//!
+//! The "declaration" of an overriding fixture is the definition it shadows:
+//! the parent conftest.py, plugin or base class fixture of the same name.
+
+use super::Backend;
+use tower_lsp_server::jsonrpc::Result;
+use tower_lsp_server::ls_types::request::{GotoDeclarationParams, GotoDeclarationResponse};
+use tower_lsp_server::ls_types::*;
+use tracing::info;
+
+impl Backend {
+    /// Handle goto_declaration request.
+    ///
+    /// Works on fixture definitions and on usages (which resolve to their
+    /// definition first). Returns `None` when the fixture overrides nothing.
+    pub async fn handle_goto_declaration(
+        &self,
+        params: GotoDeclarationParams,
+    ) -> Result<Option<GotoDeclarationResponse>> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+
+        info!(
+            "goto_declaration request: uri={:?}, line={}, char={}",
+            uri, position.line, position.character
+        );
+
+        let Some(file_path) = self.uri_to_path(&uri) else {
+            return Ok(None);
+        };
+        let Some(definition) = self.fixture_db.find_fixture_or_definition_at_position(
+            &file_path,
+            position.line,
+            position.character,
+        ) else {
+            info!("No fixture definition found");
+            return Ok(None);
+        };
+        let Some(overridden) = self.fixture_db.find_overridden_definition(&definition) else {
+            info!(
+                "Fixture {} does not override another definition",
+                definition.name
+            );
+            return Ok(None);
+        };
+
+        let Some(overridden_uri) = self.path_to_uri(&overridden.file_path) else {
+            return Ok(None);
+        };
+        let line = Self::internal_line_to_lsp(overridden.line);
+        let location = Location {
+            uri: overridden_uri,
+            range: Self::create_point_range(line, 0),
+        };
+        info!("Returning overridden definition: {:?}", location);
+        Ok(Some(GotoDeclarationResponse::Scalar(location)))
+    }
+}
@@ -1,103 +1,367 @@
 //! Diagnostics provider for pytest fixtures.
+//!
+//! Diagnostics are pushed via `textDocument/publishDiagnostics`, or pulled by
+//! clients that support LSP 3.17 pull diagnostics (`textDocument/diagnostic`
+//! and `workspace/diagnostic`).
 
-use super::Backend;
+use super::{innermost_root, Backend};
+use crate::config::Config;
+use crate::fixtures::FixtureDatabase;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use tower_lsp_server::jsonrpc::Result;
 use tower_lsp_server::ls_types::*;
 use tracing::info;
 
-impl Backend {
-    /// Publish diagnostics for undeclared fixtures and circular dependencies in a file
-    pub async fn publish_diagnostics_for_file(&self, uri: &Uri, file_path: &std::path::Path) {
-        let mut diagnostics: Vec<Diagnostic> = Vec::new();
-
-        // Get config to check for disabled diagnostics
-        let config = self.config.read().await;
-        let config = &*config; // Dereference the RwLockReadGuard
-
-        // Collect undeclared fixture diagnostics (if not disabled)
-        if !config.is_diagnostic_disabled("undeclared-fixture") {
-            let undeclared = self.fixture_db.get_undeclared_fixtures(file_path);
-            for fixture in undeclared {
-                let line = Self::internal_line_to_lsp(fixture.line);
-                diagnostics.push(Diagnostic {
-                    range: Self::create_range(
-                        line,
-                        fixture.start_char as u32,
-                        line,
-                        fixture.end_char as u32,
-                    ),
-                    severity: Some(DiagnosticSeverity::WARNING),
-                    code: Some(NumberOrString::String("undeclared-fixture".to_string())),
-                    code_description: None,
-                    source: Some("pytest-lsp".to_string()),
-                    message: format!(
-                        "Fixture '{}' is used but not declared as a parameter",
-                        fixture.name
-                    ),
-                    related_information: None,
-                    tags: None,
-                    data: None,
-                });
-            }
-        }
+/// Identifier advertised for pull diagnostics.
+pub const DIAGNOSTIC_IDENTIFIER: &str = "pytest-lsp";
 
-        // Collect circular dependency diagnostics (if not disabled)
-        if !config.is_diagnostic_disabled("circular-dependency") {
-            let cycles = self.fixture_db.detect_fixture_cycles_in_file(file_path);
-            for cycle in cycles {
-                let line = Self::internal_line_to_lsp(cycle.fixture.line);
-                let cycle_str = cycle.cycle_path.join(" → ");
-                diagnostics.push(Diagnostic {
-                    range: Self::create_range(
-                        line,
-                        cycle.fixture.start_char as u32,
-                        line,
-                        cycle.fixture.end_char as u32,
-                    ),
-                    severity: Some(DiagnosticSeverity::ERROR),
-                    code: Some(NumberOrString::String("circular-dependency".to_string())),
-                    code_description: None,
-                    source: Some("pytest-lsp".to_string()),
-                    message: format!("Circular fixture dependency detected: {}", cycle_str),
-                    related_information: None,
-                    tags: None,
-                    data: None,
-                });
-            }
+impl Backend {
+    /// Publish diagnostics for undeclared, unknown and circular fixtures in a file.
+    ///
+    /// Does nothing when the client pulls diagnostics instead.
+    pub async fn publish_diagnostics_for_file(&self, uri: &Uri, file_path: &Path) {
+        if self.pull_diagnostics.load(Ordering::Relaxed) {
+            return;
         }
 
-        // Collect scope mismatch diagnostics (if not disabled)
-        if !config.is_diagnostic_disabled("scope-mismatch") {
-            let mismatches = self.fixture_db.detect_scope_mismatches_in_file(file_path);
-            for mismatch in mismatches {
-                let line = Self::internal_line_to_lsp(mismatch.fixture.line);
-                diagnostics.push(Diagnostic {
-                    range: Self::create_range(
-                        line,
-                        mismatch.fixture.start_char as u32,
-                        line,
-                        mismatch.fixture.end_char as u32,
-                    ),
-                    severity: Some(DiagnosticSeverity::WARNING),
-                    code: Some(NumberOrString::String("scope-mismatch".to_string())),
-                    code_description: None,
-                    source: Some("pytest-lsp".to_string()),
-                    message: format!(
-                        "{}-scoped fixture '{}' depends on {}-scoped fixture '{}'",
-                        mismatch.fixture.scope.as_str(),
-                        mismatch.fixture.name,
-                        mismatch.dependency.scope.as_str(),
-                        mismatch.dependency.name
-                    ),
-                    related_information: None,
-                    tags: None,
-                    data: None,
-                });
-            }
-        }
+        let config = self.config_for(file_path).await;
+        let diagnostics = collect_file_diagnostics(&self.fixture_db, file_path, &config);
 
         info!("Publishing {} diagnostics for {:?}", diagnostics.len(), uri);
         self.client
             .publish_diagnostics(uri.clone(), diagnostics, None)
             .await;
     }
+
+    /// Re-publish diagnostics for every open document, e.g. after a workspace
+    /// scan or a configuration change.
+    pub async fn publish_diagnostics_for_open_documents(&self) {
+        if self.pull_diagnostics.load(Ordering::Relaxed) {
+            return;
+        }
+        let open_documents: Vec<(PathBuf, Uri)> = self
+            .uri_cache
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        for (file_path, uri) in open_documents {
+            self.publish_diagnostics_for_file(&uri, &file_path).await;
+        }
+    }
+
+    /// Ask a pull-diagnostics client to re-pull after fixture data changed.
+    pub async fn refresh_pull_diagnostics(&self) {
+        if !self.pull_diagnostics.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Err(e) = self.client.workspace_diagnostic_refresh().await {
+            info!(
+                "Diagnostic refresh request failed (client may not support it): {}",
+                e
+            );
+        }
+    }
+
+    /// Result ID for a file's diagnostics.
+    ///
+    /// Changes whenever any fixture definition, the file's content or the set
+    /// of disabled diagnostics changes, so unchanged files can be reported as
+    /// `unchanged` without recomputing them.
+    fn diagnostic_result_id(&self, file_path: &Path, config: &Config) -> String {
+        let version = self.fixture_db.definitions_version.load(Ordering::SeqCst);
+        let mut hasher = DefaultHasher::new();
+        self.fixture_db
+            .get_file_content(file_path)
+            .hash(&mut hasher);
+        config.disabled_diagnostics.hash(&mut hasher);
+        format!("{}-{:016x}", version, hasher.finish())
+    }
+
+    /// Handle `textDocument/diagnostic`.
+    pub async fn handle_document_diagnostic(
+        &self,
+        params: DocumentDiagnosticParams,
+    ) -> Result<DocumentDiagnosticReportResult> {
+        let uri = params.text_document.uri;
+        info!("diagnostic request: {:?}", uri);
+
+        let Some(file_path) = self.uri_to_path(&uri) else {
+            return Ok(empty_document_report());
+        };
+        let config = self.config_for(&file_path).await;
+
+        let result_id = self.diagnostic_result_id(&file_path, &config);
+        if params.previous_result_id.as_deref() == Some(result_id.as_str()) {
+            return Ok(DocumentDiagnosticReportResult::Report(
+                DocumentDiagnosticReport::Unchanged(RelatedUnchangedDocumentDiagnosticReport {
+                    related_documents: None,
+                    unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport {
+                        result_id,
+                    },
+                }),
+            ));
+        }
+
+        let items = collect_file_diagnostics(&self.fixture_db, &file_path, &config);
+        Ok(DocumentDiagnosticReportResult::Report(
+            DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport {
+                related_documents: None,
+                full_document_diagnostic_report: FullDocumentDiagnosticReport {
+                    result_id: Some(result_id),
+                    items,
+                },
+            }),
+        ))
+    }
+
+    /// Handle `workspace/diagnostic`: report every workspace test/conftest file.
+    pub async fn handle_workspace_diagnostic(
+        &self,
+        params: WorkspaceDiagnosticParams,
+    ) -> Result<WorkspaceDiagnosticReportResult> {
+        info!("workspace diagnostic request");
+
+        let roots = self.workspace_roots.read().await;
+        let primary_config = self.config.read().await;
+        let previous: HashMap<Uri, String> = params
+            .previous_result_ids
+            .into_iter()
+            .map(|previous| (previous.uri, previous.value))
+            .collect();
+
+        let mut items = Vec::new();
+        for file_path in self.fixture_db.workspace_files() {
+            let Some(uri) = self.path_to_uri(&file_path) else {
+                continue;
+            };
+            let version = self
+                .fixture_db
+                .document_versions
+                .get(&file_path)
+                .map(|v| i64::from(*v));
+            let config = innermost_root(&roots, &file_path).map_or(&*primary_config, |r| &r.config);
+            let result_id = self.diagnostic_result_id(&file_path, config);
+
+            // Previous result IDs are keyed by the URI the client knows; it may
+            // not match our canonical URI for symlinked paths, so also check
+            // the file path.
+            let previous_id = previous.get(&uri).or_else(|| {
+                previous
+                    .iter()
+                    .find(|(prev_uri, _)| self.uri_to_path(prev_uri).as_ref() == Some(&file_path))
+                    .map(|(_, id)| id)
+            });
+
+            if previous_id == Some(&result_id) {
+                items.push(WorkspaceDocumentDiagnosticReport::Unchanged(
+                    WorkspaceUnchangedDocumentDiagnosticReport {
+                        uri,
+                        version,
+                        unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport {
+                            result_id,
+                        },
+                    },
+                ));
+                continue;
+            }
+
+            let diagnostics = collect_file_diagnostics(&self.fixture_db, &file_path, config);
+            items.push(WorkspaceDocumentDiagnosticReport::Full(
+                WorkspaceFullDocumentDiagnosticReport {
+                    uri,
+                    version,
+                    full_document_diagnostic_report: FullDocumentDiagnosticReport {
+                        result_id: Some(result_id),
+                        items: diagnostics,
+                    },
+                },
+            ));
+        }
+
+        info!("Reporting diagnostics for {} workspace files", items.len());
+        Ok(WorkspaceDiagnosticReportResult::Report(
+            WorkspaceDiagnosticReport { items },
+        ))
+    }
+}
+
+/// An empty full report, for documents we cannot map to a file.
+fn empty_document_report() -> DocumentDiagnosticReportResult {
+    DocumentDiagnosticReportResult::Report(DocumentDiagnosticReport::Full(
+        RelatedFullDocumentDiagnosticReport::default(),
+    ))
+}
+
+/// Compute diagnostics for undeclared, unknown and circular fixtures, scope
+/// mismatches and fixture overrides in a file, skipping disabled codes.
+///
+/// Shared by push and pull diagnostics and by the `check` CLI command.
+pub fn collect_file_diagnostics(
+    fixture_db: &FixtureDatabase,
+    file_path: &Path,
+    config: &Config,
+) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    // Collect undeclared fixture diagnostics (if not disabled)
+    if !config.is_diagnostic_disabled("undeclared-fixture") {
+        let undeclared = fixture_db.get_undeclared_fixtures(file_path);
+        for fixture in undeclared {
+            let line = Backend::internal_line_to_lsp(fixture.line);
+            diagnostics.push(Diagnostic {
+                range: Backend::create_range(
+                    line,
+                    fixture.start_char as u32,
+                    line,
+                    fixture.end_char as u32,
+                ),
+                severity: Some(DiagnosticSeverity::WARNING),
+                code: Some(NumberOrString::String("undeclared-fixture".to_string())),
+                code_description: None,
+                source: Some("pytest-lsp".to_string()),
+                message: format!(
+                    "Fixture '{}' is used but not declared as a parameter",
+                    fixture.name
+                ),
+                related_information: None,
+                tags: None,
+                data: None,
+            });
+        }
+    }
+
+    // Collect unknown fixture diagnostics (if not disabled)
+    if !config.is_diagnostic_disabled("unknown-fixture") {
+        let unknown = fixture_db.detect_unknown_fixtures_in_file(file_path);
+        for usage in unknown {
+            let line = Backend::internal_line_to_lsp(usage.line);
+            diagnostics.push(Diagnostic {
+                range: Backend::create_range(
+                    line,
+                    usage.start_char as u32,
+                    line,
+                    usage.end_char as u32,
+                ),
+                severity: Some(DiagnosticSeverity::WARNING),
+                code: Some(NumberOrString::String("unknown-fixture".to_string())),
+                code_description: None,
+                source: Some("pytest-lsp".to_string()),
+                message: format!("Fixture '{}' not found", usage.name),
+                related_information: None,
+                tags: None,
+                data: None,
+            });
+        }
+    }
+
+    // Collect circular dependency diagnostics (if not disabled)
+    if !config.is_diagnostic_disabled("circular-dependency") {
+        let cycles = fixture_db.detect_fixture_cycles_in_file(file_path);
+        for cycle in cycles {
+            let line = Backend::internal_line_to_lsp(cycle.fixture.line);
+            let cycle_str = cycle.cycle_path.join(" → ");
+            diagnostics.push(Diagnostic {
+                range: Backend::create_range(
+                    line,
+                    cycle.fixture.start_char as u32,
+                    line,
+                    cycle.fixture.end_char as u32,
+                ),
+                severity: Some(DiagnosticSeverity::ERROR),
+                code: Some(NumberOrString::String("circular-dependency".to_string())),
+                code_description: None,
+                source: Some("pytest-lsp".to_string()),
+                message: format!("Circular fixture dependency detected: {}", cycle_str),
+                related_information: None,
+                tags: None,
+                data: None,
+            });
+        }
+    }
+
+    // Collect scope mismatch diagnostics (if not disabled)
+    if !config.is_diagnostic_disabled("scope-mismatch") {
+        let mismatches = fixture_db.detect_scope_mismatches_in_file(file_path);
+        for mismatch in mismatches {
+            let line = Backend::internal_line_to_lsp(mismatch.fixture.line);
+            diagnostics.push(Diagnostic {
+                range: Backend::create_range(
+                    line,
+                    mismatch.fixture.start_char as u32,
+                    line,
+                    mismatch.fixture.end_char as u32,
+                ),
+                severity: Some(DiagnosticSeverity::WARNING),
+                code: Some(NumberOrString::String("scope-mismatch".to_string())),
+                code_description: None,
+                source: Some("pytest-lsp".to_string()),
+                message: format!(
+                    "{}-scoped fixture '{}' depends on {}-scoped fixture '{}'",
+                    mismatch.fixture.scope.as_str(),
+                    mismatch.fixture.name,
+                    mismatch.dependency.scope.as_str(),
+                    mismatch.dependency.name
+                ),
+                related_information: None,
+                tags: None,
+                data: None,
+            });
+        }
+    }
+
+    // Collect fixture override diagnostics (if not disabled)
+    if !config.is_diagnostic_disabled("fixture-override") {
+        let overrides = fixture_db.detect_fixture_overrides_in_file(file_path);
+        for fixture_override in overrides {
+            let fixture = &fixture_override.fixture;
+            let overridden = &fixture_override.overridden;
+            let line = Backend::internal_line_to_lsp(fixture.line);
+            let related_information = Uri::from_file_path(&overridden.file_path).map(|uri| {
+                let overridden_line = Backend::internal_line_to_lsp(overridden.line);
+                vec![DiagnosticRelatedInformation {
+                    location: Location {
+                        uri,
+                        range: Backend::create_range(
+                            overridden_line,
+                            overridden.start_char as u32,
+                            overridden_line,
+                            overridden.end_char as u32,
+                        ),
+                    },
+                    message: format!("Overridden definition of '{}'", overridden.name),
+                }]
+            });
+            diagnostics.push(Diagnostic {
+                range: Backend::create_range(
+                    line,
+                    fixture.start_char as u32,
+                    line,
+                    fixture.end_char as u32,
+                ),
+                severity: Some(DiagnosticSeverity::INFORMATION),
+                code: Some(NumberOrString::String("fixture-override".to_string())),
+                code_description: None,
+                source: Some("pytest-lsp".to_string()),
+                message: format!(
+                    "Fixture '{}' overrides the definition in {}:{}",
+                    fixture.name,
+                    overridden
+                        .file_path
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy(),
+                    overridden.line
+                ),
+                related_information,
+                tags: None,
+                data: None,
+            });
+        }
+    }
+
+    diagnostics
 }
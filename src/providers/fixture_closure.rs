@@ -0,0 +1,46 @@
+//! Custom `pytest/fixtureClosure` request.
+//!
+//! Returns the fixtures pytest sets up for the test at a position, in setup
+//! order, so editors can answer "what will pytest set up?" when a test fails
+//! during setup.
+
+use super::Backend;
+use tower_lsp_server::jsonrpc::Result;
+use tower_lsp_server::ls_types::*;
+use tracing::info;
+
+/// Method name of the custom request.
+pub const FIXTURE_CLOSURE_METHOD: &str = "pytest/fixtureClosure";
+
+impl Backend {
+    /// Handle `pytest/fixtureClosure`. Returns `null` when the position is not
+    /// inside a test function.
+    pub async fn handle_fixture_closure(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> Result<Option<serde_json::Value>> {
+        let uri = params.text_document.uri;
+        let position = params.position;
+        info!(
+            "{} request: uri={:?}, line={}",
+            FIXTURE_CLOSURE_METHOD, uri, position.line
+        );
+
+        let Some(file_path) = self.uri_to_path(&uri) else {
+            return Ok(None);
+        };
+        let Some(closure) = self
+            .fixture_db
+            .compute_fixture_closure_at_line(&file_path, Self::lsp_line_to_internal(position.line))
+        else {
+            return Ok(None);
+        };
+
+        let root = self
+            .workspace_root_for(&file_path)
+            .await
+            .or_else(|| file_path.parent().map(|dir| dir.to_path_buf()))
+            .unwrap_or_default();
+        Ok(Some(closure.to_json(&root)))
+    }
+}
@@ -30,7 +30,7 @@ impl Backend {
                 info!("Found fixture definition for hover: {:?}", definition.name);
 
                 // Get workspace root for formatting documentation
-                let workspace_root = self.workspace_root.read().await.clone();
+                let workspace_root = self.workspace_root_for(&file_path).await;
 
                 // Build hover content using shared formatter
                 let content =
@@ -44,6 +44,55 @@ impl Backend {
                     }),
                     range: None,
                 }));
+            } else if let Some(argument) = self.fixture_db.find_parametrized_argument_at_position(
+                &file_path,
+                position.line,
+                position.character,
+            ) {
+                info!("Found parametrized argument for hover: {:?}", argument.name);
+
+                return Ok(Some(Hover {
+                    contents: HoverContents::Markup(MarkupContent {
+                        kind: MarkupKind::Markdown,
+                        value: Self::format_parametrized_argument_documentation(&argument),
+                    }),
+                    range: None,
+                }));
+            } else if let Some(fixture) = self.fixture_db.find_request_param_at_position(
+                &file_path,
+                position.line,
+                position.character,
+            ) {
+                info!(
+                    "Found request.param of fixture for hover: {:?}",
+                    fixture.name
+                );
+
+                return Ok(Some(Hover {
+                    contents: HoverContents::Markup(MarkupContent {
+                        kind: MarkupKind::Markdown,
+                        value: Self::format_request_param_documentation(&fixture),
+                    }),
+                    range: None,
+                }));
+            } else if let Some(test) = self.fixture_db.find_test_autouse_at_position(
+                &file_path,
+                position.line,
+                position.character,
+            ) {
+                info!(
+                    "Found autouse fixtures of test for hover: {:?}",
+                    test.test_name
+                );
+
+                let workspace_root = self.workspace_root_for(&file_path).await;
+                return Ok(Some(Hover {
+                    contents: HoverContents::Markup(MarkupContent {
+                        kind: MarkupKind::Markdown,
+                        value: Self::format_autouse_documentation(&test, workspace_root.as_ref()),
+                    }),
+                    range: None,
+                }));
             } else {
                 info!("No fixture found for hover");
             }
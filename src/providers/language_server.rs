@@ -4,97 +4,60 @@
 //! library crate compile the impl, making `Backend` usable in integration
 //! tests via `LspService::new`.
 
-use std::sync::Arc;
-
 use tower_lsp_server::jsonrpc::Result;
-use tower_lsp_server::ls_types::request::{GotoImplementationParams, GotoImplementationResponse};
+use tower_lsp_server::ls_types::request::{
+    GotoDeclarationParams, GotoDeclarationResponse, GotoImplementationParams,
+    GotoImplementationResponse,
+};
 use tower_lsp_server::ls_types::*;
 use tower_lsp_server::LanguageServer;
-use tracing::{error, info, warn};
+use tracing::{info, warn};
 
 use super::Backend;
-use crate::config;
+use crate::fixtures::{ContentChange, DocumentPosition};
 
 impl LanguageServer for Backend {
     async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
         info!("Initialize request received");
 
+        // Clients that support pull diagnostics request them; pushing as well
+        // would show every problem twice.
+        let client_pulls_diagnostics = params
+            .capabilities
+            .text_document
+            .as_ref()
+            .is_some_and(|text_document| text_document.diagnostic.is_some());
+        self.pull_diagnostics.store(
+            client_pulls_diagnostics,
+            std::sync::atomic::Ordering::Relaxed,
+        );
+
+        // Settings passed at startup override pyproject.toml like later
+        // workspace/didChangeConfiguration settings do
+        if let Some(options) = params.initialization_options.clone() {
+            *self.client_settings.write().await = options;
+        }
+
         // Scan the workspace for fixtures on initialization
         // This is done in a background task to avoid blocking the LSP initialization
-        // Try workspace_folders first (preferred), fall back to deprecated root_uri
-        let root_uri = params
-            .workspace_folders
-            .as_ref()
-            .and_then(|folders| folders.first())
-            .map(|folder| folder.uri.clone())
-            .or_else(|| {
+        // Scan every workspace folder, falling back to the deprecated root_uri
+        let root_uris: Vec<Uri> = match params.workspace_folders.as_ref() {
+            Some(folders) if !folders.is_empty() => {
+                folders.iter().map(|folder| folder.uri.clone()).collect()
+            }
+            _ =>
+            {
                 #[allow(deprecated)]
-                params.root_uri.clone()
-            });
-
-        if let Some(root_uri) = root_uri {
-            if let Some(root_path) = root_uri.to_file_path() {
-                let root_path = root_path.to_path_buf();
-                info!("Starting workspace scan: {:?}", root_path);
-
-                // Store the original workspace root (as client provided it)
-                *self.original_workspace_root.write().await = Some(root_path.clone());
-
-                // Store the canonical workspace root (with symlinks resolved)
-                let canonical_root = root_path
-                    .canonicalize()
-                    .unwrap_or_else(|_| root_path.clone());
-                *self.workspace_root.write().await = Some(canonical_root.clone());
-
-                // Load configuration from pyproject.toml
-                let loaded_config = config::Config::load(&root_path);
-                info!("Loaded config: {:?}", loaded_config);
-                *self.config.write().await = loaded_config;
-
-                // Clone references for the background task
-                let fixture_db = Arc::clone(&self.fixture_db);
-                let client = self.client.clone();
-                let exclude_patterns = self.config.read().await.exclude.clone();
-
-                // Spawn workspace scanning in a background task
-                // This allows the LSP to respond immediately while scanning continues
-                let scan_handle = tokio::spawn(async move {
-                    client
-                        .log_message(
-                            MessageType::INFO,
-                            format!("Scanning workspace: {:?}", root_path),
-                        )
-                        .await;
-
-                    // Run the synchronous scan in a blocking task to avoid blocking the async runtime
-                    let scan_result = tokio::task::spawn_blocking(move || {
-                        fixture_db.scan_workspace_with_excludes(&root_path, &exclude_patterns);
-                    })
-                    .await;
-
-                    match scan_result {
-                        Ok(()) => {
-                            info!("Workspace scan complete");
-                            client
-                                .log_message(MessageType::INFO, "Workspace scan complete")
-                                .await;
-                        }
-                        Err(e) => {
-                            error!("Workspace scan failed: {:?}", e);
-                            client
-                                .log_message(
-                                    MessageType::ERROR,
-                                    format!("Workspace scan failed: {:?}", e),
-                                )
-                                .await;
-                        }
-                    }
-                });
-
-                // Store the handle so we can cancel it on shutdown
-                *self.scan_task.lock().await = Some(scan_handle);
+                params.root_uri.clone().into_iter().collect()
             }
-        } else {
+        };
+        let root_paths: Vec<std::path::PathBuf> = root_uris
+            .iter()
+            .filter_map(|uri| uri.to_file_path())
+            .map(|path| path.to_path_buf())
+            .collect();
+
+        if root_paths.is_empty() {
             warn!("No root URI provided in initialize - workspace scanning disabled");
             self.client
                 .log_message(
@@ -102,6 +65,8 @@ impl LanguageServer for Backend {
                     "No workspace root provided - fixture analysis disabled",
                 )
                 .await;
+        } else {
+            self.add_workspace_roots(root_paths).await;
         }
 
         info!("Returning initialize result with capabilities");
@@ -112,10 +77,11 @@ impl LanguageServer for Backend {
             }),
             capabilities: ServerCapabilities {
                 definition_provider: Some(OneOf::Left(true)),
+                declaration_provider: Some(DeclarationCapability::Simple(true)),
                 hover_provider: Some(HoverProviderCapability::Simple(true)),
                 references_provider: Some(OneOf::Left(true)),
                 text_document_sync: Some(TextDocumentSyncCapability::Kind(
-                    TextDocumentSyncKind::FULL,
+                    TextDocumentSyncKind::INCREMENTAL,
                 )),
                 code_action_provider: Some(CodeActionProviderCapability::Options(
                     CodeActionOptions {
@@ -143,14 +109,54 @@ impl LanguageServer for Backend {
                     },
                     completion_item: None,
                 }),
+                signature_help_provider: Some(SignatureHelpOptions {
+                    trigger_characters: Some(vec!["(".to_string(), ",".to_string()]),
+                    retrigger_characters: None,
+                    work_done_progress_options: WorkDoneProgressOptions {
+                        work_done_progress: None,
+                    },
+                }),
                 document_symbol_provider: Some(OneOf::Left(true)),
                 workspace_symbol_provider: Some(OneOf::Left(true)),
                 code_lens_provider: Some(CodeLensOptions {
                     resolve_provider: Some(false),
                 }),
                 inlay_hint_provider: Some(OneOf::Left(true)),
+                diagnostic_provider: Some(DiagnosticServerCapabilities::Options(
+                    DiagnosticOptions {
+                        identifier: Some(super::diagnostics::DIAGNOSTIC_IDENTIFIER.to_string()),
+                        inter_file_dependencies: true,
+                        workspace_diagnostics: true,
+                        work_done_progress_options: WorkDoneProgressOptions {
+                            work_done_progress: None,
+                        },
+                    },
+                )),
+                semantic_tokens_provider: Some(
+                    SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        SemanticTokensOptions {
+                            legend: super::semantic_tokens::legend(),
+                            range: Some(true),
+                            full: Some(SemanticTokensFullOptions::Bool(true)),
+                            ..Default::default()
+                        },
+                    ),
+                ),
                 implementation_provider: Some(ImplementationProviderCapability::Simple(true)),
                 call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
+                rename_provider: Some(OneOf::Right(RenameOptions {
+                    prepare_provider: Some(true),
+                    work_done_progress_options: WorkDoneProgressOptions {
+                        work_done_progress: None,
+                    },
+                })),
+                workspace: Some(WorkspaceServerCapabilities {
+                    workspace_folders: Some(WorkspaceFoldersServerCapabilities {
+                        supported: Some(true),
+                        change_notifications: Some(OneOf::Left(true)),
+                    }),
+                    file_operations: None,
+                }),
                 ..Default::default()
             },
         })
@@ -162,29 +168,54 @@ impl LanguageServer for Backend {
             .log_message(MessageType::INFO, "pytest-language-server initialized")
             .await;
 
-        // Register a file watcher for __init__.py create/delete events.
-        // When package markers change, `file_path_to_module_path()` results
-        // (captured in `FixtureDefinition::return_type_imports`) become stale,
-        // so we re-analyze affected fixture files to refresh them.
-        let watch_init_py = Registration {
-            id: "watch-init-py".to_string(),
+        // Register a file watcher for Python files so changes made outside the
+        // editor (e.g. `git checkout`) reach the fixture database. This also
+        // covers __init__.py create/delete events: when package markers change,
+        // `file_path_to_module_path()` results (captured in
+        // `FixtureDefinition::return_type_imports`) become stale, so we
+        // re-analyze affected fixture files to refresh them.
+        let watch_python_files = Registration {
+            id: "watch-python-files".to_string(),
+            method: "workspace/didChangeWatchedFiles".to_string(),
+            register_options: Some(
+                serde_json::to_value(DidChangeWatchedFilesRegistrationOptions {
+                    watchers: vec![FileSystemWatcher {
+                        glob_pattern: GlobPattern::String("**/*.py".to_string()),
+                        kind: Some(WatchKind::Create | WatchKind::Change | WatchKind::Delete),
+                    }],
+                })
+                .unwrap(),
+            ),
+        };
+
+        // Register a watcher for the files configuration is read from, so
+        // edits apply without restarting the server.
+        let watch_config = Registration {
+            id: "watch-config-files".to_string(),
             method: "workspace/didChangeWatchedFiles".to_string(),
             register_options: Some(
                 serde_json::to_value(DidChangeWatchedFilesRegistrationOptions {
                     watchers: vec![FileSystemWatcher {
-                        glob_pattern: GlobPattern::String("**/__init__.py".to_string()),
-                        kind: Some(WatchKind::Create | WatchKind::Delete),
+                        glob_pattern: GlobPattern::String(format!(
+                            "**/{{{}}}",
+                            super::configuration::CONFIG_FILE_NAMES.join(",")
+                        )),
+                        kind: Some(WatchKind::Create | WatchKind::Change | WatchKind::Delete),
                     }],
                 })
                 .unwrap(),
             ),
         };
 
-        if let Err(e) = self.client.register_capability(vec![watch_init_py]).await {
+        if let Err(e) = self
+            .client
+            .register_capability(vec![watch_python_files, watch_config])
+            .await
+        {
             // Not fatal — file watching is best-effort.  The user can still
             // manually re-open fixture files to trigger re-analysis.
             info!(
-                "Failed to register __init__.py file watcher (client may not support it): {}",
+                "Failed to register file watchers (client may not support it): {}",
                 e
             );
         }
@@ -199,6 +230,8 @@ impl LanguageServer for Backend {
             self.uri_cache.insert(file_path.clone(), uri.clone());
 
             info!("Analyzing file: {:?}", file_path);
+            self.fixture_db
+                .set_document_version(file_path.clone(), params.text_document.version);
             self.fixture_db
                 .analyze_file(file_path.clone(), &params.text_document.text);
 
@@ -211,14 +244,49 @@ impl LanguageServer for Backend {
         let uri = params.text_document.uri.clone();
         info!("did_change: {:?}", uri);
         if let Some(file_path) = self.uri_to_path(&uri) {
-            if let Some(change) = params.content_changes.first() {
-                info!("Re-analyzing file: {:?}", file_path);
-                self.fixture_db
-                    .analyze_file(file_path.clone(), &change.text);
+            let changes: Vec<ContentChange> = params
+                .content_changes
+                .into_iter()
+                .map(|change| ContentChange {
+                    range: change.range.map(|range| {
+                        (
+                            DocumentPosition {
+                                line: range.start.line,
+                                character: range.start.character,
+                            },
+                            DocumentPosition {
+                                line: range.end.line,
+                                character: range.end.character,
+                            },
+                        )
+                    }),
+                    text: change.text,
+                })
+                .collect();
 
+            info!("Re-analyzing file: {:?}", file_path);
+            let definitions_version = self
+                .fixture_db
+                .definitions_version
+                .load(std::sync::atomic::Ordering::SeqCst);
+            if self.fixture_db.update_document(
+                file_path.clone(),
+                params.text_document.version,
+                &changes,
+            ) {
                 // Publish diagnostics for undeclared fixtures
                 self.publish_diagnostics_for_file(&uri, &file_path).await;
 
+                // Only fixture changes here can affect diagnostics in other files
+                if self
+                    .fixture_db
+                    .definitions_version
+                    .load(std::sync::atomic::Ordering::SeqCst)
+                    != definitions_version
+                {
+                    self.refresh_pull_diagnostics().await;
+                }
+
                 // Request inlay hint refresh so editors update hints after edits
                 // (e.g., when user adds/removes type annotations)
                 if let Err(e) = self.client.inlay_hint_refresh().await {
@@ -233,67 +301,15 @@ impl LanguageServer for Backend {
     }
 
     async fn did_change_watched_files(&self, params: DidChangeWatchedFilesParams) {
-        // Re-analyze fixture files whose `return_type_imports` may have become
-        // stale because an `__init__.py` was created or deleted, changing the
-        // result of `file_path_to_module_path()`.
-        for event in &params.changes {
-            if event.typ != FileChangeType::CREATED && event.typ != FileChangeType::DELETED {
-                continue;
-            }
-
-            let Some(init_path) = self.uri_to_path(&event.uri) else {
-                continue;
-            };
-
-            // The __init__.py change affects the directory it lives in and
-            // every directory below it.  Any fixture file at or under that
-            // directory may produce a different module path now.
-            let affected_dir = match init_path.parent() {
-                Some(dir) => dir.to_path_buf(),
-                None => continue,
-            };
-
-            let kind = if event.typ == FileChangeType::CREATED {
-                "created"
-            } else {
-                "deleted"
-            };
-            info!(
-                "__init__.py {} in {:?} — re-analyzing affected fixture files",
-                kind, affected_dir
-            );
-
-            // Collect fixture files that live at or below the affected directory.
-            let files_to_reanalyze: Vec<std::path::PathBuf> = self
-                .fixture_db
-                .file_definitions
-                .iter()
-                .filter(|entry| entry.key().starts_with(&affected_dir))
-                .map(|entry| entry.key().clone())
-                .collect();
-
-            for file_path in files_to_reanalyze {
-                if let Some(content) = self.fixture_db.get_file_content(&file_path) {
-                    info!("Re-analyzing {:?} after __init__.py change", file_path);
-                    self.fixture_db.analyze_file(file_path.clone(), &content);
+        self.handle_did_change_watched_files(params).await;
+    }
 
-                    // Re-publish diagnostics for the file if we have a cached URI.
-                    if let Some(uri) = self.uri_cache.get(&file_path) {
-                        self.publish_diagnostics_for_file(&uri, &file_path).await;
-                    }
-                }
-            }
-        }
+    async fn did_change_configuration(&self, params: DidChangeConfigurationParams) {
+        self.handle_did_change_configuration(params).await;
+    }
 
-        // Refresh inlay hints in case return types changed.
-        if !params.changes.is_empty() {
-            if let Err(e) = self.client.inlay_hint_refresh().await {
-                info!(
-                    "Inlay hint refresh after __init__.py change failed (client may not support it): {}",
-                    e
-                );
-            }
-        }
+    async fn did_change_workspace_folders(&self, params: DidChangeWorkspaceFoldersParams) {
+        self.handle_did_change_workspace_folders(params).await;
     }
 
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
@@ -307,6 +323,20 @@ impl LanguageServer for Backend {
         }
     }
 
+    async fn diagnostic(
+        &self,
+        params: DocumentDiagnosticParams,
+    ) -> Result<DocumentDiagnosticReportResult> {
+        self.handle_document_diagnostic(params).await
+    }
+
+    async fn workspace_diagnostic(
+        &self,
+        params: WorkspaceDiagnosticParams,
+    ) -> Result<WorkspaceDiagnosticReportResult> {
+        self.handle_workspace_diagnostic(params).await
+    }
+
     async fn goto_definition(
         &self,
         params: GotoDefinitionParams,
@@ -314,6 +344,13 @@ impl LanguageServer for Backend {
         self.handle_goto_definition(params).await
     }
 
+    async fn goto_declaration(
+        &self,
+        params: GotoDeclarationParams,
+    ) -> Result<Option<GotoDeclarationResponse>> {
+        self.handle_goto_declaration(params).await
+    }
+
     async fn goto_implementation(
         &self,
         params: GotoImplementationParams,
@@ -329,10 +366,25 @@ impl LanguageServer for Backend {
         self.handle_references(params).await
     }
 
+    async fn prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> Result<Option<PrepareRenameResponse>> {
+        self.handle_prepare_rename(params).await
+    }
+
+    async fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
+        self.handle_rename(params).await
+    }
+
     async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
         self.handle_completion(params).await
     }
 
+    async fn signature_help(&self, params: SignatureHelpParams) -> Result<Option<SignatureHelp>> {
+        self.handle_signature_help(params).await
+    }
+
     async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
         self.handle_code_action(params).await
     }
@@ -360,6 +412,20 @@ impl LanguageServer for Backend {
         self.handle_inlay_hint(params).await
     }
 
+    async fn semantic_tokens_full(
+        &self,
+        params: SemanticTokensParams,
+    ) -> Result<Option<SemanticTokensResult>> {
+        self.handle_semantic_tokens_full(params).await
+    }
+
+    async fn semantic_tokens_range(
+        &self,
+        params: SemanticTokensRangeParams,
+    ) -> Result<Option<SemanticTokensRangeResult>> {
+        self.handle_semantic_tokens_range(params).await
+    }
+
     async fn prepare_call_hierarchy(
         &self,
         params: CallHierarchyPrepareParams,
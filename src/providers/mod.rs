@@ -6,26 +6,59 @@ pub mod call_hierarchy;
 pub mod code_action;
 pub mod code_lens;
 pub mod completion;
+mod configuration;
+pub mod declaration;
 pub mod definition;
 pub mod diagnostics;
 pub mod document_symbol;
+pub mod fixture_closure;
 pub mod hover;
 pub mod implementation;
 pub mod inlay_hint;
 mod language_server;
 pub mod references;
+pub mod rename;
+pub mod semantic_tokens;
+pub mod signature_help;
+mod watched_files;
+mod workspace_folders;
 pub mod workspace_symbol;
 
 use crate::config::Config;
 use crate::fixtures::FixtureDatabase;
 use dashmap::DashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tower_lsp_server::ls_types::*;
 use tower_lsp_server::Client;
 use tracing::warn;
 
+/// A workspace folder with its own configuration.
+#[derive(Debug, Clone)]
+pub struct WorkspaceRoot {
+    /// The canonical root path (resolved symlinks)
+    pub path: PathBuf,
+    /// The root path as provided by the client (may contain symlinks)
+    pub original_path: PathBuf,
+    /// Configuration loaded from this folder's pyproject.toml
+    pub config: Config,
+}
+
+/// The innermost workspace folder containing `file_path`.
+pub(crate) fn innermost_root<'a>(
+    roots: &'a [WorkspaceRoot],
+    file_path: &Path,
+) -> Option<&'a WorkspaceRoot> {
+    roots
+        .iter()
+        .filter(|root| file_path.starts_with(&root.path))
+        .max_by_key(|root| root.path.components().count())
+}
+
 /// The LSP Backend struct containing server state.
+///
+/// All state is shared, so clones (e.g. for background tasks) see the same server.
+#[derive(Clone)]
 pub struct Backend {
     pub client: Client,
     pub fixture_db: Arc<FixtureDatabase>,
@@ -40,6 +73,13 @@ pub struct Backend {
     pub uri_cache: Arc<DashMap<PathBuf, Uri>>,
     /// Configuration loaded from pyproject.toml
     pub config: Arc<tokio::sync::RwLock<Config>>,
+    /// Every workspace folder with its own configuration. The first folder is
+    /// also mirrored in `workspace_root`, `original_workspace_root` and `config`.
+    pub workspace_roots: Arc<tokio::sync::RwLock<Vec<WorkspaceRoot>>>,
+    /// Settings sent by the client, overriding pyproject.toml in every folder
+    pub client_settings: Arc<tokio::sync::RwLock<serde_json::Value>>,
+    /// Whether the client pulls diagnostics (LSP 3.17) instead of receiving pushes
+    pub pull_diagnostics: Arc<std::sync::atomic::AtomicBool>,
 }
 
 impl Backend {
@@ -53,6 +93,27 @@ impl Backend {
             scan_task: Arc::new(tokio::sync::Mutex::new(None)),
             uri_cache: Arc::new(DashMap::new()),
             config: Arc::new(tokio::sync::RwLock::new(Config::default())),
+            workspace_roots: Arc::new(tokio::sync::RwLock::new(Vec::new())),
+            client_settings: Arc::new(tokio::sync::RwLock::new(serde_json::Value::Null)),
+            pull_diagnostics: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+        }
+    }
+
+    /// Configuration for a file: that of its workspace folder, or the primary one.
+    pub async fn config_for(&self, file_path: &Path) -> Config {
+        let roots = self.workspace_roots.read().await;
+        match innermost_root(&roots, file_path) {
+            Some(root) => root.config.clone(),
+            None => self.config.read().await.clone(),
+        }
+    }
+
+    /// Canonical root of the workspace folder containing a file, or the primary root.
+    pub async fn workspace_root_for(&self, file_path: &Path) -> Option<PathBuf> {
+        let roots = self.workspace_roots.read().await;
+        match innermost_root(&roots, file_path) {
+            Some(root) => Some(root.path.clone()),
+            None => self.workspace_root.read().await.clone(),
         }
     }
 
@@ -152,33 +213,17 @@ impl Backend {
     ) -> String {
         let mut content = String::new();
 
-        // Calculate relative path from workspace root
-        let relative_path = if let Some(root) = workspace_root {
-            fixture
-                .file_path
-                .strip_prefix(root)
-                .ok()
-                .and_then(|p| p.to_str())
-                .map(|s| s.to_string())
-                .unwrap_or_else(|| {
-                    fixture
-                        .file_path
-                        .file_name()
-                        .and_then(|f| f.to_str())
-                        .unwrap_or("unknown")
-                        .to_string()
-                })
-        } else {
-            fixture
-                .file_path
-                .file_name()
-                .and_then(|f| f.to_str())
-                .unwrap_or("unknown")
-                .to_string()
-        };
+        let relative_path = Self::display_path(&fixture.file_path, workspace_root);
 
-        // Add "from" line with relative path
-        content.push_str(&format!("**from** `{}`\n", relative_path));
+        // Add "from" line with relative path, and the fixture path it was loaded from
+        match &fixture.fixture_path {
+            Some(dir) => content.push_str(&format!(
+                "**from** `{}` (fixture path `{}`)\n",
+                relative_path,
+                Self::display_path(dir, workspace_root)
+            )),
+            None => content.push_str(&format!("**from** `{}`\n", relative_path)),
+        }
 
         // Add code block with fixture signature
         let return_annotation = if let Some(ref ret_type) = &fixture.return_type {
@@ -192,6 +237,15 @@ impl Backend {
             fixture.name, return_annotation
         ));
 
+        if !fixture.params.is_empty() {
+            content.push_str("\n\n---\n\n");
+            content.push_str(&Self::format_value_list(
+                "param",
+                &fixture.params,
+                &fixture.param_ids,
+            ));
+        }
+
         // Add docstring if present
         if let Some(ref docstring) = fixture.docstring {
             content.push_str("\n\n---\n\n");
@@ -200,4 +254,87 @@ impl Backend {
 
         content
     }
+
+    /// Format hover documentation for a `@pytest.mark.parametrize` argument
+    pub fn format_parametrized_argument_documentation(
+        argument: &crate::fixtures::ParametrizedArgument,
+    ) -> String {
+        let mut content = format!("```python\n{}  # parametrized\n```", argument.name);
+
+        if argument.values.is_empty() {
+            return content;
+        }
+
+        content.push_str("\n\n---\n\n");
+        content.push_str(&Self::format_value_list(
+            "value",
+            &argument.values,
+            &argument.ids,
+        ));
+
+        content
+    }
+
+    /// Format hover documentation for `request.param` inside a parametrized fixture
+    pub fn format_request_param_documentation(
+        fixture: &crate::fixtures::FixtureDefinition,
+    ) -> String {
+        format!(
+            "```python\nrequest.param  # param of fixture {}\n```\n\n---\n\n{}",
+            fixture.name,
+            Self::format_value_list("param", &fixture.params, &fixture.param_ids)
+        )
+    }
+
+    /// Format hover documentation listing the autouse fixtures pytest runs
+    /// for a test, with their locations
+    pub fn format_autouse_documentation(
+        test: &crate::fixtures::autouse::TestAutouse,
+        workspace_root: Option<&PathBuf>,
+    ) -> String {
+        let count = test.fixtures.len();
+        let mut content = format!(
+            "```python\ndef {}(...)  # test\n```\n\n---\n\n**{} autouse fixture{}:**\n",
+            test.test_name,
+            count,
+            if count == 1 { "" } else { "s" }
+        );
+        for fixture in &test.fixtures {
+            content.push_str(&format!(
+                "\n- `{}` ({}) — `{}:{}`",
+                fixture.name,
+                fixture.scope.as_str(),
+                Self::display_path(&fixture.file_path, workspace_root),
+                fixture.line
+            ));
+        }
+        content
+    }
+
+    /// Path relative to the workspace root, or the bare file name outside it
+    fn display_path(path: &std::path::Path, workspace_root: Option<&PathBuf>) -> String {
+        workspace_root
+            .and_then(|root| path.strip_prefix(root).ok())
+            .and_then(|p| p.to_str())
+            .or_else(|| path.file_name().and_then(|f| f.to_str()))
+            .unwrap_or("unknown")
+            .to_string()
+    }
+
+    /// Format a "**N values:**" heading followed by one bullet per value
+    fn format_value_list(noun: &str, values: &[String], ids: &[Option<String>]) -> String {
+        let mut content = format!(
+            "**{} {}{}:**\n",
+            values.len(),
+            noun,
+            if values.len() == 1 { "" } else { "s" }
+        );
+        for (i, value) in values.iter().enumerate() {
+            match ids.get(i).and_then(|id| id.as_deref()) {
+                Some(id) => content.push_str(&format!("\n- `{}` (id: `{}`)", value, id)),
+                None => content.push_str(&format!("\n- `{}`", value)),
+            }
+        }
+        content
+    }
 }
@@ -0,0 +1,122 @@
+//! Rename provider for pytest fixtures.
+//!
+//! Renames a fixture together with every usage that resolves to it, following
+//! pytest's shadowing rules and override chains.
+
+use super::Backend;
+use std::collections::HashMap;
+use tower_lsp_server::jsonrpc::{Error, Result};
+use tower_lsp_server::ls_types::*;
+use tracing::info;
+
+impl Backend {
+    /// Handle prepareRename request.
+    ///
+    /// Returns the range of the fixture name under the cursor, or an error when
+    /// the fixture cannot be renamed (e.g. it comes from a third-party package).
+    pub async fn handle_prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> Result<Option<PrepareRenameResponse>> {
+        let uri = params.text_document.uri;
+        let position = params.position;
+
+        info!(
+            "prepare_rename request: uri={:?}, line={}, char={}",
+            uri, position.line, position.character
+        );
+
+        let Some(file_path) = self.uri_to_path(&uri) else {
+            return Ok(None);
+        };
+
+        let Some((definition, location)) =
+            self.fixture_db
+                .find_rename_target(&file_path, position.line, position.character)
+        else {
+            info!("No renameable fixture at this position");
+            return Ok(None);
+        };
+
+        if definition.is_third_party {
+            return Err(Error::invalid_params(format!(
+                "Cannot rename third-party fixture '{}'",
+                definition.name
+            )));
+        }
+
+        let lsp_line = Self::internal_line_to_lsp(location.line);
+        Ok(Some(PrepareRenameResponse::RangeWithPlaceholder {
+            range: Self::create_range(
+                lsp_line,
+                location.start_char as u32,
+                lsp_line,
+                location.end_char as u32,
+            ),
+            placeholder: definition.name,
+        }))
+    }
+
+    /// Handle rename request.
+    ///
+    /// Produces a workspace edit covering the definition, its override chain,
+    /// parameters, usefixtures/indirect strings and body references.
+    pub async fn handle_rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
+        let uri = params.text_document_position.text_document.uri;
+        let position = params.text_document_position.position;
+        let new_name = params.new_name;
+
+        info!(
+            "rename request: uri={:?}, line={}, char={}, new_name={}",
+            uri, position.line, position.character, new_name
+        );
+
+        let Some(file_path) = self.uri_to_path(&uri) else {
+            return Ok(None);
+        };
+
+        let Some((definition, _)) =
+            self.fixture_db
+                .find_rename_target(&file_path, position.line, position.character)
+        else {
+            info!("No renameable fixture at this position");
+            return Ok(None);
+        };
+
+        let locations = self
+            .fixture_db
+            .plan_fixture_rename(&definition, &new_name)
+            .map_err(Error::invalid_params)?;
+
+        let mut changes: HashMap<Uri, Vec<TextEdit>> = HashMap::new();
+        for location in &locations {
+            let Some(location_uri) = self.path_to_uri(&location.file_path) else {
+                continue;
+            };
+            let lsp_line = Self::internal_line_to_lsp(location.line);
+            changes.entry(location_uri).or_default().push(TextEdit {
+                range: Self::create_range(
+                    lsp_line,
+                    location.start_char as u32,
+                    lsp_line,
+                    location.end_char as u32,
+                ),
+                new_text: new_name.clone(),
+            });
+        }
+
+        info!(
+            "Renaming {} -> {}: {} edit(s) in {} file(s)",
+            definition.name,
+            new_name,
+            locations.len(),
+            changes.len()
+        );
+
+        Ok(Some(WorkspaceEdit {
+            changes: Some(changes),
+            document_changes: None,
+            change_annotations: None,
+        }))
+    }
+}
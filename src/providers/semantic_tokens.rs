@@ -0,0 +1,236 @@
+//! Semantic tokens provider for pytest fixtures.
+//!
+//! Marks fixture definitions, fixture parameters and fixture names in strings
+//! (`usefixtures`, indirect parametrization) so editors can colour injected
+//! fixtures differently from ordinary parameters. Modifiers describe the
+//! resolved fixture: autouse, third-party, plugin or overriding another one.
+
+use super::Backend;
+use crate::fixtures::{FixtureDefinition, FixtureUsage};
+use std::path::Path;
+use tower_lsp_server::jsonrpc::Result;
+use tower_lsp_server::ls_types::*;
+use tracing::info;
+
+/// Token types, indexed by position in the legend.
+const TOKEN_FIXTURE_DEFINITION: u32 = 0;
+const TOKEN_FIXTURE_PARAMETER: u32 = 1;
+const TOKEN_FIXTURE_STRING: u32 = 2;
+
+/// Token modifiers, as bits in the order of the legend.
+const MODIFIER_DEFINITION: u32 = 1 << 0;
+const MODIFIER_FIXTURE: u32 = 1 << 1;
+const MODIFIER_AUTOUSE: u32 = 1 << 2;
+const MODIFIER_THIRD_PARTY: u32 = 1 << 3;
+const MODIFIER_PLUGIN: u32 = 1 << 4;
+const MODIFIER_OVERRIDE: u32 = 1 << 5;
+
+/// Legend advertised in the server capabilities.
+pub(crate) fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: vec![
+            SemanticTokenType::FUNCTION,
+            SemanticTokenType::PARAMETER,
+            SemanticTokenType::STRING,
+        ],
+        token_modifiers: vec![
+            SemanticTokenModifier::DEFINITION,
+            SemanticTokenModifier::new("fixture"),
+            SemanticTokenModifier::new("autouse"),
+            SemanticTokenModifier::new("thirdParty"),
+            SemanticTokenModifier::new("plugin"),
+            SemanticTokenModifier::new("override"),
+        ],
+    }
+}
+
+/// A token before delta encoding (0-based LSP line).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct RawToken {
+    line: u32,
+    start: u32,
+    length: u32,
+    token_type: u32,
+    modifiers: u32,
+}
+
+impl Backend {
+    /// Handle `textDocument/semanticTokens/full`.
+    pub async fn handle_semantic_tokens_full(
+        &self,
+        params: SemanticTokensParams,
+    ) -> Result<Option<SemanticTokensResult>> {
+        let uri = params.text_document.uri;
+        info!("semantic_tokens_full request: uri={:?}", uri);
+
+        let Some(file_path) = self.uri_to_path(&uri) else {
+            return Ok(None);
+        };
+
+        let data = encode_tokens(self.collect_semantic_tokens(&file_path, None));
+        Ok(Some(SemanticTokensResult::Tokens(SemanticTokens {
+            result_id: None,
+            data,
+        })))
+    }
+
+    /// Handle `textDocument/semanticTokens/range`.
+    pub async fn handle_semantic_tokens_range(
+        &self,
+        params: SemanticTokensRangeParams,
+    ) -> Result<Option<SemanticTokensRangeResult>> {
+        let uri = params.text_document.uri;
+        info!(
+            "semantic_tokens_range request: uri={:?}, range={:?}",
+            uri, params.range
+        );
+
+        let Some(file_path) = self.uri_to_path(&uri) else {
+            return Ok(None);
+        };
+
+        let data = encode_tokens(self.collect_semantic_tokens(&file_path, Some(params.range)));
+        Ok(Some(SemanticTokensRangeResult::Tokens(SemanticTokens {
+            result_id: None,
+            data,
+        })))
+    }
+
+    /// Collect the fixture tokens of a file, sorted by position. With a range,
+    /// only tokens on the lines it covers are kept.
+    fn collect_semantic_tokens(&self, file_path: &Path, range: Option<Range>) -> Vec<RawToken> {
+        let mut tokens = Vec::new();
+
+        let definitions: Vec<FixtureDefinition> = self
+            .fixture_db
+            .file_definitions
+            .get(file_path)
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|name| self.fixture_db.definitions.get(name))
+                    .flat_map(|defs| {
+                        defs.iter()
+                            .filter(|def| def.file_path == file_path)
+                            .cloned()
+                            .collect::<Vec<_>>()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        for def in &definitions {
+            let overrides = self.fixture_db.find_overridden_definition(def).is_some();
+            let mut modifiers = MODIFIER_DEFINITION | fixture_modifiers(def);
+            if overrides {
+                modifiers |= MODIFIER_OVERRIDE;
+            }
+            tokens.push(RawToken {
+                line: Self::internal_line_to_lsp(def.line),
+                start: def.start_char as u32,
+                length: def.end_char.saturating_sub(def.start_char) as u32,
+                token_type: TOKEN_FIXTURE_DEFINITION,
+                modifiers,
+            });
+        }
+
+        if let Some(usages) = self.fixture_db.usages.get(file_path) {
+            for usage in usages.iter() {
+                let Some(resolved) = self.resolve_usage(file_path, usage, &definitions) else {
+                    // Unknown fixtures and parametrized arguments stay plain
+                    continue;
+                };
+                let mut modifiers = fixture_modifiers(&resolved);
+                if self
+                    .fixture_db
+                    .find_overridden_definition(&resolved)
+                    .is_some()
+                {
+                    modifiers |= MODIFIER_OVERRIDE;
+                }
+                tokens.push(RawToken {
+                    line: Self::internal_line_to_lsp(usage.line),
+                    start: usage.start_char as u32,
+                    length: usage.end_char.saturating_sub(usage.start_char) as u32,
+                    token_type: if usage.is_parameter {
+                        TOKEN_FIXTURE_PARAMETER
+                    } else {
+                        TOKEN_FIXTURE_STRING
+                    },
+                    modifiers,
+                });
+            }
+        }
+
+        if let Some(range) = range {
+            tokens.retain(|token| token.line >= range.start.line && token.line <= range.end.line);
+        }
+        tokens.retain(|token| token.length > 0);
+        tokens.sort();
+        tokens.dedup_by_key(|token| (token.line, token.start));
+        tokens
+    }
+
+    /// Resolve the definition a usage refers to. A fixture requesting its own
+    /// name gets the definition it overrides, as in go-to-definition.
+    fn resolve_usage(
+        &self,
+        file_path: &Path,
+        usage: &FixtureUsage,
+        definitions: &[FixtureDefinition],
+    ) -> Option<FixtureDefinition> {
+        let enclosing = definitions.iter().find(|def| {
+            def.name == usage.name && usage.line >= def.line && usage.line <= def.end_line
+        });
+        match enclosing {
+            Some(def) => {
+                self.fixture_db
+                    .find_closest_definition_excluding(file_path, &usage.name, Some(def))
+            }
+            None => {
+                self.fixture_db
+                    .find_closest_definition_at_line(file_path, &usage.name, usage.line)
+            }
+        }
+    }
+}
+
+/// Modifiers every token referring to `def` carries.
+fn fixture_modifiers(def: &FixtureDefinition) -> u32 {
+    let mut modifiers = MODIFIER_FIXTURE;
+    if def.autouse {
+        modifiers |= MODIFIER_AUTOUSE;
+    }
+    if def.is_third_party {
+        modifiers |= MODIFIER_THIRD_PARTY;
+    }
+    if def.is_plugin {
+        modifiers |= MODIFIER_PLUGIN;
+    }
+    modifiers
+}
+
+/// Delta-encode sorted tokens as the LSP wire format expects.
+fn encode_tokens(tokens: Vec<RawToken>) -> Vec<SemanticToken> {
+    let mut data = Vec::with_capacity(tokens.len());
+    let mut prev_line = 0;
+    let mut prev_start = 0;
+    for token in tokens {
+        let delta_line = token.line - prev_line;
+        let delta_start = if delta_line == 0 {
+            token.start - prev_start
+        } else {
+            token.start
+        };
+        data.push(SemanticToken {
+            delta_line,
+            delta_start,
+            length: token.length,
+            token_type: token.token_type,
+            token_modifiers_bitset: token.modifiers,
+        });
+        prev_line = token.line;
+        prev_start = token.start;
+    }
+    data
+}
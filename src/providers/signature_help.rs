@@ -0,0 +1,333 @@
+//! Signature help provider for pytest calls and fixture-consuming functions.
+//!
+//! Documents the arguments of `@pytest.fixture(...)`,
+//! `@pytest.mark.parametrize(...)`, `@pytest.mark.usefixtures(...)` and
+//! `pytest.param(...)`, and shows what each parameter of a test or fixture
+//! resolves to while its signature is typed.
+
+use super::Backend;
+use crate::fixtures::signature::{SignatureCallee, SignatureContext};
+use crate::fixtures::FixtureDefinition;
+use std::path::{Path, PathBuf};
+use tower_lsp_server::jsonrpc::Result;
+use tower_lsp_server::ls_types::*;
+use tracing::info;
+
+/// A documented parameter of a pytest API.
+struct ParamSpec {
+    name: &'static str,
+    label: &'static str,
+    doc: &'static str,
+}
+
+/// A documented pytest API.
+struct CallSpec {
+    callee: &'static str,
+    doc: &'static str,
+    params: &'static [ParamSpec],
+    /// Parameters from this index on are keyword-only (rendered after `*`).
+    keyword_only_from: Option<usize>,
+    /// Index of a `*args` parameter taking every positional argument.
+    variadic: Option<usize>,
+}
+
+const FIXTURE: CallSpec = CallSpec {
+    callee: "pytest.fixture",
+    doc: "Decorator to mark a fixture factory function.",
+    params: &[
+        ParamSpec {
+            name: "fixture_function",
+            label: "fixture_function=None",
+            doc: "The decorated function, when `@pytest.fixture` is used without arguments.",
+        },
+        ParamSpec {
+            name: "scope",
+            label: "scope=\"function\"",
+            doc: "How long the fixture value is shared: `\"function\"` (default), `\"class\"`, `\"module\"`, `\"package\"` or `\"session\"`. May also be a callable returning one of these.",
+        },
+        ParamSpec {
+            name: "params",
+            label: "params=None",
+            doc: "Values to parametrize the fixture with. Every test requesting it runs once per value, available as `request.param`.",
+        },
+        ParamSpec {
+            name: "autouse",
+            label: "autouse=False",
+            doc: "If `True`, the fixture is activated for every test that can see it, without being requested.",
+        },
+        ParamSpec {
+            name: "ids",
+            label: "ids=None",
+            doc: "Test ids for `params`: a list of strings, or a callable computing an id from each value.",
+        },
+        ParamSpec {
+            name: "name",
+            label: "name=None",
+            doc: "Name to request the fixture by, instead of the function name.",
+        },
+    ],
+    keyword_only_from: Some(1),
+    variadic: None,
+};
+
+const PARAMETRIZE: CallSpec = CallSpec {
+    callee: "pytest.mark.parametrize",
+    doc: "Run the test once per set of argument values.",
+    params: &[
+        ParamSpec {
+            name: "argnames",
+            label: "argnames",
+            doc: "Comma-separated string or list of argument names.",
+        },
+        ParamSpec {
+            name: "argvalues",
+            label: "argvalues",
+            doc: "List of values, or of tuples of values for several argnames. Wrap a set in `pytest.param(...)` to give it an id or marks.",
+        },
+        ParamSpec {
+            name: "indirect",
+            label: "indirect=False",
+            doc: "`True`, or a list of argnames, to pass the values to the fixture of the same name as `request.param` instead of directly to the test.",
+        },
+        ParamSpec {
+            name: "ids",
+            label: "ids=None",
+            doc: "Test ids for `argvalues`: a list of strings, or a callable computing an id from each value.",
+        },
+        ParamSpec {
+            name: "scope",
+            label: "scope=None",
+            doc: "Scope of the parameters, used to group tests. Defaults to the narrowest scope of the indirect fixtures.",
+        },
+    ],
+    keyword_only_from: None,
+    variadic: None,
+};
+
+const USEFIXTURES: CallSpec = CallSpec {
+    callee: "pytest.mark.usefixtures",
+    doc: "Set up fixtures for each test without requesting them as arguments.",
+    params: &[ParamSpec {
+        name: "names",
+        label: "*names",
+        doc: "Names of the fixtures to use.",
+    }],
+    keyword_only_from: None,
+    variadic: Some(0),
+};
+
+const PARAM: CallSpec = CallSpec {
+    callee: "pytest.param",
+    doc: "A parameter set for `@pytest.mark.parametrize` or fixture `params`.",
+    params: &[
+        ParamSpec {
+            name: "values",
+            label: "*values",
+            doc: "The values of the parameter set, one per argname.",
+        },
+        ParamSpec {
+            name: "id",
+            label: "id=None",
+            doc: "Test id of this parameter set.",
+        },
+        ParamSpec {
+            name: "marks",
+            label: "marks=()",
+            doc: "Marks to apply to this parameter set, e.g. `pytest.mark.xfail`.",
+        },
+    ],
+    keyword_only_from: Some(1),
+    variadic: Some(0),
+};
+
+impl Backend {
+    /// Handle `textDocument/signatureHelp`.
+    pub async fn handle_signature_help(
+        &self,
+        params: SignatureHelpParams,
+    ) -> Result<Option<SignatureHelp>> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+
+        info!(
+            "signature_help request: uri={:?}, line={}, char={}",
+            uri, position.line, position.character
+        );
+
+        let Some(file_path) = self.uri_to_path(&uri) else {
+            return Ok(None);
+        };
+        let Some(context) =
+            self.fixture_db
+                .get_signature_context(&file_path, position.line, position.character)
+        else {
+            return Ok(None);
+        };
+
+        let spec = match &context.callee {
+            SignatureCallee::Fixture => &FIXTURE,
+            SignatureCallee::Parametrize => &PARAMETRIZE,
+            SignatureCallee::Usefixtures => &USEFIXTURES,
+            SignatureCallee::Param => &PARAM,
+            SignatureCallee::Function { .. } => {
+                let workspace_root = self.workspace_root_for(&file_path).await;
+                return Ok(self.function_signature_help(
+                    &file_path,
+                    &context,
+                    workspace_root.as_ref(),
+                ));
+            }
+        };
+
+        Ok(Some(call_signature_help(spec, &context)))
+    }
+
+    /// Signature help for the parameter list of a test or fixture: each
+    /// parameter shows the fixture it resolves to.
+    fn function_signature_help(
+        &self,
+        file_path: &Path,
+        context: &SignatureContext,
+        workspace_root: Option<&PathBuf>,
+    ) -> Option<SignatureHelp> {
+        let SignatureCallee::Function {
+            name,
+            line,
+            is_fixture,
+            params,
+        } = &context.callee
+        else {
+            return None;
+        };
+        let (line, is_fixture) = (*line, *is_fixture);
+
+        let mut label = format!("{}(", name);
+        let mut parameters = Vec::new();
+        for (i, param) in params.iter().enumerate() {
+            if i > 0 {
+                label.push_str(", ");
+            }
+            let resolved = if param == "self" || param == "cls" {
+                None
+            } else {
+                self.resolve_signature_parameter(file_path, name, line, is_fixture, param)
+            };
+
+            let param_label = match resolved.as_ref().and_then(|def| def.return_type.as_ref()) {
+                Some(return_type) => format!("{}: {}", param, return_type),
+                None => param.clone(),
+            };
+            let start = label.encode_utf16().count() as u32;
+            label.push_str(&param_label);
+            let end = label.encode_utf16().count() as u32;
+
+            let documentation = match &resolved {
+                Some(def) => Some(Self::format_fixture_documentation(def, workspace_root)),
+                None if param == "self" || param == "cls" => None,
+                None => Some(format!(
+                    "`{}` does not resolve to a fixture definition (parametrized argument or pytest built-in?)",
+                    param
+                )),
+            };
+            parameters.push(ParameterInformation {
+                label: ParameterLabel::LabelOffsets([start, end]),
+                documentation: documentation.map(markdown),
+            });
+        }
+        label.push(')');
+
+        let active_parameter =
+            (!params.is_empty()).then(|| context.argument_index.min(params.len() - 1) as u32);
+        let kind = if is_fixture { "Fixture" } else { "Test" };
+        Some(SignatureHelp {
+            signatures: vec![SignatureInformation {
+                label,
+                documentation: Some(markdown(format!(
+                    "{} `{}`: parameters are resolved to fixtures",
+                    kind, name
+                ))),
+                parameters: Some(parameters),
+                active_parameter,
+            }],
+            active_signature: Some(0),
+            active_parameter,
+        })
+    }
+
+    /// Resolve a parameter of a test or fixture signature. A fixture
+    /// requesting its own name gets the definition it overrides.
+    fn resolve_signature_parameter(
+        &self,
+        file_path: &Path,
+        function_name: &str,
+        line: usize,
+        is_fixture: bool,
+        param: &str,
+    ) -> Option<FixtureDefinition> {
+        if is_fixture && param == function_name {
+            let current = self.fixture_db.definitions.get(param).and_then(|defs| {
+                defs.iter()
+                    .find(|def| def.file_path == file_path && def.line == line)
+                    .cloned()
+            });
+            if let Some(current) = current {
+                return self.fixture_db.find_closest_definition_excluding(
+                    file_path,
+                    param,
+                    Some(&current),
+                );
+            }
+        }
+        self.fixture_db
+            .find_closest_definition_at_line(file_path, param, line)
+    }
+}
+
+/// Signature help for a documented pytest API.
+fn call_signature_help(spec: &CallSpec, context: &SignatureContext) -> SignatureHelp {
+    let mut label = format!("{}(", spec.callee);
+    let mut parameters = Vec::new();
+    for (i, param) in spec.params.iter().enumerate() {
+        if i > 0 {
+            label.push_str(", ");
+        }
+        if spec.keyword_only_from == Some(i) && spec.variadic.is_none() {
+            label.push_str("*, ");
+        }
+        let start = label.len() as u32;
+        label.push_str(param.label);
+        parameters.push(ParameterInformation {
+            label: ParameterLabel::LabelOffsets([start, label.len() as u32]),
+            documentation: Some(markdown(param.doc.to_string())),
+        });
+    }
+    label.push(')');
+
+    let positional = spec.keyword_only_from.unwrap_or(spec.params.len());
+    let active_parameter = match &context.keyword {
+        Some(keyword) => spec.params.iter().position(|param| param.name == keyword),
+        None => match spec.variadic {
+            Some(index) => Some(index),
+            None => (context.argument_index < positional).then_some(context.argument_index),
+        },
+    }
+    .map(|index| index as u32);
+
+    SignatureHelp {
+        signatures: vec![SignatureInformation {
+            label,
+            documentation: Some(markdown(spec.doc.to_string())),
+            parameters: Some(parameters),
+            active_parameter,
+        }],
+        active_signature: Some(0),
+        active_parameter,
+    }
+}
+
+fn markdown(value: String) -> Documentation {
+    Documentation::MarkupContent(MarkupContent {
+        kind: MarkupKind::Markdown,
+        value,
+    })
+}
@@ -0,0 +1,177 @@
+//! Handling of `workspace/didChangeWatchedFiles`.
+//!
+//! Keeps the fixture database in sync with changes made outside the editor
+//! (e.g. `git checkout`): Python files are re-analyzed or purged, and
+//! configuration files reload the settings of their workspace folder.
+
+use super::configuration::is_config_file;
+use super::{innermost_root, Backend};
+use std::path::PathBuf;
+use tower_lsp_server::ls_types::*;
+use tracing::{debug, info};
+
+impl Backend {
+    /// Handle `workspace/didChangeWatchedFiles`.
+    pub async fn handle_did_change_watched_files(&self, params: DidChangeWatchedFilesParams) {
+        // Configuration is only read from a workspace folder's root, so a
+        // changed config file reloads the folder it sits in (if any).
+        let mut config_roots: Vec<PathBuf> = Vec::new();
+        for event in &params.changes {
+            let Some(path) = event.uri.to_file_path() else {
+                continue;
+            };
+            if !is_config_file(&path) {
+                continue;
+            }
+            if let Some(dir) = path.parent() {
+                let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+                info!("Configuration file changed: {:?}", path);
+                if !config_roots.contains(&dir) {
+                    config_roots.push(dir);
+                }
+            }
+        }
+        if !config_roots.is_empty() {
+            self.reload_configuration(Some(&config_roots)).await;
+        }
+
+        let mut python_files_changed = false;
+        for event in &params.changes {
+            let Some(path) = event.uri.to_file_path() else {
+                continue;
+            };
+            if path.extension().is_none_or(|ext| ext != "py") {
+                continue;
+            }
+            python_files_changed |= self.sync_python_file(event).await;
+        }
+
+        // Re-analyze fixture files whose `return_type_imports` may have become
+        // stale because an `__init__.py` was created or deleted, changing the
+        // result of `file_path_to_module_path()`.
+        for event in &params.changes {
+            if event.typ != FileChangeType::CREATED && event.typ != FileChangeType::DELETED {
+                continue;
+            }
+
+            let Some(init_path) = self.uri_to_path(&event.uri) else {
+                continue;
+            };
+            if init_path
+                .file_name()
+                .is_none_or(|name| name != "__init__.py")
+            {
+                continue;
+            }
+
+            // The __init__.py change affects the directory it lives in and
+            // every directory below it.  Any fixture file at or under that
+            // directory may produce a different module path now.
+            let affected_dir = match init_path.parent() {
+                Some(dir) => dir.to_path_buf(),
+                None => continue,
+            };
+
+            let kind = if event.typ == FileChangeType::CREATED {
+                "created"
+            } else {
+                "deleted"
+            };
+            info!(
+                "__init__.py {} in {:?} — re-analyzing affected fixture files",
+                kind, affected_dir
+            );
+
+            // Collect fixture files that live at or below the affected directory.
+            let files_to_reanalyze: Vec<PathBuf> = self
+                .fixture_db
+                .file_definitions
+                .iter()
+                .filter(|entry| entry.key().starts_with(&affected_dir))
+                .map(|entry| entry.key().clone())
+                .collect();
+
+            for file_path in files_to_reanalyze {
+                if let Some(content) = self.fixture_db.get_file_content(&file_path) {
+                    info!("Re-analyzing {:?} after __init__.py change", file_path);
+                    self.fixture_db.analyze_file(file_path.clone(), &content);
+
+                    // Re-publish diagnostics for the file if we have a cached URI.
+                    if let Some(uri) = self.uri_cache.get(&file_path) {
+                        self.publish_diagnostics_for_file(&uri, &file_path).await;
+                    }
+                }
+            }
+        }
+
+        // Fixtures changed on disk can affect diagnostics in any open document
+        if python_files_changed {
+            self.publish_diagnostics_for_open_documents().await;
+            self.refresh_pull_diagnostics().await;
+        }
+
+        // Refresh inlay hints in case return types changed.
+        let has_module_changes = params.changes.iter().any(|event| {
+            event
+                .uri
+                .to_file_path()
+                .is_some_and(|path| !is_config_file(&path))
+        });
+        if has_module_changes {
+            if let Err(e) = self.client.inlay_hint_refresh().await {
+                info!(
+                    "Inlay hint refresh after file change failed (client may not support it): {}",
+                    e
+                );
+            }
+        }
+    }
+
+    /// Apply a Python file event to the fixture database: re-analyze created
+    /// or changed files, purge deleted ones. Open documents are left alone
+    /// since the editor's content wins over the disk's.
+    ///
+    /// Returns `true` if the database changed.
+    async fn sync_python_file(&self, event: &FileEvent) -> bool {
+        let Some(file_path) = self.uri_to_path(&event.uri) else {
+            return false;
+        };
+        if self.fixture_db.document_versions.contains_key(&file_path) {
+            debug!("Ignoring disk change to open document: {:?}", file_path);
+            return false;
+        }
+
+        if event.typ == FileChangeType::DELETED {
+            return self.fixture_db.remove_file(&file_path);
+        }
+
+        if !self.is_tracked_file(&file_path).await {
+            return false;
+        }
+        match std::fs::read_to_string(&file_path) {
+            Ok(content) => {
+                info!("Re-analyzing {:?} after change on disk", file_path);
+                self.fixture_db.analyze_file(file_path, &content);
+                true
+            }
+            Err(e) => {
+                debug!("Failed to read changed file {:?}: {}", file_path, e);
+                false
+            }
+        }
+    }
+
+    /// Whether a file belongs in the fixture database: it was analyzed before
+    /// (e.g. a module imported by a conftest.py), or a workspace scan would
+    /// pick it up.
+    async fn is_tracked_file(&self, file_path: &std::path::Path) -> bool {
+        if self.fixture_db.has_file(file_path) {
+            return true;
+        }
+        let roots = self.workspace_roots.read().await;
+        innermost_root(&roots, file_path).is_some_and(|root| {
+            self.fixture_db
+                .is_workspace_scan_target(&root.path, file_path, &root.config.exclude)
+        })
+    }
+}
@@ -0,0 +1,216 @@
+//! Workspace folder management for multi-root workspaces.
+//!
+//! Every folder is a separate root with its own configuration, virtual
+//! environment and exclude patterns. Folders are scanned in the background,
+//! one at a time, and can be added or removed at runtime via
+//! `workspace/didChangeWorkspaceFolders`.
+
+use super::{Backend, WorkspaceRoot};
+use crate::config::Config;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tower_lsp_server::ls_types::*;
+use tracing::{error, info};
+
+impl Backend {
+    /// Start tracking workspace folders: load each folder's configuration and
+    /// scan it in the background. Folders that are already tracked are ignored.
+    ///
+    /// The first folder becomes the primary root if there is none yet.
+    pub async fn add_workspace_roots(&self, root_paths: Vec<PathBuf>) {
+        let settings = self.client_settings.read().await.clone();
+        let mut new_roots: Vec<WorkspaceRoot> = Vec::new();
+        {
+            let tracked = self.workspace_roots.read().await;
+            for root_path in root_paths {
+                // Store the canonical path (with symlinks resolved) next to the one the client sent
+                let canonical_root = root_path
+                    .canonicalize()
+                    .unwrap_or_else(|_| root_path.clone());
+                if tracked.iter().any(|root| root.path == canonical_root)
+                    || new_roots.iter().any(|root| root.path == canonical_root)
+                {
+                    continue;
+                }
+
+                // Load configuration from this folder's pyproject.toml
+                let config = Config::load_with_settings(&root_path, &settings);
+                info!("Loaded config for {:?}: {:?}", root_path, config);
+                self.fixture_db
+                    .set_root_pytest_options(&canonical_root, config.pytest.clone());
+                self.fixture_db
+                    .set_root_venv_settings(&canonical_root, config.venv_settings(&root_path));
+                self.fixture_db
+                    .set_root_fixture_paths(&canonical_root, config.fixture_path_dirs(&root_path));
+
+                new_roots.push(WorkspaceRoot {
+                    path: canonical_root,
+                    original_path: root_path,
+                    config,
+                });
+            }
+        }
+
+        let Some(first) = new_roots.first() else {
+            return;
+        };
+
+        if self.workspace_root.read().await.is_none() {
+            self.set_primary_root(Some(first)).await;
+        }
+        self.workspace_roots
+            .write()
+            .await
+            .extend(new_roots.iter().cloned());
+
+        self.spawn_workspace_scan(new_roots).await;
+    }
+
+    /// Stop tracking a workspace folder and forget everything found in it.
+    pub async fn remove_workspace_root(&self, root_path: PathBuf) {
+        let canonical_root = root_path.canonicalize().unwrap_or(root_path);
+
+        let removed = {
+            let mut roots = self.workspace_roots.write().await;
+            let count = roots.len();
+            roots.retain(|root| root.path != canonical_root);
+            roots.len() != count
+        };
+        if !removed {
+            return;
+        }
+        info!("Removing workspace folder: {:?}", canonical_root);
+
+        let files_before = self.fixture_db.workspace_files();
+        self.fixture_db.remove_workspace_root(&canonical_root);
+
+        // Promote the next folder if the primary one went away
+        if self.workspace_root.read().await.as_ref() == Some(&canonical_root) {
+            let next = self.workspace_roots.read().await.first().cloned();
+            self.set_primary_root(next.as_ref()).await;
+        }
+
+        // Clear pushed diagnostics of files that are no longer analyzed
+        if !self
+            .pull_diagnostics
+            .load(std::sync::atomic::Ordering::Relaxed)
+        {
+            let files_after: HashSet<PathBuf> =
+                self.fixture_db.workspace_files().into_iter().collect();
+            for file_path in files_before {
+                if files_after.contains(&file_path) {
+                    continue;
+                }
+                if let Some(uri) = self.path_to_uri(&file_path) {
+                    self.client.publish_diagnostics(uri, Vec::new(), None).await;
+                }
+            }
+        }
+        self.refresh_pull_diagnostics().await;
+    }
+
+    /// Handle `workspace/didChangeWorkspaceFolders`.
+    pub async fn handle_did_change_workspace_folders(
+        &self,
+        params: DidChangeWorkspaceFoldersParams,
+    ) {
+        info!(
+            "did_change_workspace_folders: {} added, {} removed",
+            params.event.added.len(),
+            params.event.removed.len()
+        );
+
+        for folder in params.event.removed {
+            if let Some(path) = folder.uri.to_file_path() {
+                self.remove_workspace_root(path.to_path_buf()).await;
+            }
+        }
+
+        let added: Vec<PathBuf> = params
+            .event
+            .added
+            .iter()
+            .filter_map(|folder| folder.uri.to_file_path())
+            .map(|path| path.to_path_buf())
+            .collect();
+        self.add_workspace_roots(added).await;
+    }
+
+    /// Mirror a folder (or none) into the single-root fields.
+    pub(super) async fn set_primary_root(&self, root: Option<&WorkspaceRoot>) {
+        *self.workspace_root.write().await = root.map(|root| root.path.clone());
+        *self.original_workspace_root.write().await = root.map(|root| root.original_path.clone());
+        let config = root.map(|root| root.config.clone()).unwrap_or_default();
+        self.fixture_db.set_pytest_options(config.pytest.clone());
+        *self.config.write().await = config;
+    }
+
+    /// Scan folders in a background task so the server stays responsive.
+    ///
+    /// Scans share the database's index cache, so a new scan waits for the
+    /// previous one to finish.
+    pub(super) async fn spawn_workspace_scan(&self, roots: Vec<WorkspaceRoot>) {
+        let backend = self.clone();
+        let previous_scan = self.scan_task.lock().await.take();
+
+        let scan_handle = tokio::spawn(async move {
+            if let Some(previous_scan) = previous_scan {
+                let _ = previous_scan.await;
+            }
+
+            for root in roots {
+                info!("Starting workspace scan: {:?}", root.original_path);
+                backend
+                    .client
+                    .log_message(
+                        MessageType::INFO,
+                        format!("Scanning workspace: {:?}", root.original_path),
+                    )
+                    .await;
+
+                let fixture_db = Arc::clone(&backend.fixture_db);
+                let exclude_patterns = root.config.exclude.clone();
+                let cache_dir = root.config.index_cache_dir(&root.path);
+                let root_path = root.original_path.clone();
+
+                // Run the synchronous scan in a blocking task to avoid blocking the async runtime
+                let scan_result = tokio::task::spawn_blocking(move || {
+                    match &cache_dir {
+                        Some(cache_dir) => fixture_db.enable_index_cache(cache_dir),
+                        None => fixture_db.disable_index_cache(),
+                    }
+                    fixture_db.scan_workspace_with_excludes(&root_path, &exclude_patterns);
+                    fixture_db.save_index_cache();
+                })
+                .await;
+
+                match scan_result {
+                    Ok(()) => {
+                        info!("Workspace scan complete: {:?}", root.original_path);
+                        backend
+                            .client
+                            .log_message(MessageType::INFO, "Workspace scan complete")
+                            .await;
+                    }
+                    Err(e) => {
+                        error!("Workspace scan failed: {:?}", e);
+                        backend
+                            .client
+                            .log_message(
+                                MessageType::ERROR,
+                                format!("Workspace scan failed: {:?}", e),
+                            )
+                            .await;
+                    }
+                }
+            }
+
+            backend.publish_diagnostics_for_open_documents().await;
+            backend.refresh_pull_diagnostics().await;
+        });
+
+        // Store the handle so we can cancel it on shutdown
+        *self.scan_task.lock().await = Some(scan_handle);
+    }
+}
@@ -0,0 +1,269 @@
+//! Machine-readable output formats for CLI findings.
+//!
+//! Supports SARIF 2.1.0 (for GitHub code scanning and other SARIF viewers) and
+//! GitHub Actions workflow commands (inline pull request annotations), plus the
+//! path helpers shared by all CLI output.
+
+use std::path::{Path, PathBuf};
+
+/// Rule IDs (matching the diagnostic codes) and their short descriptions.
+const RULES: &[(&str, &str)] = &[
+    (
+        "undeclared-fixture",
+        "Fixture used in a function body but not declared as a parameter",
+    ),
+    (
+        "unknown-fixture",
+        "Parameter does not resolve to any available fixture",
+    ),
+    (
+        "scope-mismatch",
+        "Broader-scoped fixture depends on a narrower-scoped fixture",
+    ),
+    (
+        "circular-dependency",
+        "Fixtures depend on each other in a cycle",
+    ),
+    ("unused-fixture", "Fixture is never used by any test"),
+];
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Severity of a finding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FindingLevel {
+    Error,
+    Warning,
+    Note,
+}
+
+impl FindingLevel {
+    fn sarif_level(self) -> &'static str {
+        match self {
+            FindingLevel::Error => "error",
+            FindingLevel::Warning => "warning",
+            FindingLevel::Note => "note",
+        }
+    }
+
+    fn github_command(self) -> &'static str {
+        match self {
+            FindingLevel::Error => "error",
+            FindingLevel::Warning => "warning",
+            FindingLevel::Note => "notice",
+        }
+    }
+}
+
+/// A single problem reported by a CLI command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// Rule ID (the diagnostic code, e.g. `scope-mismatch`).
+    pub rule_id: String,
+    pub message: String,
+    pub level: FindingLevel,
+    /// Path relative to the repository root (see `report_root`), using `/` separators.
+    pub file: String,
+    pub line: usize,       // 1-based
+    pub column: usize,     // 1-based
+    pub end_line: usize,   // 1-based
+    pub end_column: usize, // 1-based, exclusive
+}
+
+/// Render findings as a SARIF 2.1.0 log.
+pub fn to_sarif(findings: &[Finding]) -> serde_json::Value {
+    // Known rules first (in a stable order), then any codes we have no description for
+    let mut rule_ids: Vec<&str> = RULES.iter().map(|(id, _)| *id).collect();
+    for finding in findings {
+        if !rule_ids.contains(&finding.rule_id.as_str()) {
+            rule_ids.push(&finding.rule_id);
+        }
+    }
+
+    let rules: Vec<serde_json::Value> = rule_ids
+        .iter()
+        .map(|id| {
+            let description = RULES
+                .iter()
+                .find(|(rule_id, _)| rule_id == id)
+                .map_or(*id, |(_, description)| *description);
+            serde_json::json!({
+                "id": id,
+                "shortDescription": { "text": description },
+            })
+        })
+        .collect();
+
+    let results: Vec<serde_json::Value> = findings
+        .iter()
+        .map(|finding| {
+            let rule_index = rule_ids
+                .iter()
+                .position(|id| *id == finding.rule_id)
+                .unwrap_or_default();
+            serde_json::json!({
+                "ruleId": finding.rule_id,
+                "ruleIndex": rule_index,
+                "level": finding.level.sarif_level(),
+                "message": { "text": finding.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": finding.file,
+                            "uriBaseId": "%SRCROOT%",
+                        },
+                        "region": {
+                            "startLine": finding.line,
+                            "startColumn": finding.column,
+                            "endLine": finding.end_line,
+                            "endColumn": finding.end_column,
+                        },
+                    },
+                }],
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "pytest-language-server",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+/// Render findings as GitHub Actions workflow commands, one per line.
+pub fn to_github_annotations(findings: &[Finding]) -> String {
+    findings
+        .iter()
+        .map(|finding| {
+            format!(
+                "::{} file={},line={},col={},endLine={},endColumn={},title={}::{}",
+                finding.level.github_command(),
+                escape_github_property(&finding.file),
+                finding.line,
+                finding.column,
+                finding.end_line,
+                finding.end_column,
+                escape_github_property(&finding.rule_id),
+                escape_github_data(&finding.message)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Escape the message part of a workflow command.
+fn escape_github_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a `key=value` property of a workflow command.
+fn escape_github_property(value: &str) -> String {
+    escape_github_data(value)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+/// Directory that SARIF and GitHub annotation paths are relative to: the git
+/// repository containing `path` (what `%SRCROOT%` and `file=` refer to), or
+/// the current directory outside a repository.
+pub fn report_root(path: &Path) -> PathBuf {
+    path.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+        .or_else(|| {
+            std::env::current_dir()
+                .ok()
+                .map(|cwd| cwd.canonicalize().unwrap_or(cwd))
+        })
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Path of `file_path` relative to `root`, with `/` separators.
+/// Files outside `root` (e.g. site-packages) keep their full path.
+pub fn relative_path(file_path: &Path, root: &Path) -> String {
+    match file_path.strip_prefix(root) {
+        Ok(relative) => relative
+            .iter()
+            .map(|component| component.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+        Err(_) => file_path.to_string_lossy().into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding() -> Finding {
+        Finding {
+            rule_id: "scope-mismatch".to_string(),
+            message: "session-scoped fixture 'db' depends on function-scoped fixture 'tmp'"
+                .to_string(),
+            level: FindingLevel::Warning,
+            file: "tests/conftest.py".to_string(),
+            line: 8,
+            column: 5,
+            end_line: 8,
+            end_column: 7,
+        }
+    }
+
+    #[test]
+    fn test_relative_path() {
+        let root = Path::new("/repo");
+        assert_eq!(
+            relative_path(Path::new("/repo/tests/conftest.py"), root),
+            "tests/conftest.py"
+        );
+        assert_eq!(
+            relative_path(Path::new("/venv/lib/plugin.py"), root),
+            "/venv/lib/plugin.py"
+        );
+    }
+
+    #[test]
+    fn test_github_annotation() {
+        assert_eq!(
+            to_github_annotations(&[finding()]),
+            "::warning file=tests/conftest.py,line=8,col=5,endLine=8,endColumn=7,title=scope-mismatch::session-scoped fixture 'db' depends on function-scoped fixture 'tmp'"
+        );
+    }
+
+    #[test]
+    fn test_github_escaping() {
+        assert_eq!(escape_github_data("50%\nnext"), "50%25%0Anext");
+        assert_eq!(escape_github_property("a:b,c"), "a%3Ab%2Cc");
+    }
+
+    #[test]
+    fn test_sarif_result_references_rule() {
+        let sarif = to_sarif(&[finding()]);
+        let run = &sarif["runs"][0];
+        let result = &run["results"][0];
+        assert_eq!(result["ruleId"], "scope-mismatch");
+        let index = result["ruleIndex"].as_u64().unwrap() as usize;
+        assert_eq!(
+            run["tool"]["driver"]["rules"][index]["id"],
+            "scope-mismatch"
+        );
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["region"]["startColumn"],
+            5
+        );
+    }
+}
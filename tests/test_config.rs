@@ -1,6 +1,6 @@
 //! Integration tests for configuration file support.
 
-use pytest_language_server::{Config, FixtureDatabase};
+use pytest_language_server::{Config, FixtureDatabase, PytestIniOptions};
 use std::fs;
 use tempfile::TempDir;
 
@@ -245,15 +245,17 @@ fn test_all_diagnostics_disabled() {
     let temp_dir = create_temp_project(
         r#"
 [tool.pytest-language-server]
-disabled_diagnostics = ["undeclared-fixture", "scope-mismatch", "circular-dependency"]
+disabled_diagnostics = ["undeclared-fixture", "unknown-fixture", "scope-mismatch", "circular-dependency", "fixture-override"]
 "#,
     );
 
     let config = Config::load(temp_dir.path());
 
     assert!(config.is_diagnostic_disabled("undeclared-fixture"));
+    assert!(config.is_diagnostic_disabled("unknown-fixture"));
     assert!(config.is_diagnostic_disabled("scope-mismatch"));
     assert!(config.is_diagnostic_disabled("circular-dependency"));
+    assert!(config.is_diagnostic_disabled("fixture-override"));
 }
 
 // ============ Skip Plugins Tests ============
@@ -267,11 +269,11 @@ skip_plugins = ["pytest-xdist", "pytest-cov"]
 "#,
     );
 
-    let config = Config::load(temp_dir.path());
+    let settings = Config::load(temp_dir.path()).venv_settings(temp_dir.path());
 
-    assert!(config.should_skip_plugin("pytest-xdist"));
-    assert!(config.should_skip_plugin("pytest-cov"));
-    assert!(!config.should_skip_plugin("pytest-mock"));
+    assert!(settings.skips_plugin("xdist", Some("pytest-xdist")));
+    assert!(settings.skips_plugin("pytest_cov", None));
+    assert!(!settings.skips_plugin("pytest_mock", Some("pytest-mock")));
 }
 
 // ============ Edge Cases ============
@@ -333,3 +335,260 @@ fixture_paths = ["fixtures/", "shared/fixtures/"]
     let config = Config::load(temp_dir.path());
     assert_eq!(config.fixture_paths, vec!["fixtures/", "shared/fixtures/"]);
 }
+
+// ============ Index Cache Tests ============
+
+#[test]
+fn test_index_cache_is_disabled_by_default() {
+    let temp_dir = create_temp_project("[project]\nname = \"myproject\"\n");
+
+    let config = Config::load(temp_dir.path());
+    assert_eq!(config.index_cache_dir(temp_dir.path()), None);
+}
+
+#[test]
+fn test_index_cache_dir_defaults_to_pytest_cache() {
+    let temp_dir = create_temp_project(
+        r#"
+[tool.pytest-language-server]
+index_cache = true
+"#,
+    );
+
+    let config = Config::load(temp_dir.path());
+    assert_eq!(
+        config.index_cache_dir(temp_dir.path()),
+        Some(
+            temp_dir
+                .path()
+                .join(".pytest_cache")
+                .join("pytest-language-server")
+        )
+    );
+}
+
+#[test]
+fn test_index_cache_options() {
+    let temp_dir = create_temp_project(
+        r#"
+[tool.pytest-language-server]
+index_cache = true
+cache_dir = ".cache/pls"
+"#,
+    );
+    let config = Config::load(temp_dir.path());
+    assert_eq!(
+        config.index_cache_dir(temp_dir.path()),
+        Some(temp_dir.path().join(".cache/pls"))
+    );
+
+    let temp_dir = create_temp_project(
+        r#"
+[tool.pytest-language-server]
+index_cache = false
+"#,
+    );
+    let config = Config::load(temp_dir.path());
+    assert_eq!(config.index_cache_dir(temp_dir.path()), None);
+}
+
+// ============ Client Settings Tests ============
+
+#[test]
+fn test_client_settings_override_pyproject_keys() {
+    let temp_dir = create_temp_project(
+        r#"
+[tool.pytest-language-server]
+exclude = ["build"]
+disabled_diagnostics = ["undeclared-fixture"]
+"#,
+    );
+
+    // Only keys present in the settings override pyproject.toml
+    let settings = serde_json::json!({
+        "pytest-language-server": { "disabled_diagnostics": ["scope-mismatch"] }
+    });
+    let config = Config::load_with_settings(temp_dir.path(), &settings);
+    assert_eq!(config.disabled_diagnostics, vec!["scope-mismatch"]);
+    assert_eq!(config.exclude.len(), 1);
+
+    // Settings may also be sent without the wrapping key
+    let settings = serde_json::json!({ "exclude": [] });
+    let config = Config::load_with_settings(temp_dir.path(), &settings);
+    assert!(config.exclude.is_empty());
+    assert_eq!(config.disabled_diagnostics, vec!["undeclared-fixture"]);
+}
+
+#[test]
+fn test_invalid_client_settings_are_ignored() {
+    let temp_dir = create_temp_project(
+        r#"
+[tool.pytest-language-server]
+exclude = ["build"]
+"#,
+    );
+
+    let settings = serde_json::json!({ "exclude": "build" });
+    let config = Config::load_with_settings(temp_dir.path(), &settings);
+    assert_eq!(config.exclude.len(), 1);
+}
+
+#[test]
+fn test_scan_settings_differ() {
+    let base = Config::default();
+    let diagnostics_only = Config {
+        disabled_diagnostics: vec!["unknown-fixture".to_string()],
+        ..Default::default()
+    };
+    assert!(!diagnostics_only.scan_settings_differ(&base));
+
+    let excluded = Config {
+        exclude: vec![glob::Pattern::new("build").unwrap()],
+        ..Default::default()
+    };
+    assert!(excluded.scan_settings_differ(&base));
+
+    let cached = Config {
+        index_cache: true,
+        ..Default::default()
+    };
+    assert!(cached.scan_settings_differ(&base));
+    let relocated = Config {
+        cache_dir: Some(std::path::PathBuf::from(".cache/pls")),
+        ..Default::default()
+    };
+    assert!(relocated.scan_settings_differ(&base));
+}
+
+// ============ Pytest Ini Options Tests ============
+
+#[test]
+fn test_pytest_options_default_without_config() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let config = Config::load(temp_dir.path());
+    assert_eq!(config.pytest, PytestIniOptions::default());
+    assert!(config.pytest.source.is_none());
+}
+
+#[test]
+fn test_pytest_options_from_pyproject_ini_options() {
+    let temp_dir = create_temp_project(
+        r#"
+[tool.pytest.ini_options]
+python_files = ["check_*.py"]
+python_functions = "check_*"
+python_classes = ["Check", "*Suite"]
+testpaths = ["checks"]
+"#,
+    );
+
+    let config = Config::load(temp_dir.path());
+    assert_eq!(config.pytest.python_files, vec!["check_*.py"]);
+    assert_eq!(config.pytest.python_functions, vec!["check_*"]);
+    assert_eq!(config.pytest.python_classes, vec!["Check", "*Suite"]);
+    assert_eq!(config.pytest.testpaths, vec!["checks"]);
+    assert_eq!(
+        config.pytest.source,
+        Some(temp_dir.path().join("pyproject.toml"))
+    );
+}
+
+#[test]
+fn test_pytest_options_from_setup_cfg_and_tox_ini() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("setup.cfg"),
+        "[tool:pytest]\npython_files = check_*.py\n",
+    )
+    .unwrap();
+
+    let config = Config::load(temp_dir.path());
+    assert_eq!(config.pytest.python_files, vec!["check_*.py"]);
+
+    // tox.ini takes precedence over setup.cfg
+    fs::write(
+        temp_dir.path().join("tox.ini"),
+        "[tox]\nenvlist = py312\n\n[pytest]\ntestpaths =\n    tests\n    integration\n",
+    )
+    .unwrap();
+
+    let config = Config::load(temp_dir.path());
+    assert_eq!(config.pytest.testpaths, vec!["tests", "integration"]);
+    assert_eq!(config.pytest.python_files, vec!["test_*.py", "*_test.py"]);
+}
+
+#[test]
+fn test_pytest_ini_takes_precedence_over_pyproject() {
+    let temp_dir = create_temp_project(
+        r#"
+[tool.pytest.ini_options]
+python_functions = ["verify"]
+"#,
+    );
+    fs::write(
+        temp_dir.path().join("pytest.ini"),
+        "[pytest]\npython_functions = check_*\n",
+    )
+    .unwrap();
+
+    let config = Config::load(temp_dir.path());
+    assert_eq!(config.pytest.python_functions, vec!["check_*"]);
+}
+
+#[test]
+fn test_pytest_options_drive_workspace_scan() {
+    let temp_dir = create_temp_project(
+        r#"
+[tool.pytest.ini_options]
+python_files = "check_*.py"
+python_functions = "check_*"
+testpaths = ["checks"]
+"#,
+    );
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("checks")).unwrap();
+    fs::create_dir_all(root.join("scripts")).unwrap();
+    create_test_file(
+        root,
+        "conftest.py",
+        "import pytest\n\n@pytest.fixture\ndef client():\n    return 1\n",
+    );
+    create_test_file(
+        &root.join("checks"),
+        "check_login.py",
+        "def check_login(client):\n    pass\n\ndef helper(client):\n    pass\n",
+    );
+    create_test_file(
+        &root.join("checks"),
+        "test_ignored.py",
+        "def test_ignored(client):\n    pass\n",
+    );
+    create_test_file(
+        &root.join("scripts"),
+        "check_outside.py",
+        "def check_outside(client):\n    pass\n",
+    );
+
+    let config = Config::load(root);
+    let db = FixtureDatabase::new();
+    db.set_pytest_options(config.pytest);
+    db.scan_workspace(root);
+
+    // Root conftest.py is still loaded even though it is outside testpaths
+    assert!(db.definitions.contains_key("client"));
+
+    let files_with_usages: Vec<_> = db
+        .usages
+        .iter()
+        .filter(|entry| !entry.value().is_empty())
+        .map(|entry| (entry.key().clone(), entry.value().clone()))
+        .collect();
+    assert_eq!(files_with_usages.len(), 1);
+
+    // Only check_login() is a test; helper() is a plain function
+    let (path, usages) = &files_with_usages[0];
+    assert!(path.ends_with("checks/check_login.py"));
+    assert_eq!(usages.len(), 1);
+    assert_eq!(usages[0].line, 1);
+}
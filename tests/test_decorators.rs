@@ -235,3 +235,89 @@ fn test_extract_parametrize_indirect() {
         }
     }
 }
+
+/// Parse `code` and run `extract_parametrize` on every decorator of the first function.
+fn parametrize_infos(code: &str) -> Vec<decorators::ParametrizeInfo> {
+    let parsed = parse(code, Mode::Module, "").unwrap();
+    let rustpython_parser::ast::Mod::Module(module) = parsed else {
+        panic!("expected module");
+    };
+    let rustpython_parser::ast::Stmt::FunctionDef(func_def) = &module.body[0] else {
+        panic!("expected function");
+    };
+    func_def
+        .decorator_list
+        .iter()
+        .filter_map(|d| decorators::extract_parametrize(d, code))
+        .collect()
+}
+
+#[test]
+#[timeout(30000)]
+fn test_extract_parametrize_comma_separated_with_indirect_list() {
+    let infos = parametrize_infos(
+        "@pytest.mark.parametrize('a, f1,b', [(1, 'x', 2)], indirect=['f1'])\ndef test_x(a, f1, b): pass",
+    );
+    assert_eq!(infos.len(), 1);
+    let info = &infos[0];
+    assert_eq!(info.argnames, vec!["a", "f1", "b"]);
+    assert_eq!(info.indirect, vec!["f1"]);
+    assert!(info.is_direct_argname("a"));
+    assert!(!info.is_direct_argname("f1"));
+    assert_eq!(info.values_for("b").0, vec!["2"]);
+}
+
+#[test]
+#[timeout(30000)]
+fn test_extract_parametrize_list_and_tuple_argnames() {
+    let infos = parametrize_infos(
+        "@pytest.mark.parametrize(['x', 'y'], [(1, 2), [3, 4]])\n@pytest.mark.parametrize(('z',), ['a'])\ndef test_x(x, y, z): pass",
+    );
+    assert_eq!(infos.len(), 2);
+    assert_eq!(infos[0].argnames, vec!["x", "y"]);
+    assert_eq!(infos[0].values_for("y").0, vec!["2", "4"]);
+    assert_eq!(infos[1].argnames, vec!["z"]);
+    assert_eq!(infos[1].values_for("z").0, vec!["'a'"]);
+}
+
+#[test]
+#[timeout(30000)]
+fn test_extract_parametrize_pytest_param_and_ids() {
+    let infos = parametrize_infos(
+        "@pytest.mark.parametrize(\n    argnames='n',\n    argvalues=[pytest.param(1, id='one'), 2, 3],\n    ids=['ignored', 'two'],\n)\ndef test_x(n): pass",
+    );
+    assert_eq!(infos.len(), 1);
+    let ids: Vec<Option<&str>> = infos[0].cases.iter().map(|c| c.id.as_deref()).collect();
+    assert_eq!(ids, vec![Some("one"), Some("two"), None]);
+    assert_eq!(infos[0].values_for("n").0, vec!["1", "2", "3"]);
+}
+
+#[test]
+#[timeout(30000)]
+fn test_extract_parametrize_ids_follow_cases_with_a_value() {
+    let infos = parametrize_infos(
+        "@pytest.mark.parametrize('x, y', [(1, 2), pytest.param(3, id='short'), (4, 5)], ids=['a', 'b', 'c'])\ndef test_x(x, y): pass",
+    );
+    assert_eq!(infos.len(), 1);
+    let (values, ids) = infos[0].values_for("y");
+    assert_eq!(values, vec!["2", "5"]);
+    assert_eq!(ids, vec![Some("a".to_string()), Some("c".to_string())]);
+}
+
+#[test]
+#[timeout(30000)]
+fn test_extract_parametrize_indirect_with_list_argnames() {
+    let code = "@pytest.mark.parametrize(['f1', 'f2'], [('a', 'b')], indirect=True)\ndef test_x(f1, f2): pass";
+    let parsed = parse(code, Mode::Module, "").unwrap();
+
+    if let rustpython_parser::ast::Mod::Module(module) = parsed {
+        if let rustpython_parser::ast::Stmt::FunctionDef(func_def) = &module.body[0] {
+            let fixtures =
+                decorators::extract_parametrize_indirect_fixtures(&func_def.decorator_list[0]);
+            let names: Vec<&str> = fixtures.iter().map(|(n, _)| n.as_str()).collect();
+            assert_eq!(names, vec!["f1", "f2"]);
+            // Each name maps to its own string literal
+            assert_ne!(fixtures[0].1, fixtures[1].1);
+        }
+    }
+}
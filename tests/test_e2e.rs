@@ -30,6 +30,12 @@ fn normalize_path_in_output(output: &str) -> String {
     )
 }
 
+// Helper function to create an empty in-project virtual environment, without
+// which unknown fixtures are not reported
+fn create_venv(root: &std::path::Path) {
+    std::fs::create_dir_all(root.join(".venv/lib/python3.12/site-packages")).unwrap();
+}
+
 // MARK: CLI E2E Tests
 
 #[test]
@@ -301,6 +307,44 @@ def test_something(my_fixture):
     std::fs::remove_dir_all(&temp_dir).ok();
 }
 
+#[test]
+#[timeout(30000)]
+fn test_cli_fixtures_unused_uses_project_configuration() {
+    let workspace = tempdir().unwrap();
+    let root = workspace.path();
+    std::fs::write(
+        root.join("pyproject.toml"),
+        "[tool.pytest-language-server]\nfixture_paths = [\"testing/fixtures\"]\nexclude = [\"vendored/**\"]\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(root.join("testing/fixtures")).unwrap();
+    std::fs::write(
+        root.join("testing/fixtures/db.py"),
+        "import pytest\n\n@pytest.fixture\ndef database():\n    return 1\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(root.join("vendored")).unwrap();
+    std::fs::write(
+        root.join("vendored/conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef vendored_fixture():\n    return 2\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(root.join("tests")).unwrap();
+    std::fs::write(
+        root.join("tests/test_db.py"),
+        "def test_db(database):\n    pass\n",
+    )
+    .unwrap();
+
+    // The fixture path fixture is used and the excluded one is never scanned
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    cmd.args(["fixtures", "unused"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No unused fixtures found"));
+}
+
 #[test]
 #[timeout(30000)]
 fn test_cli_fixtures_unused_nonexistent_path() {
@@ -406,6 +450,537 @@ fn test_cli_fixtures_list_shows_autouse_label() {
     );
 }
 
+#[test]
+#[timeout(30000)]
+fn test_cli_fixtures_list_includes_fixture_paths() {
+    let workspace = tempdir().unwrap();
+    let root = workspace.path();
+    std::fs::write(
+        root.join("pyproject.toml"),
+        "[tool.pytest-language-server]\nfixture_paths = [\"testing/fixtures\"]\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(root.join("testing/fixtures")).unwrap();
+    std::fs::write(
+        root.join("testing/fixtures/db.py"),
+        "import pytest\n\n@pytest.fixture\ndef database():\n    return 1\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(root.join("tests")).unwrap();
+    std::fs::write(
+        root.join("tests/test_db.py"),
+        "def test_db(database):\n    pass\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    let output = cmd
+        .args(["fixtures", "list"])
+        .arg(root)
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("fixtures/ (fixture path)"),
+        "fixture path directories should be labelled: {}",
+        stdout
+    );
+    assert!(
+        stdout.contains("database (used 1 time)"),
+        "fixtures from fixture paths should resolve for tests: {}",
+        stdout
+    );
+}
+
+// MARK: CLI check E2E Tests
+
+#[test]
+#[timeout(30000)]
+fn test_cli_check_test_project_output() {
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    let output = cmd
+        .arg("check")
+        .arg("tests/test_project")
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("Failed to execute command");
+
+    // test_undeclared_example.py intentionally contains undeclared fixtures
+    assert_eq!(output.status.code(), Some(1));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_snapshot!("cli_check_test_project", normalize_path_in_output(&stdout));
+}
+
+#[test]
+#[timeout(30000)]
+fn test_cli_check_reports_compiler_style_lines() {
+    let temp_dir = tempdir().unwrap();
+    std::fs::write(
+        temp_dir.path().join("conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef func_fix():\n    return 1\n\n@pytest.fixture(scope=\"session\")\ndef sess_fix(func_fix):\n    return func_fix\n",
+    )
+    .unwrap();
+    std::fs::write(
+        temp_dir.path().join("test_example.py"),
+        "def test_something(sess_fix, missing_fix):\n    pass\n",
+    )
+    .unwrap();
+    create_venv(temp_dir.path());
+
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    let output = cmd
+        .arg("check")
+        .arg(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(
+            "conftest.py:8:5: scope-mismatch session-scoped fixture 'sess_fix' depends on function-scoped fixture 'func_fix'"
+        ),
+        "unexpected output: {}",
+        stdout
+    );
+    assert!(
+        stdout.contains("test_example.py:1:30: unknown-fixture Fixture 'missing_fix' not found"),
+        "unexpected output: {}",
+        stdout
+    );
+}
+
+#[test]
+#[timeout(30000)]
+fn test_cli_check_honours_disabled_diagnostics() {
+    let temp_dir = tempdir().unwrap();
+    std::fs::write(
+        temp_dir.path().join("pyproject.toml"),
+        "[tool.pytest-language-server]\ndisabled_diagnostics = [\"unknown-fixture\"]\n",
+    )
+    .unwrap();
+    std::fs::write(
+        temp_dir.path().join("test_example.py"),
+        "def test_something(missing_fix):\n    pass\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    cmd.arg("check")
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No problems found"));
+}
+
+#[test]
+#[timeout(30000)]
+fn test_cli_check_skips_unknown_fixtures_without_virtual_environment() {
+    let temp_dir = tempdir().unwrap();
+    std::fs::write(
+        temp_dir.path().join("test_example.py"),
+        "def test_something(mocker):\n    pass\n",
+    )
+    .unwrap();
+
+    // Plugin fixtures like `mocker` cannot be known without an environment
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    cmd.arg("check")
+        .arg(temp_dir.path())
+        .env_remove("VIRTUAL_ENV")
+        .env_remove("CONDA_PREFIX")
+        .env_remove("UV_PROJECT_ENVIRONMENT")
+        .env("HOME", temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("unknown-fixture").not());
+}
+
+#[test]
+#[timeout(30000)]
+fn test_cli_check_nonexistent_path() {
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    cmd.arg("check")
+        .arg("/nonexistent/path/to/project")
+        .assert()
+        .failure();
+}
+
+// MARK: CLI fixtures graph E2E Tests
+
+#[test]
+#[timeout(30000)]
+fn test_cli_fixtures_graph_dot_output() {
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    let output = cmd
+        .args(["fixtures", "graph", "tests/test_project"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_snapshot!("cli_fixtures_graph_dot", normalize_path_in_output(&stdout));
+}
+
+#[test]
+#[timeout(30000)]
+fn test_cli_fixtures_graph_mermaid_with_scope_colors() {
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    let output = cmd
+        .args([
+            "fixtures",
+            "graph",
+            "tests/test_project",
+            "--format",
+            "mermaid",
+            "--color-scopes",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("flowchart LR\n"));
+    assert!(stdout.contains("n20[\"session_fixture (session)<br/>integration/test_scopes.py:5\"]"));
+    assert!(stdout.contains("classDef session fill:#f4cccc"));
+}
+
+#[test]
+#[timeout(30000)]
+fn test_cli_fixtures_graph_json_with_root_and_depth() {
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    let output = cmd
+        .args([
+            "fixtures",
+            "graph",
+            "tests/test_project",
+            "--format",
+            "json",
+            "--root",
+            "transaction",
+            "--max-depth",
+            "1",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let graph: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let names: Vec<&str> = graph["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|node| node["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["transaction", "db_cursor"]);
+    assert_eq!(graph["nodes"][1]["file"], "database/conftest.py");
+    assert_eq!(graph["edges"][0]["from"], "n0");
+    assert_eq!(graph["edges"][0]["to"], "n1");
+}
+
+#[test]
+#[timeout(30000)]
+fn test_cli_fixtures_graph_unknown_root() {
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    cmd.args([
+        "fixtures",
+        "graph",
+        "tests/test_project",
+        "--root",
+        "no_such_fixture",
+    ])
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains(
+        "Fixture not found: no_such_fixture",
+    ));
+}
+
+#[test]
+#[timeout(30000)]
+fn test_cli_fixtures_graph_uses_project_configuration() {
+    let workspace = tempdir().unwrap();
+    let root = workspace.path();
+    std::fs::write(
+        root.join("pyproject.toml"),
+        "[tool.pytest-language-server]\nfixture_paths = [\"testing/fixtures\"]\nexclude = [\"vendored/**\"]\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(root.join("testing/fixtures")).unwrap();
+    std::fs::write(
+        root.join("testing/fixtures/db.py"),
+        "import pytest\n\n@pytest.fixture\ndef database():\n    return 1\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(root.join("vendored")).unwrap();
+    std::fs::write(
+        root.join("vendored/conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef vendored_fixture():\n    return 2\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    let output = cmd
+        .args(["fixtures", "graph"])
+        .arg(root)
+        .args(["--format", "json"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let graph: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let names: Vec<&str> = graph["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|node| node["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["database"]);
+}
+
+// MARK: CLI fixtures closure E2E Tests
+
+#[test]
+#[timeout(30000)]
+fn test_cli_fixtures_closure_text_output() {
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    let output = cmd
+        .args([
+            "fixtures",
+            "closure",
+            "tests/test_project/database/test_models.py::test_transaction",
+            "--root",
+            "tests/test_project",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "database/test_models.py::test_transaction (3 fixtures)\n\
+         \x20 1. db_connection [requested by db_cursor] function database/conftest.py:5\n\
+         \x20 2. db_cursor [requested by transaction] function database/conftest.py:11\n\
+         \x20 3. transaction [parameter] function database/conftest.py:17\n"
+    );
+}
+
+#[test]
+#[timeout(30000)]
+fn test_cli_fixtures_closure_unknown_test() {
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    cmd.args([
+        "fixtures",
+        "closure",
+        "tests/test_project/database/test_models.py::test_missing",
+        "--root",
+        "tests/test_project",
+    ])
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("Test not found"));
+}
+
+#[test]
+#[timeout(30000)]
+fn test_cli_fixtures_closure_uses_project_configuration() {
+    let workspace = tempdir().unwrap();
+    let root = workspace.path();
+    std::fs::write(
+        root.join("pyproject.toml"),
+        "[tool.pytest-language-server]\nfixture_paths = [\"testing/fixtures\"]\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(root.join("testing/fixtures")).unwrap();
+    std::fs::write(
+        root.join("testing/fixtures/db.py"),
+        "import pytest\n\n@pytest.fixture\ndef database():\n    return 1\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(root.join("tests")).unwrap();
+    std::fs::write(
+        root.join("tests/test_db.py"),
+        "def test_db(database):\n    pass\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    let output = cmd
+        .args(["fixtures", "closure"])
+        .arg(format!(
+            "{}::test_db",
+            root.join("tests/test_db.py").display()
+        ))
+        .arg("--root")
+        .arg(root)
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("database [parameter] function testing/fixtures/db.py:4"),
+        "fixtures from fixture paths should be in the closure: {}",
+        stdout
+    );
+}
+
+// MARK: SARIF / GitHub output E2E Tests
+
+#[test]
+#[timeout(30000)]
+fn test_cli_fixtures_unused_sarif_output() {
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    let output = cmd
+        .args([
+            "fixtures",
+            "unused",
+            "tests/test_project",
+            "--format",
+            "sarif",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(1));
+
+    let sarif: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("SARIF output should be valid JSON");
+    assert_eq!(sarif["version"], "2.1.0");
+
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+    let iterator_fixture = results
+        .iter()
+        .find(|r| {
+            r["message"]["text"]
+                .as_str()
+                .is_some_and(|m| m.contains("'iterator_fixture'"))
+        })
+        .expect("iterator_fixture should be reported");
+    assert_eq!(iterator_fixture["ruleId"], "unused-fixture");
+
+    let location = &iterator_fixture["locations"][0]["physicalLocation"];
+    // Relative to the repository root, not the scanned directory
+    assert_eq!(
+        location["artifactLocation"]["uri"],
+        "tests/test_project/conftest.py"
+    );
+    let region = &location["region"];
+    let start = region["startColumn"].as_u64().unwrap();
+    let end = region["endColumn"].as_u64().unwrap();
+    assert_eq!(end - start, "iterator_fixture".len() as u64);
+}
+
+#[test]
+#[timeout(30000)]
+fn test_cli_check_github_output() {
+    let temp_dir = tempdir().unwrap();
+    std::fs::write(
+        temp_dir.path().join("test_example.py"),
+        "def test_something(missing_fix):\n    pass\n",
+    )
+    .unwrap();
+
+    create_venv(temp_dir.path());
+
+    // Outside a git repository paths are relative to the current directory
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    let output = cmd
+        .current_dir(temp_dir.path())
+        .args(["check", ".", "--format", "github"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout.trim_end(),
+        "::warning file=test_example.py,line=1,col=20,endLine=1,endColumn=31,title=unknown-fixture::Fixture 'missing_fix' not found"
+    );
+}
+
+#[test]
+#[timeout(30000)]
+fn test_cli_check_subdirectory_reports_repository_relative_paths() {
+    let temp_dir = tempdir().unwrap();
+    let repo = temp_dir.path();
+    std::fs::create_dir(repo.join(".git")).unwrap();
+    let tests_dir = repo.join("pkg").join("tests");
+    std::fs::create_dir_all(&tests_dir).unwrap();
+    std::fs::write(
+        tests_dir.join("test_example.py"),
+        "def test_something(missing_fix):\n    pass\n",
+    )
+    .unwrap();
+
+    create_venv(&tests_dir);
+    create_venv(&repo.join("pkg"));
+
+    // Run from the checked subdirectory itself
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    let output = cmd
+        .current_dir(&tests_dir)
+        .args(["check", ".", "--format", "github"])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("::warning file=pkg/tests/test_example.py,line=1,"),
+        "GitHub annotation should use a repository-relative path: {}",
+        stdout
+    );
+
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    let output = cmd
+        .arg("check")
+        .arg(repo.join("pkg"))
+        .args(["--format", "sarif"])
+        .output()
+        .expect("Failed to execute command");
+    let sarif: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        sarif["runs"][0]["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"]
+            ["uri"],
+        "pkg/tests/test_example.py"
+    );
+}
+
+#[test]
+#[timeout(30000)]
+fn test_cli_check_sarif_output_without_problems() {
+    let temp_dir = tempdir().unwrap();
+    std::fs::write(
+        temp_dir.path().join("test_example.py"),
+        "def test_something(tmp_path):\n    pass\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    let output = cmd
+        .arg("check")
+        .arg(temp_dir.path())
+        .args(["--format", "sarif"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let sarif: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(sarif["runs"][0]["results"].as_array().unwrap().is_empty());
+}
+
+#[test]
+#[timeout(30000)]
+fn test_cli_rejects_unknown_format() {
+    let mut cmd = Command::cargo_bin("pytest-language-server").unwrap();
+    cmd.args(["check", "tests/test_project", "--format", "xml"])
+        .assert()
+        .failure();
+}
+
 // MARK: Workspace Scanning E2E Tests
 
 #[test]
@@ -3,7 +3,7 @@
 //! All tests have a 30-second timeout to prevent hangs from blocking CI.
 
 use ntest::timeout;
-use pytest_language_server::FixtureDatabase;
+use pytest_language_server::{FixtureDatabase, PytestIniOptions};
 use std::path::PathBuf;
 
 #[test]
@@ -9107,16 +9107,18 @@ def test_normal_parametrize(value):
     let test_path = PathBuf::from("/tmp/test_indirect/test_normal.py");
     db.analyze_file(test_path.clone(), test_content);
 
-    // value should be detected as a parameter usage, but not as an indirect fixture
-    let usages = db.usages.get(&test_path).unwrap();
-    let value_usages: Vec<_> = usages.iter().filter(|u| u.name == "value").collect();
-
-    // Should only have 1 usage from the function parameter
-    assert_eq!(
-        value_usages.len(),
-        1,
-        "value should only have 1 usage (from parameter, not indirect)"
+    // value is supplied by parametrize, so it is not a fixture usage at all
+    assert!(
+        db.usages
+            .get(&test_path)
+            .is_none_or(|usages| usages.iter().all(|u| u.name != "value")),
+        "value should not be recorded as a fixture usage"
     );
+
+    let args = db.parametrized_args.get(&test_path).unwrap();
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0].name, "value");
+    assert_eq!(args[0].values, vec!["1", "2", "3"]);
 }
 
 // MARK: Scoping Tests - Issue #23
@@ -9821,6 +9823,54 @@ def my_fixture(my_fixture):
     );
 }
 
+#[test]
+#[timeout(30000)]
+fn test_cycle_detection_ignores_override_of_parent_fixture() {
+    let db = FixtureDatabase::new();
+
+    db.analyze_file(
+        PathBuf::from("/tmp/test_override_cycle/conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef my_fixture():\n    return 'base'\n",
+    );
+    db.analyze_file(
+        PathBuf::from("/tmp/test_override_cycle/sub/conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef my_fixture(my_fixture):\n    return my_fixture + '_modified'\n",
+    );
+
+    let cycles = db.detect_fixture_cycles();
+    assert!(
+        cycles.is_empty(),
+        "Overriding a parent fixture is not a cycle, got {:?}",
+        cycles
+    );
+}
+
+#[test]
+#[timeout(30000)]
+fn test_cycle_detection_self_dependency_without_visible_override() {
+    let db = FixtureDatabase::new();
+
+    // The same-named fixture in a sibling directory is not visible from b/,
+    // so `my_fixture(my_fixture)` has nothing to override.
+    db.analyze_file(
+        PathBuf::from("/tmp/test_sibling_cycle/b/conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef my_fixture(my_fixture):\n    return my_fixture\n",
+    );
+    db.analyze_file(
+        PathBuf::from("/tmp/test_sibling_cycle/a/conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef my_fixture():\n    return 'a'\n",
+    );
+
+    let cycles = db.detect_fixture_cycles();
+    assert!(
+        cycles
+            .iter()
+            .any(|cycle| cycle.cycle_path == vec!["my_fixture", "my_fixture"]),
+        "Expected a self-cycle, got {:?}",
+        cycles
+    );
+}
+
 #[test]
 #[timeout(30000)]
 fn test_cycle_detection_caching() {
@@ -15209,3 +15259,1653 @@ fn test_completion_context_text_fallback_fixture_scope_single_quotes() {
         ),
     }
 }
+
+// ============================================================================
+// Fixture rename
+// ============================================================================
+
+/// Collect rename locations as (file name, line, start_char, end_char) tuples.
+fn rename_spans(
+    db: &FixtureDatabase,
+    fixture_name: &str,
+    file: &str,
+    new_name: &str,
+) -> Vec<(String, usize, usize, usize)> {
+    let def = db
+        .definitions
+        .get(fixture_name)
+        .and_then(|defs| {
+            defs.iter()
+                .find(|d| d.file_path.as_path() == std::path::Path::new(file))
+                .cloned()
+        })
+        .expect("definition");
+    db.plan_fixture_rename(&def, new_name)
+        .expect("rename should succeed")
+        .into_iter()
+        .map(|l| {
+            (
+                l.file_path
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string(),
+                l.line,
+                l.start_char,
+                l.end_char,
+            )
+        })
+        .collect()
+}
+
+#[test]
+#[timeout(30000)]
+fn test_rename_covers_definition_params_strings_and_body() {
+    let db = FixtureDatabase::new();
+
+    let conftest = r#"import pytest
+
+@pytest.fixture
+def db():
+    return 1
+"#;
+    db.analyze_file(PathBuf::from("/tmp/rename1/conftest.py"), conftest);
+
+    let test_content = r#"import pytest
+
+@pytest.mark.usefixtures("db")
+def test_a():
+    pass
+
+def test_b(db):
+    assert db == 1
+    value = [db for _ in range(2)]
+
+@pytest.mark.parametrize("db, other", [(1, 2)], indirect=True)
+def test_c(db, other):
+    pass
+"#;
+    db.analyze_file(PathBuf::from("/tmp/rename1/test_a.py"), test_content);
+
+    let spans = rename_spans(&db, "db", "/tmp/rename1/conftest.py", "database");
+
+    assert!(spans.contains(&("conftest.py".to_string(), 4, 4, 6)));
+    // usefixtures string (quotes excluded)
+    assert!(spans.contains(&("test_a.py".to_string(), 3, 26, 28)));
+    // parameter and body references
+    assert!(spans.contains(&("test_a.py".to_string(), 7, 11, 13)));
+    assert!(spans.contains(&("test_a.py".to_string(), 8, 11, 13)));
+    assert!(spans.contains(&("test_a.py".to_string(), 9, 13, 15)));
+    // indirect argnames string narrowed to the fixture name
+    assert!(spans.contains(&("test_a.py".to_string(), 11, 26, 28)));
+    assert!(spans.contains(&("test_a.py".to_string(), 12, 11, 13)));
+    assert_eq!(spans.len(), 7, "unexpected spans: {:?}", spans);
+}
+
+#[test]
+#[timeout(30000)]
+fn test_rename_uses_name_argument_when_present() {
+    let db = FixtureDatabase::new();
+
+    let conftest = r#"import pytest
+
+@pytest.fixture(name="client")
+def _client_fixture():
+    return 1
+"#;
+    db.analyze_file(PathBuf::from("/tmp/rename2/conftest.py"), conftest);
+    db.analyze_file(
+        PathBuf::from("/tmp/rename2/test_a.py"),
+        "def test_a(client):\n    pass\n",
+    );
+
+    let spans = rename_spans(&db, "client", "/tmp/rename2/conftest.py", "api_client");
+    assert_eq!(
+        spans,
+        vec![
+            ("conftest.py".to_string(), 3, 22, 28),
+            ("test_a.py".to_string(), 1, 11, 17),
+        ]
+    );
+}
+
+#[test]
+#[timeout(30000)]
+fn test_rename_follows_override_chain() {
+    let db = FixtureDatabase::new();
+
+    db.analyze_file(
+        PathBuf::from("/tmp/rename3/conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef db():\n    return 1\n",
+    );
+    db.analyze_file(
+        PathBuf::from("/tmp/rename3/sub/conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef db(db):\n    return db + 1\n",
+    );
+    db.analyze_file(
+        PathBuf::from("/tmp/rename3/sub/test_a.py"),
+        "def test_a(db):\n    pass\n",
+    );
+
+    let spans = rename_spans(&db, "db", "/tmp/rename3/conftest.py", "database");
+
+    let sub_conftest: Vec<_> = spans
+        .iter()
+        .filter(|(f, l, _, _)| f == "conftest.py" && *l >= 4)
+        .collect();
+    // Parent def, child def name, child parameter, child body reference
+    assert_eq!(sub_conftest.len(), 4, "spans: {:?}", spans);
+    assert!(spans.contains(&("test_a.py".to_string(), 1, 11, 13)));
+}
+
+#[test]
+#[timeout(30000)]
+fn test_rename_child_override_leaves_parent_alone() {
+    let db = FixtureDatabase::new();
+
+    db.analyze_file(
+        PathBuf::from("/tmp/rename4/conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef db():\n    return 1\n",
+    );
+    db.analyze_file(
+        PathBuf::from("/tmp/rename4/sub/conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef db(db):\n    return db + 1\n",
+    );
+    db.analyze_file(
+        PathBuf::from("/tmp/rename4/sub/test_a.py"),
+        "def test_a(db):\n    pass\n",
+    );
+
+    let spans = rename_spans(&db, "db", "/tmp/rename4/sub/conftest.py", "wrapped_db");
+    // Only the child's own name and the test parameter; `def db(db)`'s parameter
+    // still refers to the parent fixture.
+    assert_eq!(
+        spans,
+        vec![
+            ("conftest.py".to_string(), 4, 4, 6),
+            ("test_a.py".to_string(), 1, 11, 13),
+        ]
+    );
+}
+
+#[test]
+#[timeout(30000)]
+fn test_rename_rejects_invalid_and_conflicting_names() {
+    let db = FixtureDatabase::new();
+
+    db.analyze_file(
+        PathBuf::from("/tmp/rename5/conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef db():\n    return 1\n\n@pytest.fixture\ndef other():\n    return 2\n",
+    );
+    db.analyze_file(
+        PathBuf::from("/tmp/rename5/test_a.py"),
+        "def test_a(db, extra):\n    pass\n",
+    );
+
+    let def = db.definitions.get("db").unwrap()[0].clone();
+    assert!(db.plan_fixture_rename(&def, "not valid").is_err());
+    assert!(db.plan_fixture_rename(&def, "class").is_err());
+    assert!(db.plan_fixture_rename(&def, "other").is_err());
+    assert!(db.plan_fixture_rename(&def, "extra").is_err());
+    assert_eq!(db.plan_fixture_rename(&def, "db").unwrap(), vec![]);
+}
+
+#[test]
+#[timeout(30000)]
+fn test_rename_rejects_third_party_fixture() {
+    let db = FixtureDatabase::new();
+
+    db.analyze_file(
+        PathBuf::from("/tmp/rename6/.venv/lib/python3.12/site-packages/plugin/fixtures.py"),
+        "import pytest\n\n@pytest.fixture\ndef vendored():\n    return 1\n",
+    );
+    let def = db.definitions.get("vendored").unwrap()[0].clone();
+    assert!(def.is_third_party);
+
+    let err = db.plan_fixture_rename(&def, "renamed").unwrap_err();
+    assert!(err.contains("third-party"), "unexpected error: {}", err);
+}
+
+#[test]
+#[timeout(30000)]
+fn test_find_rename_target_on_name_argument() {
+    let db = FixtureDatabase::new();
+
+    let path = PathBuf::from("/tmp/rename7/conftest.py");
+    db.analyze_file(
+        path.clone(),
+        "import pytest\n\n@pytest.fixture(name=\"client\")\ndef _client():\n    return 1\n",
+    );
+
+    let (def, location) = db.find_rename_target(&path, 2, 24).expect("target");
+    assert_eq!(def.name, "client");
+    assert_eq!(
+        (location.line, location.start_char, location.end_char),
+        (3, 22, 28)
+    );
+
+    // The Python function name is not the fixture name
+    assert!(db.find_rename_target(&path, 3, 6).is_none());
+}
+
+// ============================================================================
+// Unknown fixture detection
+// ============================================================================
+
+#[test]
+#[timeout(30000)]
+fn test_unknown_fixture_detected_for_typo() {
+    let db = FixtureDatabase::new();
+
+    db.analyze_file(
+        PathBuf::from("/tmp/unknown1/conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef database():\n    return 1\n",
+    );
+    let test_path = PathBuf::from("/tmp/unknown1/test_db.py");
+    db.analyze_file(
+        test_path.clone(),
+        "def test_db(database, databse):\n    pass\n",
+    );
+
+    let unknown = db.detect_unknown_fixtures_in_file(&test_path);
+    assert_eq!(unknown.len(), 1);
+    assert_eq!(unknown[0].name, "databse");
+    assert_eq!(
+        (unknown[0].line, unknown[0].start_char, unknown[0].end_char),
+        (1, 22, 29)
+    );
+
+    assert_eq!(
+        db.suggest_fixture_names(&test_path, "databse"),
+        vec!["database".to_string()]
+    );
+}
+
+#[test]
+#[timeout(30000)]
+fn test_unknown_fixture_skips_non_fixture_parameters() {
+    let db = FixtureDatabase::new();
+
+    let test_path = PathBuf::from("/tmp/unknown2/test_params.py");
+    let content = r#"
+import pytest
+
+pytestmark = pytest.mark.parametrize("module_arg", [1])
+
+
+@pytest.mark.parametrize("x, y", [(1, 2)])
+def test_direct(x, y, module_arg, tmp_path, option=3):
+    pass
+
+
+@pytest.mark.parametrize("class_arg", [1])
+class TestGroup:
+    def test_method(self, class_arg):
+        pass
+
+    @classmethod
+    def test_classmethod(cls):
+        pass
+
+
+@pytest.mark.parametrize("missing", ["a"], indirect=True)
+def test_indirect(missing):
+    pass
+"#;
+    db.analyze_file(test_path.clone(), content);
+
+    let names: Vec<String> = db
+        .detect_unknown_fixtures_in_file(&test_path)
+        .into_iter()
+        .map(|u| u.name)
+        .collect();
+    // Only the indirect argname is a real fixture request that resolves to nothing
+    assert_eq!(names, vec!["missing".to_string()]);
+}
+
+#[test]
+#[timeout(30000)]
+fn test_unknown_fixture_in_fixture_dependencies() {
+    let db = FixtureDatabase::new();
+
+    let conftest = PathBuf::from("/tmp/unknown3/conftest.py");
+    db.analyze_file(
+        conftest.clone(),
+        "import pytest\n\n@pytest.fixture\ndef client(request, sesion):\n    return 1\n",
+    );
+
+    let unknown = db.detect_unknown_fixtures_in_file(&conftest);
+    assert_eq!(unknown.len(), 1);
+    assert_eq!(unknown[0].name, "sesion");
+    assert!(db.suggest_fixture_names(&conftest, "sesion").is_empty());
+}
+
+#[test]
+#[timeout(30000)]
+fn test_unknown_fixture_suggestions_are_ranked() {
+    let db = FixtureDatabase::new();
+
+    db.analyze_file(
+        PathBuf::from("/tmp/unknown4/conftest.py"),
+        r#"
+import pytest
+
+@pytest.fixture
+def user():
+    return 1
+
+@pytest.fixture
+def users():
+    return 2
+
+@pytest.fixture
+def admin_user():
+    return 3
+"#,
+    );
+    let test_path = PathBuf::from("/tmp/unknown4/test_users.py");
+    db.analyze_file(test_path.clone(), "def test_users(usr):\n    pass\n");
+
+    assert_eq!(
+        db.suggest_fixture_names(&test_path, "usr"),
+        vec!["user".to_string(), "users".to_string()]
+    );
+}
+
+#[test]
+#[timeout(30000)]
+fn test_unknown_fixture_skips_modules_with_generate_tests_hook() {
+    let db = FixtureDatabase::new();
+
+    let test_path = PathBuf::from("/tmp/unknown5/test_generated.py");
+    db.analyze_file(
+        test_path.clone(),
+        r#"
+def pytest_generate_tests(metafunc):
+    if "number" in metafunc.fixturenames:
+        metafunc.parametrize("number", [1, 2])
+
+
+def test_number(number):
+    pass
+"#,
+    );
+    assert!(db.detect_unknown_fixtures_in_file(&test_path).is_empty());
+
+    // Without the hook the same parameter is unknown
+    let plain_path = PathBuf::from("/tmp/unknown5/other/test_plain.py");
+    db.analyze_file(plain_path.clone(), "def test_number(number):\n    pass\n");
+    assert_eq!(db.detect_unknown_fixtures_in_file(&plain_path).len(), 1);
+}
+
+#[test]
+#[timeout(30000)]
+fn test_unknown_fixture_skips_files_under_conftest_with_generate_tests_hook() {
+    let db = FixtureDatabase::new();
+
+    db.analyze_file(
+        PathBuf::from("/tmp/unknown6/conftest.py"),
+        r#"
+def pytest_generate_tests(metafunc):
+    if "number" in metafunc.fixturenames:
+        metafunc.parametrize("number", [1, 2])
+"#,
+    );
+    let test_path = PathBuf::from("/tmp/unknown6/sub/test_generated.py");
+    db.analyze_file(test_path.clone(), "def test_number(number):\n    pass\n");
+
+    assert!(db.detect_unknown_fixtures_in_file(&test_path).is_empty());
+}
+
+// ============================================================================
+// Parametrize argnames
+// ============================================================================
+
+#[test]
+#[timeout(30000)]
+fn test_parametrize_argnames_are_not_fixture_usages() {
+    let db = FixtureDatabase::new();
+
+    let test_path = PathBuf::from("/tmp/parametrize1/test_args.py");
+    let content = r#"
+import pytest
+
+pytestmark = pytest.mark.parametrize("module_arg", [0])
+
+
+@pytest.mark.parametrize(["x", "y"], [(1, 2), pytest.param(3, 4, id="big")])
+@pytest.mark.parametrize("flag", [True, False], ids=["on", "off"])
+def test_stacked(x, y, flag, module_arg, tmp_path):
+    pass
+
+
+@pytest.mark.parametrize("class_arg", ["a"])
+class TestGroup:
+    pytestmark = [pytest.mark.parametrize("inner", [None])]
+
+    def test_method(self, class_arg, inner, db):
+        pass
+
+
+@pytest.mark.parametrize("fix", ["v"], indirect=True)
+def test_indirect(fix):
+    pass
+"#;
+    db.analyze_file(test_path.clone(), content);
+
+    let mut usage_names: Vec<String> = db
+        .usages
+        .get(&test_path)
+        .unwrap()
+        .iter()
+        .filter(|u| u.is_parameter)
+        .map(|u| u.name.clone())
+        .collect();
+    usage_names.sort();
+    assert_eq!(usage_names, vec!["db", "fix", "tmp_path"]);
+
+    let args = db.parametrized_args.get(&test_path).unwrap();
+    let mut arg_names: Vec<&str> = args.iter().map(|a| a.name.as_str()).collect();
+    arg_names.sort();
+    assert_eq!(
+        arg_names,
+        vec!["class_arg", "flag", "inner", "module_arg", "x", "y"]
+    );
+
+    let y = args.iter().find(|a| a.name == "y").unwrap();
+    assert_eq!(y.values, vec!["2", "4"]);
+    assert_eq!(y.ids, vec![None, Some("big".to_string())]);
+
+    let flag = args.iter().find(|a| a.name == "flag").unwrap();
+    assert_eq!(flag.values, vec!["True", "False"]);
+    assert_eq!(
+        flag.ids,
+        vec![Some("on".to_string()), Some("off".to_string())]
+    );
+}
+
+#[test]
+#[timeout(30000)]
+fn test_parametrize_argname_shadowing_fixture_does_not_resolve() {
+    let db = FixtureDatabase::new();
+
+    db.analyze_file(
+        PathBuf::from("/tmp/parametrize2/conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef value():\n    return 1\n",
+    );
+    let test_path = PathBuf::from("/tmp/parametrize2/test_value.py");
+    db.analyze_file(
+        test_path.clone(),
+        "import pytest\n\n@pytest.mark.parametrize(\"value\", [1])\ndef test_value(value):\n    pass\n",
+    );
+
+    // Cursor on the parameter: not a fixture, but a parametrized argument
+    assert!(db.find_fixture_definition(&test_path, 3, 16).is_none());
+    let arg = db
+        .find_parametrized_argument_at_position(&test_path, 3, 16)
+        .expect("parametrized argument");
+    assert_eq!(arg.name, "value");
+    assert_eq!((arg.line, arg.start_char, arg.end_char), (4, 15, 20));
+}
+
+// ============================================================================
+// Pytest collection options (python_functions / python_classes)
+// ============================================================================
+
+#[test]
+#[timeout(30000)]
+fn test_python_functions_and_classes_control_test_detection() {
+    let db = FixtureDatabase::new();
+    db.set_pytest_options(PytestIniOptions {
+        python_functions: vec!["check_*".to_string()],
+        python_classes: vec!["Check".to_string()],
+        ..Default::default()
+    });
+
+    let test_path = PathBuf::from("/tmp/pytest_options/test_checks.py");
+    let content = r#"
+def check_user(db):
+    pass
+
+def test_user(db):
+    pass
+
+class CheckUsers:
+    def check_list(self, session):
+        pass
+
+class Helpers:
+    def check_other(self, other):
+        pass
+"#;
+    db.analyze_file(test_path.clone(), content);
+
+    let usages = db.usages.get(&test_path).unwrap();
+    let names: Vec<&str> = usages.iter().map(|u| u.name.as_str()).collect();
+    assert_eq!(names, vec!["db", "session"]);
+}
+
+// ============================================================================
+// Fixture dependency graph
+// ============================================================================
+
+#[test]
+#[timeout(30000)]
+fn test_fixture_graph_keeps_overrides_distinct() {
+    let db = FixtureDatabase::new();
+    db.analyze_file(
+        PathBuf::from("/tmp/test_graph/conftest.py"),
+        "import pytest\n\n@pytest.fixture(scope=\"session\")\ndef db():\n    return 'base'\n",
+    );
+    db.analyze_file(
+        PathBuf::from("/tmp/test_graph/sub/conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef db(db, tmp_path):\n    return db\n\n@pytest.fixture\ndef client(db):\n    return db\n",
+    );
+
+    let graph = db.build_fixture_graph(Some("client"), None);
+    let describe = |index: usize| {
+        let node = &graph.nodes[index];
+        match &node.definition {
+            Some(def) => format!("{}@{}", node.name, def.file_path.display()),
+            None => node.name.clone(),
+        }
+    };
+    let edges: Vec<(String, String)> = graph
+        .edges
+        .iter()
+        .map(|edge| (describe(edge.from), describe(edge.to)))
+        .collect();
+
+    assert_eq!(graph.nodes.len(), 4);
+    assert_eq!(
+        edges,
+        vec![
+            (
+                "client@/tmp/test_graph/sub/conftest.py".to_string(),
+                "db@/tmp/test_graph/sub/conftest.py".to_string()
+            ),
+            (
+                "db@/tmp/test_graph/sub/conftest.py".to_string(),
+                "db@/tmp/test_graph/conftest.py".to_string()
+            ),
+            (
+                "db@/tmp/test_graph/sub/conftest.py".to_string(),
+                "tmp_path".to_string()
+            ),
+        ]
+    );
+
+    // Depth 1 stops after the direct dependency
+    let shallow = db.build_fixture_graph(Some("client"), Some(1));
+    assert_eq!(shallow.nodes.len(), 2);
+    assert_eq!(shallow.edges.len(), 1);
+
+    assert!(db
+        .build_fixture_graph(Some("missing"), None)
+        .nodes
+        .is_empty());
+}
+
+// ============================================================================
+// Persistent index cache
+// ============================================================================
+
+fn definition_summary(db: &FixtureDatabase) -> Vec<(String, PathBuf, usize, Vec<String>)> {
+    let mut summary: Vec<_> = db
+        .definitions
+        .iter()
+        .flat_map(|entry| {
+            entry
+                .value()
+                .iter()
+                .map(|def| {
+                    (
+                        def.name.clone(),
+                        def.file_path.clone(),
+                        def.line,
+                        def.dependencies.clone(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    summary.sort();
+    summary
+}
+
+#[test]
+#[timeout(30000)]
+fn test_index_cache_warm_start_restores_and_reanalyzes_changed_files() {
+    use tempfile::tempdir;
+
+    let workspace = tempdir().unwrap();
+    let cache = tempdir().unwrap();
+    let root = workspace.path().canonicalize().unwrap();
+    std::fs::write(
+        root.join("conftest.py"),
+        "import pytest\nfrom helpers import *\n\n@pytest.fixture\ndef db():\n    return 1\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("test_app.py"),
+        "def test_app(db, helper):\n    assert db\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("helpers.py"),
+        "import pytest\n\n@pytest.fixture\ndef helper(db):\n    return db\n",
+    )
+    .unwrap();
+
+    let cold = FixtureDatabase::new();
+    cold.enable_index_cache(cache.path());
+    cold.scan_workspace(&root);
+    cold.save_index_cache();
+    assert!(cache.path().join("index.json").exists());
+
+    // Unchanged files (including the star-imported module) come back identical
+    let warm = FixtureDatabase::new();
+    warm.enable_index_cache(cache.path());
+    warm.scan_workspace(&root);
+    assert_eq!(definition_summary(&warm), definition_summary(&cold));
+    let usages: Vec<String> = warm
+        .usages
+        .get(&root.join("test_app.py"))
+        .unwrap()
+        .iter()
+        .map(|u| u.name.clone())
+        .collect();
+    assert_eq!(usages, vec!["db", "helper"]);
+    assert!(warm
+        .find_fixture_definition(&root.join("test_app.py"), 0, 22)
+        .is_some_and(|def| def.file_path == root.join("helpers.py")));
+    warm.save_index_cache();
+
+    // A changed file is re-analyzed instead of restored
+    std::fs::write(
+        root.join("conftest.py"),
+        "import pytest\nfrom helpers import *\n\n@pytest.fixture\ndef database():\n    return 1\n",
+    )
+    .unwrap();
+    let changed = FixtureDatabase::new();
+    changed.enable_index_cache(cache.path());
+    changed.scan_workspace(&root);
+    assert!(changed.definitions.contains_key("database"));
+    assert!(!changed.definitions.contains_key("db"));
+}
+
+#[test]
+#[timeout(30000)]
+fn test_index_cache_discarded_when_pytest_options_change() {
+    use tempfile::tempdir;
+
+    let workspace = tempdir().unwrap();
+    let cache = tempdir().unwrap();
+    let root = workspace.path().canonicalize().unwrap();
+    std::fs::write(
+        root.join("test_checks.py"),
+        "def check_user(db):\n    pass\n",
+    )
+    .unwrap();
+
+    let db = FixtureDatabase::new();
+    db.enable_index_cache(cache.path());
+    db.scan_workspace(&root);
+    db.save_index_cache();
+    assert!(db.usages.get(&root.join("test_checks.py")).is_none());
+
+    // With python_functions = check_*, the cached (usage-free) result is stale
+    let db = FixtureDatabase::new();
+    db.set_pytest_options(PytestIniOptions {
+        python_functions: vec!["check_*".to_string()],
+        ..Default::default()
+    });
+    db.enable_index_cache(cache.path());
+    db.scan_workspace(&root);
+    assert_eq!(
+        db.usages.get(&root.join("test_checks.py")).unwrap().len(),
+        1
+    );
+}
+
+#[test]
+#[timeout(30000)]
+fn test_index_cache_saves_scanned_files_evicted_from_memory() {
+    use tempfile::tempdir;
+
+    let workspace = tempdir().unwrap();
+    let cache = tempdir().unwrap();
+    let root = workspace.path().canonicalize().unwrap();
+    std::fs::write(
+        root.join("test_app.py"),
+        "def test_app(tmp_path):\n    pass\n",
+    )
+    .unwrap();
+
+    let db = FixtureDatabase::new();
+    db.enable_index_cache(cache.path());
+    db.scan_workspace(&root);
+    // Simulate the content cache evicting the file before the index is written
+    db.file_cache.remove(&root.join("test_app.py"));
+    db.save_index_cache();
+
+    let index: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(cache.path().join("index.json")).unwrap())
+            .unwrap();
+    let key = root.join("test_app.py").to_string_lossy().into_owned();
+    assert!(
+        index["files"].get(&key).is_some(),
+        "Scanned file should stay in the index: {}",
+        index["files"]
+    );
+}
+
+#[test]
+#[timeout(30000)]
+fn test_index_cache_warm_start_honours_fixture_paths() {
+    use tempfile::tempdir;
+
+    let workspace = tempdir().unwrap();
+    let root = workspace.path().canonicalize().unwrap();
+    let cache_dir = root.join(".pytest_cache").join("pytest-language-server");
+    std::fs::create_dir_all(root.join("shared")).unwrap();
+    std::fs::write(
+        root.join("shared").join("conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef shared_fix():\n    return 1\n",
+    )
+    .unwrap();
+
+    let cold = FixtureDatabase::new();
+    cold.enable_index_cache(&cache_dir);
+    cold.scan_workspace(&root);
+    cold.save_index_cache();
+    assert!(!cold.definitions.get("shared_fix").unwrap()[0].is_plugin);
+
+    // Listing the directory in fixture_paths turns its fixtures into plugin fixtures
+    let warm = FixtureDatabase::new();
+    warm.set_root_fixture_paths(&root, vec![root.join("shared")]);
+    warm.enable_index_cache(&cache_dir);
+    warm.scan_workspace(&root);
+    let def = warm.definitions.get("shared_fix").unwrap()[0].clone();
+    assert!(def.is_plugin);
+    assert_eq!(def.fixture_path, Some(root.join("shared")));
+}
+
+#[test]
+#[timeout(30000)]
+fn test_index_cache_reanalyzes_files_when_package_layout_changes() {
+    use tempfile::tempdir;
+
+    let workspace = tempdir().unwrap();
+    let cache = tempdir().unwrap();
+    let root = workspace.path().canonicalize().unwrap();
+    std::fs::create_dir_all(root.join("pkg")).unwrap();
+    std::fs::write(
+        root.join("pkg").join("conftest.py"),
+        "import pytest\n\nclass Db:\n    pass\n\n@pytest.fixture\ndef db() -> Db:\n    return Db()\n",
+    )
+    .unwrap();
+    let type_import = |db: &FixtureDatabase| {
+        db.definitions.get("db").unwrap()[0].return_type_imports[0]
+            .import_statement
+            .clone()
+    };
+
+    let cold = FixtureDatabase::new();
+    cold.enable_index_cache(cache.path());
+    cold.scan_workspace(&root);
+    cold.save_index_cache();
+    assert_eq!(type_import(&cold), "from conftest import Db");
+
+    // The unchanged conftest now lives in a package
+    std::fs::write(root.join("pkg").join("__init__.py"), "").unwrap();
+    let warm = FixtureDatabase::new();
+    warm.enable_index_cache(cache.path());
+    warm.scan_workspace(&root);
+    assert_eq!(type_import(&warm), "from pkg.conftest import Db");
+}
+
+// ============================================================================
+// Multi-root workspaces
+// ============================================================================
+
+#[test]
+#[timeout(30000)]
+fn test_root_pytest_options_apply_to_files_under_root() {
+    let db = FixtureDatabase::new();
+    db.set_root_pytest_options(
+        std::path::Path::new("/tmp/multi_root/checks"),
+        PytestIniOptions {
+            python_functions: vec!["check_*".to_string()],
+            ..Default::default()
+        },
+    );
+
+    let content = "def check_user(db):\n    pass\n\ndef test_user(session):\n    pass\n";
+    let in_root = PathBuf::from("/tmp/multi_root/checks/test_users.py");
+    let elsewhere = PathBuf::from("/tmp/multi_root/other/test_users.py");
+    db.analyze_file(in_root.clone(), content);
+    db.analyze_file(elsewhere.clone(), content);
+
+    let names = |path: &PathBuf| -> Vec<String> {
+        db.usages
+            .get(path)
+            .unwrap()
+            .iter()
+            .map(|u| u.name.clone())
+            .collect()
+    };
+    assert_eq!(names(&in_root), vec!["db"]);
+    assert_eq!(names(&elsewhere), vec!["session"]);
+}
+
+#[test]
+#[timeout(30000)]
+fn test_remove_workspace_root_forgets_its_files() {
+    use tempfile::tempdir;
+
+    let first = tempdir().unwrap();
+    let second = tempdir().unwrap();
+    let first_root = first.path().canonicalize().unwrap();
+    let second_root = second.path().canonicalize().unwrap();
+    std::fs::write(
+        first_root.join("conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef shared():\n    return 1\n",
+    )
+    .unwrap();
+    std::fs::write(
+        first_root.join("test_first.py"),
+        "def test_first(shared):\n    pass\n",
+    )
+    .unwrap();
+    std::fs::write(
+        second_root.join("conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef shared():\n    return 2\n",
+    )
+    .unwrap();
+
+    let db = FixtureDatabase::new();
+    db.scan_workspace(&first_root);
+    db.scan_workspace(&second_root);
+    assert_eq!(db.definitions.get("shared").unwrap().len(), 2);
+    assert_eq!(db.workspace_files().len(), 3);
+
+    db.remove_workspace_root(&first_root);
+
+    let remaining = db.definitions.get("shared").unwrap().clone();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].file_path, second_root.join("conftest.py"));
+    assert!(db.usages.get(&first_root.join("test_first.py")).is_none());
+    assert!(db.find_fixture_references("shared").is_empty());
+    assert_eq!(db.workspace_files(), vec![second_root.join("conftest.py")]);
+    assert_eq!(*db.workspace_root.lock().unwrap(), Some(second_root));
+}
+
+#[test]
+#[timeout(30000)]
+fn test_venv_plugin_fixtures_are_scoped_to_their_root() {
+    use tempfile::tempdir;
+
+    let first = tempdir().unwrap();
+    let second = tempdir().unwrap();
+    let first_root = first.path().canonicalize().unwrap();
+    let second_root = second.path().canonicalize().unwrap();
+
+    // Only the first root has a virtual environment with a pytest11 plugin
+    let site_packages = first_root
+        .join(".venv")
+        .join("lib")
+        .join("python3.11")
+        .join("site-packages");
+    std::fs::create_dir_all(site_packages.join("venv_plugin")).unwrap();
+    std::fs::write(
+        site_packages.join("venv_plugin").join("__init__.py"),
+        "import pytest\n\n@pytest.fixture\ndef venv_fix():\n    return 1\n",
+    )
+    .unwrap();
+    let dist_info = site_packages.join("venv_plugin-1.0.dist-info");
+    std::fs::create_dir_all(&dist_info).unwrap();
+    std::fs::write(
+        dist_info.join("entry_points.txt"),
+        "[pytest11]\nvenv_plugin = venv_plugin\n",
+    )
+    .unwrap();
+
+    let test_content = "def test_it(venv_fix):\n    pass\n";
+    std::fs::write(first_root.join("test_first.py"), test_content).unwrap();
+    std::fs::write(second_root.join("test_second.py"), test_content).unwrap();
+
+    let db = FixtureDatabase::new();
+    db.scan_workspace(&first_root);
+    db.scan_workspace(&second_root);
+
+    let available = |path: &std::path::Path| {
+        db.get_available_fixtures(path)
+            .iter()
+            .any(|def| def.name == "venv_fix")
+    };
+    assert!(db
+        .find_fixture_definition(&first_root.join("test_first.py"), 0, 13)
+        .is_some());
+    assert!(available(&first_root.join("test_first.py")));
+    assert!(db
+        .find_fixture_definition(&second_root.join("test_second.py"), 0, 13)
+        .is_none());
+    assert!(!available(&second_root.join("test_second.py")));
+
+    // Removing the root also forgets its environment's fixtures
+    db.remove_workspace_root(&first_root);
+    assert!(!db.definitions.contains_key("venv_fix"));
+    assert!(!db
+        .site_packages_paths
+        .lock()
+        .unwrap()
+        .contains(&site_packages.canonicalize().unwrap()));
+}
+
+// ============================================================================
+// Parametrized fixtures (params= / ids=)
+// ============================================================================
+
+#[test]
+#[timeout(30000)]
+fn test_fixture_params_and_ids_are_extracted() {
+    let db = FixtureDatabase::new();
+    let conftest = PathBuf::from("/tmp/fixture_params/conftest.py");
+    db.analyze_file(
+        conftest.clone(),
+        r#"import pytest
+
+@pytest.fixture(params=["sqlite", pytest.param("postgres", id="pg"), "mysql"], ids=["lite", "ignored"])
+def backend(request):
+    return request.param
+
+@pytest.fixture(params=VALUES)
+def dynamic(request):
+    return request.param
+
+@pytest.fixture
+def plain():
+    return 1
+"#,
+    );
+
+    let backend = &db.definitions.get("backend").unwrap()[0];
+    assert_eq!(
+        backend.params,
+        vec!["\"sqlite\"", "\"postgres\"", "\"mysql\""]
+    );
+    // pytest.param(id=...) wins over ids=; ids= may be shorter than params=
+    assert_eq!(
+        backend.param_ids,
+        vec![Some("lite".to_string()), Some("pg".to_string()), None]
+    );
+
+    // Non-literal params cannot be enumerated
+    assert!(db.definitions.get("dynamic").unwrap()[0].params.is_empty());
+    assert!(db.definitions.get("plain").unwrap()[0].params.is_empty());
+
+    // Cursor on `request.param` inside the parametrized fixture (0-based line 4)
+    let fixture = db
+        .find_request_param_at_position(&conftest, 4, 20)
+        .expect("request.param of backend");
+    assert_eq!(fixture.name, "backend");
+    assert!(db
+        .find_request_param_at_position(&conftest, 3, 12)
+        .is_none());
+    // `dynamic` has no known params
+    assert!(db
+        .find_request_param_at_position(&conftest, 8, 20)
+        .is_none());
+}
+
+#[test]
+#[timeout(30000)]
+fn test_find_test_variants_multiplies_params() {
+    let db = FixtureDatabase::new();
+    db.analyze_file(
+        PathBuf::from("/tmp/fixture_variants/conftest.py"),
+        r#"import pytest
+
+@pytest.fixture(params=[1, 2])
+def number(request):
+    return request.param
+
+@pytest.fixture(params=["a", "b", "c"])
+def letter(request):
+    return request.param
+
+@pytest.fixture
+def pair(number, letter):
+    return (number, letter)
+"#,
+    );
+    let test_path = PathBuf::from("/tmp/fixture_variants/test_variants.py");
+    db.analyze_file(
+        test_path.clone(),
+        r#"import pytest
+
+def test_number(number):
+    pass
+
+def test_pair(pair):
+    pass
+
+@pytest.mark.parametrize("flag", [True, False])
+def test_flagged(number, flag):
+    pass
+
+@pytest.mark.parametrize("number", [10], indirect=True)
+def test_indirect(number):
+    pass
+
+class TestGroup:
+    def test_method(self, letter):
+        pass
+
+def test_plain(tmp_path):
+    pass
+"#,
+    );
+
+    let variants: Vec<(String, usize, usize, Vec<String>)> = db
+        .find_test_variants(&test_path)
+        .into_iter()
+        .map(|v| (v.test_name, v.line, v.count, v.fixtures))
+        .collect();
+    assert_eq!(
+        variants,
+        vec![
+            ("test_number".to_string(), 3, 2, vec!["number".to_string()]),
+            (
+                "test_pair".to_string(),
+                6,
+                6,
+                vec!["number".to_string(), "letter".to_string()]
+            ),
+            (
+                "test_flagged".to_string(),
+                10,
+                4,
+                vec!["number".to_string()]
+            ),
+            ("test_method".to_string(), 18, 3, vec!["letter".to_string()]),
+        ]
+    );
+}
+
+// ============================================================================
+// Class-scoped fixtures
+// ============================================================================
+
+#[test]
+#[timeout(30000)]
+fn test_class_fixture_visible_in_class_and_subclasses_only() {
+    let db = FixtureDatabase::new();
+    db.analyze_file(
+        PathBuf::from("/tmp/class_fixtures/conftest.py"),
+        r#"import pytest
+
+@pytest.fixture
+def db():
+    return "module"
+"#,
+    );
+    let test_path = PathBuf::from("/tmp/class_fixtures/test_classes.py");
+    db.analyze_file(
+        test_path.clone(),
+        r#"import pytest
+
+class TestBase:
+    @pytest.fixture
+    def client(self):
+        return 1
+
+    @pytest.fixture
+    def db(self):
+        return "class"
+
+    def test_base(self, client, db):
+        pass
+
+class TestChild(TestBase):
+    def test_child(self, client, db):
+        pass
+
+class TestOther:
+    def test_other(self, client, db):
+        pass
+
+def test_module(client, db):
+    pass
+"#,
+    );
+
+    let definition_at = |line: u32, character: u32| {
+        db.find_fixture_definition(&test_path, line, character)
+            .map(|def| (def.file_path.file_name().unwrap().to_owned(), def.line))
+    };
+    let class_client = Some(("test_classes.py".into(), 5));
+    let class_db = Some(("test_classes.py".into(), 9));
+    let conftest_db = Some(("conftest.py".into(), 4));
+
+    // Inside the defining class (0-based line 11: `def test_base(self, client, db)`)
+    assert_eq!(definition_at(11, 24), class_client);
+    assert_eq!(definition_at(11, 32), class_db);
+    // Subclass inherits the fixtures of its base
+    assert_eq!(definition_at(15, 25), class_client);
+    assert_eq!(definition_at(15, 33), class_db);
+    // Unrelated class and module level only see module fixtures
+    assert_eq!(definition_at(19, 25), None);
+    assert_eq!(definition_at(19, 33), conftest_db);
+    assert_eq!(definition_at(22, 16), None);
+    assert_eq!(definition_at(22, 24), conftest_db);
+
+    let unknown: Vec<(String, usize)> = db
+        .detect_unknown_fixtures_in_file(&test_path)
+        .into_iter()
+        .map(|usage| (usage.name, usage.line))
+        .collect();
+    assert_eq!(
+        unknown,
+        vec![("client".to_string(), 20), ("client".to_string(), 23)]
+    );
+
+    let names_at = |line: usize| -> Vec<(String, Option<String>)> {
+        db.get_available_fixtures_at_line(&test_path, line)
+            .into_iter()
+            .filter(|def| def.name == "client" || def.name == "db")
+            .map(|def| (def.name, def.class_name))
+            .collect()
+    };
+    assert_eq!(
+        names_at(16),
+        vec![
+            ("client".to_string(), Some("TestBase".to_string())),
+            ("db".to_string(), Some("TestBase".to_string())),
+        ]
+    );
+    assert_eq!(names_at(23), vec![("db".to_string(), None)]);
+}
+
+#[test]
+#[timeout(30000)]
+fn test_class_fixture_requesting_its_own_name_gets_module_fixture() {
+    let db = FixtureDatabase::new();
+    db.analyze_file(
+        PathBuf::from("/tmp/class_override/conftest.py"),
+        r#"import pytest
+
+@pytest.fixture
+def db():
+    return "module"
+"#,
+    );
+    let test_path = PathBuf::from("/tmp/class_override/test_override.py");
+    db.analyze_file(
+        test_path.clone(),
+        r#"import pytest
+
+class TestOverride:
+    @pytest.fixture
+    def db(self, db):
+        return db + "!"
+
+    def test_it(self, db):
+        pass
+"#,
+    );
+
+    // `db` parameter of the class fixture (0-based line 4) is the conftest one
+    let overridden = db.find_fixture_definition(&test_path, 4, 17).unwrap();
+    assert!(overridden.file_path.ends_with("conftest.py"));
+    assert_eq!(overridden.class_name, None);
+
+    // The test sees the class override
+    let resolved = db.find_fixture_definition(&test_path, 7, 23).unwrap();
+    assert_eq!(resolved.class_name.as_deref(), Some("TestOverride"));
+    assert_eq!(resolved.line, 5);
+}
+
+// ============================================================================
+// Fixture closure of a test
+// ============================================================================
+
+#[test]
+#[timeout(30000)]
+fn test_fixture_closure_in_setup_order() {
+    let db = FixtureDatabase::new();
+    db.analyze_file(
+        PathBuf::from("/tmp/test_closure/conftest.py"),
+        r#"import pytest
+
+@pytest.fixture(scope="session")
+def db():
+    return "base"
+
+@pytest.fixture(autouse=True)
+def reset_env():
+    yield
+
+@pytest.fixture
+def audit():
+    return []
+"#,
+    );
+    db.analyze_file(
+        PathBuf::from("/tmp/test_closure/sub/conftest.py"),
+        r#"import pytest
+
+@pytest.fixture
+def db(db):
+    return db
+
+@pytest.fixture
+def client(db, tmp_path):
+    return db
+"#,
+    );
+    let test_path = PathBuf::from("/tmp/test_closure/sub/test_client.py");
+    db.analyze_file(
+        test_path.clone(),
+        r#"import pytest
+
+class TestClient:
+    @pytest.mark.usefixtures("audit")
+    @pytest.mark.parametrize("flag", [True, False])
+    def test_get(self, client, flag):
+        pass
+"#,
+    );
+
+    let closure = db
+        .compute_fixture_closure(&test_path, "TestClient::test_get")
+        .expect("closure");
+    assert_eq!(closure.line, 6);
+    let entries: Vec<(String, Option<String>, &str, Option<String>)> = closure
+        .entries
+        .iter()
+        .map(|entry| {
+            (
+                entry.name.clone(),
+                entry
+                    .definition
+                    .as_ref()
+                    .map(|def| format!("{}:{}", def.file_path.display(), def.line)),
+                entry.source.as_str(),
+                entry
+                    .overrides
+                    .as_ref()
+                    .map(|def| format!("{}:{}", def.file_path.display(), def.line)),
+            )
+        })
+        .collect();
+    let at = |location: &str| Some(location.to_string());
+    assert_eq!(
+        entries,
+        vec![
+            // Session scope first, even though it is only a dependency
+            (
+                "db".into(),
+                at("/tmp/test_closure/conftest.py:4"),
+                "dependency",
+                None
+            ),
+            (
+                "reset_env".into(),
+                at("/tmp/test_closure/conftest.py:8"),
+                "autouse",
+                None
+            ),
+            (
+                "audit".into(),
+                at("/tmp/test_closure/conftest.py:12"),
+                "usefixtures",
+                None
+            ),
+            (
+                "db".into(),
+                at("/tmp/test_closure/sub/conftest.py:4"),
+                "dependency",
+                at("/tmp/test_closure/conftest.py:4")
+            ),
+            ("tmp_path".into(), None, "dependency", None),
+            (
+                "client".into(),
+                at("/tmp/test_closure/sub/conftest.py:8"),
+                "parameter",
+                None
+            ),
+        ]
+    );
+    assert_eq!(closure.entries[4].requested_by.as_deref(), Some("client"));
+
+    // The same closure is found from any line of the test
+    assert_eq!(
+        db.compute_fixture_closure_at_line(&test_path, 7),
+        Some(closure)
+    );
+    assert!(db.compute_fixture_closure_at_line(&test_path, 1).is_none());
+    assert!(db.compute_fixture_closure(&test_path, "test_get").is_none());
+}
+
+#[test]
+#[timeout(30000)]
+fn test_fixture_closure_resolves_dependencies_from_the_test() {
+    let db = FixtureDatabase::new();
+    db.analyze_file(
+        PathBuf::from("/tmp/test_closure_nested/conftest.py"),
+        r#"import pytest
+
+@pytest.fixture
+def username():
+    return "root"
+
+@pytest.fixture
+def other_username(username):
+    return "other-" + username
+"#,
+    );
+    db.analyze_file(
+        PathBuf::from("/tmp/test_closure_nested/sub/conftest.py"),
+        r#"import pytest
+
+@pytest.fixture
+def username():
+    return "sub"
+"#,
+    );
+    let test_path = PathBuf::from("/tmp/test_closure_nested/sub/test_x.py");
+    db.analyze_file(
+        test_path.clone(),
+        "def test_it(other_username):\n    pass\n",
+    );
+
+    let closure = db
+        .compute_fixture_closure(&test_path, "test_it")
+        .expect("closure");
+    let username = closure
+        .entries
+        .iter()
+        .find(|entry| entry.name == "username")
+        .expect("username in closure");
+    // The override next to the test wins over the one next to `other_username`
+    assert_eq!(
+        username.definition.as_ref().map(|def| &def.file_path),
+        Some(&PathBuf::from("/tmp/test_closure_nested/sub/conftest.py"))
+    );
+    assert_eq!(username.requested_by.as_deref(), Some("other_username"));
+}
+
+// ============================================================================
+// request.getfixturevalue
+// ============================================================================
+
+#[test]
+#[timeout(30000)]
+fn test_getfixturevalue_is_a_fixture_usage() {
+    let db = FixtureDatabase::new();
+
+    let conftest = PathBuf::from("/tmp/test_getfixturevalue/conftest.py");
+    db.analyze_file(
+        conftest.clone(),
+        r#"import pytest
+
+@pytest.fixture
+def db():
+    return 1
+
+@pytest.fixture
+def cache():
+    return 2
+
+def pytest_runtest_setup(item):
+    item._request.getfixturevalue("db")
+"#,
+    );
+    let test_path = PathBuf::from("/tmp/test_getfixturevalue/test_dynamic.py");
+    let content = r#"import pytest
+
+@pytest.fixture(params=["db", "cache"])
+def backend(request):
+    return request.getfixturevalue(request.param)
+
+def test_direct(request):
+    assert request.getfixturevalue("db")
+
+def test_alias(request):
+    req = request
+    value = req.getfixturevalue("cache")
+
+def load(request):
+    return request.getfixturevalue("dbb")
+
+def test_other(client):
+    client.getfixturevalue("db")
+"#;
+    db.analyze_file(test_path.clone(), content);
+
+    let usages = db.usages.get(&test_path).unwrap();
+    let dynamic: Vec<_> = usages
+        .iter()
+        .filter(|u| u.is_dynamic)
+        .map(|u| (u.name.as_str(), u.line, u.start_char, u.end_char))
+        .collect();
+    // Non-literal names and calls on other objects are not usages
+    assert_eq!(
+        dynamic,
+        vec![
+            ("db", 8, 36, 38),
+            ("cache", 12, 33, 38),
+            ("dbb", 15, 36, 39)
+        ]
+    );
+
+    // Hooks look fixtures up through the item's request
+    let hook_usages: Vec<_> = db
+        .usages
+        .get(&conftest)
+        .unwrap()
+        .iter()
+        .filter(|u| u.is_dynamic)
+        .map(|u| (u.name.clone(), u.line, u.start_char, u.end_char))
+        .collect();
+    assert_eq!(hook_usages, vec![("db".to_string(), 12, 35, 37)]);
+
+    // Go to definition and references
+    let def = db.find_fixture_definition(&test_path, 7, 37).unwrap();
+    assert_eq!(
+        (def.name.as_str(), def.file_path.as_path()),
+        ("db", conftest.as_path())
+    );
+    let references = db.find_references_for_definition(&def);
+    assert!(references
+        .iter()
+        .any(|u| u.file_path == test_path && u.line == 8 && u.is_dynamic));
+    assert!(references
+        .iter()
+        .any(|u| u.file_path == conftest && u.line == 12 && u.is_dynamic));
+
+    // The string is renamed with the fixture
+    let spans = rename_spans(
+        &db,
+        "cache",
+        "/tmp/test_getfixturevalue/conftest.py",
+        "store",
+    );
+    assert!(spans.contains(&("test_dynamic.py".to_string(), 12, 33, 38)));
+
+    // Unknown names are reported like unknown parameters
+    let unknown: Vec<_> = db
+        .detect_unknown_fixtures_in_file(&test_path)
+        .into_iter()
+        .map(|u| u.name)
+        .collect();
+    // (`client` is an unknown parameter)
+    assert_eq!(unknown, vec!["dbb", "client"]);
+}
+
+// ============================================================================
+// fixture_paths
+// ============================================================================
+
+#[test]
+#[timeout(30000)]
+fn test_fixture_paths_are_visible_between_conftest_and_third_party() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path().canonicalize().unwrap();
+    let fixture_dir = root.join("testing/fixtures");
+    std::fs::create_dir_all(&fixture_dir).unwrap();
+    std::fs::write(
+        fixture_dir.join("shared.py"),
+        "import pytest\n\n@pytest.fixture\ndef database():\n    return 1\n\n@pytest.fixture\ndef client():\n    return 2\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(root.join("tests")).unwrap();
+    std::fs::write(
+        root.join("tests/conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef client():\n    return 3\n",
+    )
+    .unwrap();
+    let test_path = root.join("tests/test_app.py");
+    std::fs::write(&test_path, "def test_app(database, client):\n    pass\n").unwrap();
+
+    let db = FixtureDatabase::new();
+    db.set_root_fixture_paths(&root, vec![fixture_dir.clone()]);
+    db.scan_workspace(&root);
+
+    // A third-party definition loses against the fixture path
+    db.analyze_file(
+        PathBuf::from("/tmp/venv/lib/python3.12/site-packages/plugin/fixtures.py"),
+        "import pytest\n\n@pytest.fixture\ndef database():\n    return 4\n",
+    );
+
+    let database = db.find_fixture_definition(&test_path, 0, 14).unwrap();
+    assert_eq!(database.file_path, fixture_dir.join("shared.py"));
+    assert_eq!(database.fixture_path.as_ref(), Some(&fixture_dir));
+    assert!(database.is_plugin && !database.is_third_party);
+
+    // conftest.py wins over the fixture path
+    let client = db.find_fixture_definition(&test_path, 0, 24).unwrap();
+    assert_eq!(client.file_path, root.join("tests/conftest.py"));
+    assert!(client.fixture_path.is_none());
+
+    assert!(db.detect_unknown_fixtures_in_file(&test_path).is_empty());
+}
+
+// ============================================================================
+// Fixture overrides
+// ============================================================================
+
+#[test]
+#[timeout(30000)]
+fn test_detect_fixture_overrides_in_file() {
+    let db = FixtureDatabase::new();
+
+    let parent = PathBuf::from("/tmp/overrides/conftest.py");
+    db.analyze_file(
+        parent.clone(),
+        "import pytest\n\n@pytest.fixture\ndef database():\n    return 1\n\n@pytest.fixture\ndef client():\n    return 2\n",
+    );
+    db.analyze_file(
+        PathBuf::from("/tmp/venv/lib/python3.12/site-packages/_pytest/tmpdir.py"),
+        "import pytest\n\n@pytest.fixture\ndef tmp_path():\n    pass\n",
+    );
+
+    let test_path = PathBuf::from("/tmp/overrides/sub/test_app.py");
+    db.analyze_file(
+        test_path.clone(),
+        r#"import pytest
+
+@pytest.fixture
+def database(database):
+    return database + 1
+
+@pytest.fixture
+def helper():
+    return 1
+
+@pytest.fixture
+def helper():
+    return 2
+
+class TestApp:
+    @pytest.fixture
+    def helper(self):
+        return 3
+
+@pytest.fixture
+def tmp_path():
+    pass
+"#,
+    );
+
+    let overrides = db.detect_fixture_overrides_in_file(&test_path);
+    let summary: Vec<(String, usize, usize)> = overrides
+        .iter()
+        .map(|o| (o.fixture.name.clone(), o.fixture.line, o.overridden.line))
+        .collect();
+    // The module-level `helper` redefinition is not an override; the class
+    // fixture overrides the module-level one
+    assert_eq!(
+        summary,
+        vec![
+            ("database".to_string(), 4, 4),
+            ("helper".to_string(), 17, 12),
+            ("tmp_path".to_string(), 21, 4),
+        ]
+    );
+    assert_eq!(overrides[0].overridden.file_path, parent);
+    assert!(overrides[2].overridden.is_third_party);
+
+    assert!(db.detect_fixture_overrides_in_file(&parent).is_empty());
+}
+
+#[test]
+#[timeout(30000)]
+fn test_class_fixture_base_resolved_through_imports() {
+    let db = FixtureDatabase::new();
+    for (file, value) in [("base.py", "base"), ("other.py", "other")] {
+        db.analyze_file(
+            PathBuf::from(format!("/tmp/class_imports/{}", file)),
+            &format!(
+                r#"import pytest
+
+class TestBase:
+    @pytest.fixture
+    def client(self):
+        return "{}"
+"#,
+                value
+            ),
+        );
+    }
+    let test_path = PathBuf::from("/tmp/class_imports/test_imports.py");
+    db.analyze_file(
+        test_path.clone(),
+        r#"import other
+from base import TestBase as Imported
+
+class TestAliased(Imported):
+    def test_aliased(self, client):
+        pass
+
+class TestDotted(other.TestBase):
+    def test_dotted(self, client):
+        pass
+
+class TestUnresolved(TestBase):
+    def test_unresolved(self, client):
+        pass
+"#,
+    );
+
+    let definition_file = |line: u32, character: u32| {
+        db.find_fixture_definition(&test_path, line, character)
+            .map(|def| def.file_path.file_name().unwrap().to_owned())
+    };
+    assert_eq!(definition_file(4, 28), Some("base.py".into()));
+    assert_eq!(definition_file(8, 27), Some("other.py".into()));
+    // `TestBase` is not imported here, so no same-named class is guessed
+    assert_eq!(definition_file(12, 31), None);
+}
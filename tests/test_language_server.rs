@@ -10,7 +10,9 @@ use std::sync::Arc;
 
 use ntest::timeout;
 use pytest_language_server::{Backend, FixtureDatabase};
-use tower_lsp_server::ls_types::request::GotoImplementationParams;
+use tower_lsp_server::ls_types::request::{
+    GotoDeclarationParams, GotoDeclarationResponse, GotoImplementationParams,
+};
 use tower_lsp_server::ls_types::*;
 use tower_lsp_server::{LanguageServer, LspService};
 
@@ -30,6 +32,9 @@ fn make_backend_with_db(db: Arc<FixtureDatabase>) -> Backend {
             scan_task: b.scan_task.clone(),
             uri_cache: b.uri_cache.clone(),
             config: b.config.clone(),
+            workspace_roots: b.workspace_roots.clone(),
+            client_settings: b.client_settings.clone(),
+            pull_diagnostics: b.pull_diagnostics.clone(),
         });
         b
     });
@@ -229,8 +234,11 @@ async fn test_initialize_capabilities_all_providers_present() {
     assert!(caps.workspace_symbol_provider.is_some());
     assert!(caps.code_lens_provider.is_some());
     assert!(caps.inlay_hint_provider.is_some());
+    assert!(caps.semantic_tokens_provider.is_some());
+    assert!(caps.signature_help_provider.is_some());
     assert!(caps.implementation_provider.is_some());
     assert!(caps.call_hierarchy_provider.is_some());
+    assert!(caps.rename_provider.is_some());
 }
 
 #[tokio::test]
@@ -290,6 +298,269 @@ async fn test_initialize_background_scan_handle_stored() {
     assert!(handle.is_some(), "scan task handle should be stored");
 }
 
+// ── workspace folders ─────────────────────────────────────────────────────
+
+/// Create a workspace folder with a conftest.py defining `fixture_name`.
+fn make_folder(fixture_name: &str, pyproject: Option<&str>) -> tempfile::TempDir {
+    let dir = tempfile::tempdir().expect("tempdir");
+    std::fs::write(
+        dir.path().join("conftest.py"),
+        format!(
+            "import pytest\n\n@pytest.fixture\ndef {}():\n    return 1\n",
+            fixture_name
+        ),
+    )
+    .unwrap();
+    if let Some(pyproject) = pyproject {
+        std::fs::write(dir.path().join("pyproject.toml"), pyproject).unwrap();
+    }
+    dir
+}
+
+fn folder(dir: &tempfile::TempDir) -> WorkspaceFolder {
+    WorkspaceFolder {
+        uri: Uri::from_file_path(dir.path()).expect("folder URI"),
+        name: "ws".to_string(),
+    }
+}
+
+/// Wait for the background scan started by initialize or a folder change.
+async fn wait_for_scan(backend: &Backend) {
+    if let Some(handle) = backend.scan_task.lock().await.take() {
+        handle.await.expect("scan task");
+    }
+}
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_initialize_scans_every_workspace_folder() {
+    let first = make_folder("first_fixture", None);
+    let second = make_folder(
+        "second_fixture",
+        Some("[tool.pytest-language-server]\ndisabled_diagnostics = [\"unknown-fixture\"]\n"),
+    );
+    let backend = make_backend();
+
+    let params = InitializeParams {
+        workspace_folders: Some(vec![folder(&first), folder(&second)]),
+        ..Default::default()
+    };
+    let caps = backend.initialize(params).await.unwrap().capabilities;
+    let folders_caps = caps
+        .workspace
+        .and_then(|workspace| workspace.workspace_folders)
+        .expect("workspace folders capability");
+    assert_eq!(folders_caps.supported, Some(true));
+    assert_eq!(folders_caps.change_notifications, Some(OneOf::Left(true)));
+
+    wait_for_scan(&backend).await;
+    assert!(backend.fixture_db.definitions.contains_key("first_fixture"));
+    assert!(backend
+        .fixture_db
+        .definitions
+        .contains_key("second_fixture"));
+
+    // The first folder is the primary root; each folder keeps its own config
+    let first_root = first.path().canonicalize().unwrap();
+    let second_root = second.path().canonicalize().unwrap();
+    assert_eq!(backend.workspace_roots.read().await.len(), 2);
+    assert_eq!(
+        backend.workspace_root.read().await.as_ref(),
+        Some(&first_root)
+    );
+    assert!(!backend
+        .config_for(&first_root.join("test_a.py"))
+        .await
+        .is_diagnostic_disabled("unknown-fixture"));
+    assert!(backend
+        .config_for(&second_root.join("test_b.py"))
+        .await
+        .is_diagnostic_disabled("unknown-fixture"));
+    assert_eq!(
+        backend
+            .workspace_root_for(&second_root.join("test_b.py"))
+            .await,
+        Some(second_root)
+    );
+}
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_did_change_workspace_folders_adds_and_removes_roots() {
+    let first = make_folder("first_fixture", None);
+    let second = make_folder("second_fixture", None);
+    let backend = make_backend();
+
+    let params = InitializeParams {
+        workspace_folders: Some(vec![folder(&first)]),
+        ..Default::default()
+    };
+    backend.initialize(params).await.unwrap();
+    wait_for_scan(&backend).await;
+    assert!(!backend
+        .fixture_db
+        .definitions
+        .contains_key("second_fixture"));
+
+    backend
+        .did_change_workspace_folders(DidChangeWorkspaceFoldersParams {
+            event: WorkspaceFoldersChangeEvent {
+                added: vec![folder(&second)],
+                removed: vec![],
+            },
+        })
+        .await;
+    wait_for_scan(&backend).await;
+    assert!(backend
+        .fixture_db
+        .definitions
+        .contains_key("second_fixture"));
+
+    backend
+        .did_change_workspace_folders(DidChangeWorkspaceFoldersParams {
+            event: WorkspaceFoldersChangeEvent {
+                added: vec![],
+                removed: vec![folder(&first)],
+            },
+        })
+        .await;
+    assert!(!backend.fixture_db.definitions.contains_key("first_fixture"));
+    assert!(backend
+        .fixture_db
+        .definitions
+        .contains_key("second_fixture"));
+
+    // The remaining folder becomes the primary root
+    let second_root = second.path().canonicalize().unwrap();
+    assert_eq!(backend.workspace_roots.read().await.len(), 1);
+    assert_eq!(
+        backend.workspace_root.read().await.as_ref(),
+        Some(&second_root)
+    );
+}
+
+// ── configuration reload ──────────────────────────────────────────────────
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_did_change_configuration_applies_client_settings() {
+    let dir = make_folder("first_fixture", None);
+    let backend = make_backend();
+    backend
+        .initialize(InitializeParams {
+            workspace_folders: Some(vec![folder(&dir)]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    wait_for_scan(&backend).await;
+
+    let test_path = dir.path().canonicalize().unwrap().join("test_a.py");
+    assert!(!backend
+        .config_for(&test_path)
+        .await
+        .is_diagnostic_disabled("unknown-fixture"));
+
+    backend
+        .did_change_configuration(DidChangeConfigurationParams {
+            settings: serde_json::json!({
+                "pytest-language-server": { "disabled_diagnostics": ["unknown-fixture"] }
+            }),
+        })
+        .await;
+
+    assert!(backend
+        .config_for(&test_path)
+        .await
+        .is_diagnostic_disabled("unknown-fixture"));
+    assert!(backend
+        .config
+        .read()
+        .await
+        .is_diagnostic_disabled("unknown-fixture"));
+}
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_pyproject_change_rescans_with_new_excludes() {
+    let dir = make_folder("first_fixture", None);
+    let root = dir.path().canonicalize().unwrap();
+    std::fs::create_dir(root.join("vendored")).unwrap();
+    std::fs::write(
+        root.join("vendored").join("conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef vendored_fixture():\n    return 1\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("test_open.py"),
+        "def test_a(first_fixture):\n    pass\n",
+    )
+    .unwrap();
+
+    let backend = make_backend();
+    backend
+        .initialize(InitializeParams {
+            workspace_folders: Some(vec![folder(&dir)]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    wait_for_scan(&backend).await;
+    assert!(backend
+        .fixture_db
+        .definitions
+        .contains_key("vendored_fixture"));
+
+    // An open document with unsaved edits
+    let open_path = root.join("test_open.py");
+    let open_uri = Uri::from_file_path(&open_path).unwrap();
+    let unsaved = "def test_a(first_fixture, vendored_fixture):\n    pass\n";
+    backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: open_uri,
+                language_id: "python".to_string(),
+                version: 3,
+                text: unsaved.to_string(),
+            },
+        })
+        .await;
+
+    let pyproject = root.join("pyproject.toml");
+    std::fs::write(
+        &pyproject,
+        "[tool.pytest-language-server]\nexclude = [\"vendored/**\"]\n",
+    )
+    .unwrap();
+    backend
+        .did_change_watched_files(DidChangeWatchedFilesParams {
+            changes: vec![FileEvent {
+                uri: Uri::from_file_path(&pyproject).unwrap(),
+                typ: FileChangeType::CREATED,
+            }],
+        })
+        .await;
+    wait_for_scan(&backend).await;
+
+    assert!(!backend
+        .fixture_db
+        .definitions
+        .contains_key("vendored_fixture"));
+    assert!(backend.fixture_db.definitions.contains_key("first_fixture"));
+    assert_eq!(backend.config.read().await.exclude.len(), 1);
+
+    // The open document keeps the editor's content rather than the disk's
+    assert_eq!(
+        backend
+            .fixture_db
+            .file_cache
+            .get(&open_path)
+            .map(|content| content.to_string()),
+        Some(unsaved.to_string())
+    );
+    assert_eq!(backend.fixture_db.usages.get(&open_path).unwrap().len(), 2);
+}
+
 // ── initialized ───────────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -475,6 +746,75 @@ async fn test_did_change_triggers_inlay_hint_refresh() {
     // Mock client absorbs inlay_hint_refresh error silently – just must not panic
 }
 
+#[tokio::test]
+#[timeout(30000)]
+async fn test_did_change_applies_incremental_range_edits() {
+    let db = Arc::new(FixtureDatabase::new());
+    let backend = make_backend_with_db(Arc::clone(&db));
+
+    let file_uri = turi("test_ls_change_incremental", "conftest.py");
+    let file_path = tfile("test_ls_change_incremental", "conftest.py");
+
+    backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: file_uri.clone(),
+                language_id: "python".to_string(),
+                version: 1,
+                text: "import pytest\n\n# ü😀\n@pytest.fixture\ndef old_fixture():\n    return 1\n"
+                    .to_string(),
+            },
+        })
+        .await;
+
+    // Rename the fixture via a range edit, then edit the body in the same batch
+    backend
+        .did_change(DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier {
+                uri: file_uri.clone(),
+                version: 2,
+            },
+            content_changes: vec![
+                TextDocumentContentChangeEvent {
+                    range: Some(rng(4, 4, 4, 7)),
+                    range_length: None,
+                    text: "new".to_string(),
+                },
+                TextDocumentContentChangeEvent {
+                    range: Some(rng(5, 11, 5, 12)),
+                    range_length: None,
+                    text: "2".to_string(),
+                },
+            ],
+        })
+        .await;
+
+    assert!(db.definitions.contains_key("new_fixture"));
+    assert!(!db.definitions.contains_key("old_fixture"));
+    assert_eq!(
+        db.file_cache.get(&file_path).unwrap().as_str(),
+        "import pytest\n\n# ü😀\n@pytest.fixture\ndef new_fixture():\n    return 2\n"
+    );
+
+    // A change carrying an older version is ignored
+    backend
+        .did_change(DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier {
+                uri: file_uri,
+                version: 2,
+            },
+            content_changes: vec![TextDocumentContentChangeEvent {
+                range: Some(rng(4, 4, 4, 7)),
+                range_length: None,
+                text: "stale".to_string(),
+            }],
+        })
+        .await;
+
+    assert!(db.definitions.contains_key("new_fixture"));
+    assert!(!db.definitions.contains_key("stale_fixture"));
+}
+
 // ── did_change_watched_files ──────────────────────────────────────────────
 
 #[tokio::test]
@@ -618,6 +958,121 @@ async fn test_did_change_watched_files_multiple_events() {
     backend.did_change_watched_files(params).await;
 }
 
+#[tokio::test]
+#[timeout(30000)]
+async fn test_did_change_watched_files_syncs_python_files_on_disk() {
+    let dir = make_folder("old_fixture", None);
+    let backend = make_backend();
+    backend
+        .initialize(InitializeParams {
+            workspace_folders: Some(vec![folder(&dir)]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    wait_for_scan(&backend).await;
+    assert!(backend.fixture_db.definitions.contains_key("old_fixture"));
+
+    // conftest.py rewritten outside the editor (e.g. `git checkout`)
+    let conftest = dir.path().join("conftest.py");
+    std::fs::write(
+        &conftest,
+        "import pytest\n\n@pytest.fixture\ndef new_fixture():\n    return 2\n",
+    )
+    .unwrap();
+    // A new test module appears
+    let test_file = dir.path().join("test_new.py");
+    std::fs::write(&test_file, "def test_it(new_fixture):\n    pass\n").unwrap();
+    // Files outside pytest's collection are ignored
+    let helper = dir.path().join("helper.py");
+    std::fs::write(&helper, "x = 1\n").unwrap();
+
+    backend
+        .did_change_watched_files(DidChangeWatchedFilesParams {
+            changes: vec![
+                FileEvent {
+                    uri: Uri::from_file_path(&conftest).unwrap(),
+                    typ: FileChangeType::CHANGED,
+                },
+                FileEvent {
+                    uri: Uri::from_file_path(&test_file).unwrap(),
+                    typ: FileChangeType::CREATED,
+                },
+                FileEvent {
+                    uri: Uri::from_file_path(&helper).unwrap(),
+                    typ: FileChangeType::CREATED,
+                },
+            ],
+        })
+        .await;
+    assert!(!backend.fixture_db.definitions.contains_key("old_fixture"));
+    assert!(backend.fixture_db.definitions.contains_key("new_fixture"));
+    let test_path = test_file.canonicalize().unwrap();
+    assert!(backend.fixture_db.usages.contains_key(&test_path));
+    assert!(!backend.fixture_db.has_file(&helper.canonicalize().unwrap()));
+
+    // conftest.py deleted
+    let conftest_path = conftest.canonicalize().unwrap();
+    std::fs::remove_file(&conftest).unwrap();
+    backend
+        .did_change_watched_files(DidChangeWatchedFilesParams {
+            changes: vec![FileEvent {
+                uri: Uri::from_file_path(&conftest).unwrap(),
+                typ: FileChangeType::DELETED,
+            }],
+        })
+        .await;
+    assert!(!backend.fixture_db.definitions.contains_key("new_fixture"));
+    assert!(!backend
+        .fixture_db
+        .file_definitions
+        .contains_key(&conftest_path));
+}
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_did_change_watched_files_ignores_open_documents() {
+    let dir = make_folder("disk_fixture", None);
+    let backend = make_backend();
+    backend
+        .initialize(InitializeParams {
+            workspace_folders: Some(vec![folder(&dir)]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    wait_for_scan(&backend).await;
+
+    let conftest = dir.path().join("conftest.py");
+    let uri = Uri::from_file_path(&conftest).unwrap();
+    backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "python".to_string(),
+                version: 1,
+                text: "import pytest\n\n@pytest.fixture\ndef editor_fixture():\n    return 3\n"
+                    .to_string(),
+            },
+        })
+        .await;
+
+    // The editor's unsaved content wins over the disk's
+    backend
+        .did_change_watched_files(DidChangeWatchedFilesParams {
+            changes: vec![FileEvent {
+                uri,
+                typ: FileChangeType::CHANGED,
+            }],
+        })
+        .await;
+    assert!(backend
+        .fixture_db
+        .definitions
+        .contains_key("editor_fixture"));
+    assert!(!backend.fixture_db.definitions.contains_key("disk_fixture"));
+}
+
 // ── did_close ─────────────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -671,43 +1126,226 @@ async fn test_did_close_unknown_file_does_not_panic() {
         .await;
 }
 
-// ── goto_definition ───────────────────────────────────────────────────────
+// ── diagnostic / workspace_diagnostic (pull) ──────────────────────────────
+
+fn document_diagnostic_params(
+    uri: Uri,
+    previous_result_id: Option<String>,
+) -> DocumentDiagnosticParams {
+    DocumentDiagnosticParams {
+        text_document: TextDocumentIdentifier { uri },
+        identifier: None,
+        previous_result_id,
+        work_done_progress_params: wdp(),
+        partial_result_params: prp(),
+    }
+}
 
 #[tokio::test]
 #[timeout(30000)]
-async fn test_goto_definition_returns_ok_on_empty_db() {
+async fn test_initialize_advertises_pull_diagnostics() {
     let backend = make_backend();
-    let result = backend
-        .goto_definition(GotoDefinitionParams {
-            text_document_position_params: tdp(turi("test_ls_gotodef", "test_file.py"), 0, 0),
-            work_done_progress_params: wdp(),
-            partial_result_params: prp(),
-        })
-        .await;
-    assert!(result.is_ok());
-    assert!(result.unwrap().is_none());
+    let params = InitializeParams {
+        capabilities: ClientCapabilities {
+            text_document: Some(TextDocumentClientCapabilities {
+                diagnostic: Some(DiagnosticClientCapabilities::default()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let caps = backend.initialize(params).await.unwrap().capabilities;
+    match caps.diagnostic_provider {
+        Some(DiagnosticServerCapabilities::Options(options)) => {
+            assert!(options.workspace_diagnostics);
+            assert!(options.inter_file_dependencies);
+        }
+        other => panic!("expected diagnostic options, got {:?}", other),
+    }
+    assert!(backend
+        .pull_diagnostics
+        .load(std::sync::atomic::Ordering::Relaxed));
 }
 
 #[tokio::test]
 #[timeout(30000)]
-async fn test_goto_definition_resolves_fixture() {
+async fn test_document_diagnostic_reports_unchanged_for_same_result_id() {
     let db = Arc::new(FixtureDatabase::new());
     let backend = make_backend_with_db(Arc::clone(&db));
 
-    let conftest_path = tfile("test_ls_gotodef2", "conftest.py");
-    db.analyze_file(
-        conftest_path.clone(),
-        "import pytest\n\n@pytest.fixture\ndef my_fixture():\n    return 1\n",
+    let file_uri = turi("test_ls_pull_diag", "conftest.py");
+    open_file(
+        &backend,
+        file_uri.clone(),
+        "import pytest\n\n@pytest.fixture\ndef func_fix():\n    return 1\n\n@pytest.fixture(scope=\"session\")\ndef sess_fix(func_fix):\n    return func_fix\n",
+    )
+    .await;
+
+    let report = backend
+        .diagnostic(document_diagnostic_params(file_uri.clone(), None))
+        .await
+        .unwrap();
+    let DocumentDiagnosticReportResult::Report(DocumentDiagnosticReport::Full(full)) = report
+    else {
+        panic!("expected a full report, got {:?}", report);
+    };
+    let full = full.full_document_diagnostic_report;
+    assert_eq!(full.items.len(), 1);
+    assert_eq!(
+        full.items[0].code,
+        Some(NumberOrString::String("scope-mismatch".to_string()))
     );
+    let result_id = full.result_id.expect("result id");
+
+    // Nothing changed: the client can keep its previous diagnostics
+    let report = backend
+        .diagnostic(document_diagnostic_params(
+            file_uri.clone(),
+            Some(result_id.clone()),
+        ))
+        .await
+        .unwrap();
+    assert!(matches!(
+        report,
+        DocumentDiagnosticReportResult::Report(DocumentDiagnosticReport::Unchanged(ref r))
+            if r.unchanged_document_diagnostic_report.result_id == result_id
+    ));
 
-    let test_path = tfile("test_ls_gotodef2", "test_example.py");
+    // Editing the file produces a new result ID and a full report
+    backend
+        .did_change(DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier {
+                uri: file_uri.clone(),
+                version: 2,
+            },
+            content_changes: vec![TextDocumentContentChangeEvent {
+                range: Some(rng(6, 23, 6, 30)),
+                range_length: None,
+                text: "function".to_string(),
+            }],
+        })
+        .await;
+    let report = backend
+        .diagnostic(document_diagnostic_params(file_uri, Some(result_id)))
+        .await
+        .unwrap();
+    let DocumentDiagnosticReportResult::Report(DocumentDiagnosticReport::Full(full)) = report
+    else {
+        panic!("expected a full report after the edit, got {:?}", report);
+    };
+    assert!(full.full_document_diagnostic_report.items.is_empty());
+}
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_workspace_diagnostic_covers_files_that_are_not_open() {
+    let db = Arc::new(FixtureDatabase::new());
+    let backend = make_backend_with_db(Arc::clone(&db));
+
+    // Analyzed by the workspace scan, never opened in the editor
+    let test_path = tfile("test_ls_pull_workspace", "test_closed.py");
     db.analyze_file(
         test_path.clone(),
-        "def test_it(my_fixture):\n    assert my_fixture == 1\n",
+        "def test_something(missing_fixture):\n    pass\n",
     );
-    // Pre-register URI cache so path_to_uri works
-    let conftest_uri = turi("test_ls_gotodef2", "conftest.py");
-    backend.uri_cache.insert(conftest_path, conftest_uri);
+
+    let params = WorkspaceDiagnosticParams {
+        identifier: None,
+        previous_result_ids: vec![],
+        work_done_progress_params: wdp(),
+        partial_result_params: prp(),
+    };
+    let WorkspaceDiagnosticReportResult::Report(report) =
+        backend.workspace_diagnostic(params).await.unwrap()
+    else {
+        panic!("expected a workspace report");
+    };
+
+    let test_uri = backend.path_to_uri(&test_path).unwrap();
+    let full = report
+        .items
+        .iter()
+        .find_map(|item| match item {
+            WorkspaceDocumentDiagnosticReport::Full(full) if full.uri == test_uri => Some(full),
+            _ => None,
+        })
+        .expect("report for the closed test file");
+    let items = &full.full_document_diagnostic_report.items;
+    assert_eq!(items.len(), 1);
+    assert_eq!(
+        items[0].code,
+        Some(NumberOrString::String("unknown-fixture".to_string()))
+    );
+
+    // Re-pulling with the previous result IDs reports everything as unchanged
+    let previous_result_ids = report
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            WorkspaceDocumentDiagnosticReport::Full(full) => Some(PreviousResultId {
+                uri: full.uri.clone(),
+                value: full.full_document_diagnostic_report.result_id.clone()?,
+            }),
+            WorkspaceDocumentDiagnosticReport::Unchanged(_) => None,
+        })
+        .collect();
+    let params = WorkspaceDiagnosticParams {
+        identifier: None,
+        previous_result_ids,
+        work_done_progress_params: wdp(),
+        partial_result_params: prp(),
+    };
+    let WorkspaceDiagnosticReportResult::Report(report) =
+        backend.workspace_diagnostic(params).await.unwrap()
+    else {
+        panic!("expected a workspace report");
+    };
+    assert!(!report.items.is_empty());
+    assert!(report
+        .items
+        .iter()
+        .all(|item| matches!(item, WorkspaceDocumentDiagnosticReport::Unchanged(_))));
+}
+
+// ── goto_definition ───────────────────────────────────────────────────────
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_goto_definition_returns_ok_on_empty_db() {
+    let backend = make_backend();
+    let result = backend
+        .goto_definition(GotoDefinitionParams {
+            text_document_position_params: tdp(turi("test_ls_gotodef", "test_file.py"), 0, 0),
+            work_done_progress_params: wdp(),
+            partial_result_params: prp(),
+        })
+        .await;
+    assert!(result.is_ok());
+    assert!(result.unwrap().is_none());
+}
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_goto_definition_resolves_fixture() {
+    let db = Arc::new(FixtureDatabase::new());
+    let backend = make_backend_with_db(Arc::clone(&db));
+
+    let conftest_path = tfile("test_ls_gotodef2", "conftest.py");
+    db.analyze_file(
+        conftest_path.clone(),
+        "import pytest\n\n@pytest.fixture\ndef my_fixture():\n    return 1\n",
+    );
+
+    let test_path = tfile("test_ls_gotodef2", "test_example.py");
+    db.analyze_file(
+        test_path.clone(),
+        "def test_it(my_fixture):\n    assert my_fixture == 1\n",
+    );
+    // Pre-register URI cache so path_to_uri works
+    let conftest_uri = turi("test_ls_gotodef2", "conftest.py");
+    backend.uri_cache.insert(conftest_path, conftest_uri);
 
     let result = backend
         .goto_definition(GotoDefinitionParams {
@@ -723,6 +1361,79 @@ async fn test_goto_definition_resolves_fixture() {
     assert!(result.is_ok());
 }
 
+// ── goto_declaration ──────────────────────────────────────────────────────
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_goto_declaration_jumps_to_overridden_fixture() {
+    let db = Arc::new(FixtureDatabase::new());
+    let backend = make_backend_with_db(Arc::clone(&db));
+
+    let parent_path = tfile("test_ls_decl", "conftest.py");
+    db.analyze_file(
+        parent_path.clone(),
+        "import pytest\n\n@pytest.fixture\ndef database():\n    return 1\n",
+    );
+    db.analyze_file(
+        tfile("test_ls_decl/sub", "conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef database(database):\n    return database + 1\n",
+    );
+    let child_uri = turi("test_ls_decl/sub", "conftest.py");
+
+    let declaration_at = |line, character| GotoDeclarationParams {
+        text_document_position_params: tdp(child_uri.clone(), line, character),
+        work_done_progress_params: wdp(),
+        partial_result_params: prp(),
+    };
+
+    // From the overriding definition to the parent conftest.py
+    let result = backend
+        .goto_declaration(declaration_at(3, 6))
+        .await
+        .unwrap();
+    match result {
+        Some(GotoDeclarationResponse::Scalar(location)) => {
+            assert_eq!(location.uri, turi("test_ls_decl", "conftest.py"));
+            assert_eq!(location.range.start.line, 3);
+        }
+        other => panic!("expected the parent definition, got {:?}", other),
+    }
+
+    // The parent fixture overrides nothing
+    let parent_params = GotoDeclarationParams {
+        text_document_position_params: tdp(turi("test_ls_decl", "conftest.py"), 3, 6),
+        work_done_progress_params: wdp(),
+        partial_result_params: prp(),
+    };
+    assert!(backend
+        .goto_declaration(parent_params)
+        .await
+        .unwrap()
+        .is_none());
+
+    // The override is reported as an informational diagnostic
+    let report = backend
+        .diagnostic(document_diagnostic_params(child_uri.clone(), None))
+        .await
+        .unwrap();
+    let DocumentDiagnosticReportResult::Report(DocumentDiagnosticReport::Full(report)) = report
+    else {
+        panic!("expected a full report, got {:?}", report);
+    };
+    let diagnostics = report.full_document_diagnostic_report.items;
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = &diagnostics[0];
+    assert_eq!(
+        diagnostic.code,
+        Some(NumberOrString::String("fixture-override".to_string()))
+    );
+    assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::INFORMATION));
+    assert_eq!(diagnostic.range.start, pos(3, 4));
+    let related = diagnostic.related_information.as_ref().unwrap();
+    assert_eq!(related[0].location.uri, turi("test_ls_decl", "conftest.py"));
+    assert_eq!(related[0].location.range.start, pos(3, 4));
+}
+
 // ── goto_implementation ───────────────────────────────────────────────────
 
 #[tokio::test]
@@ -740,84 +1451,504 @@ async fn test_goto_implementation_returns_ok_on_empty_db() {
     assert!(result.unwrap().is_none());
 }
 
-// ── hover ─────────────────────────────────────────────────────────────────
-
+// ── hover ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_hover_returns_none_on_empty_file() {
+    let backend = make_backend();
+    let result = backend
+        .hover(HoverParams {
+            text_document_position_params: tdp(turi("test_ls_hover", "test.py"), 0, 0),
+            work_done_progress_params: wdp(),
+        })
+        .await;
+    assert!(result.is_ok());
+    assert!(result.unwrap().is_none(), "no hover for unknown position");
+}
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_hover_returns_content_for_known_fixture() {
+    let db = Arc::new(FixtureDatabase::new());
+    let backend = make_backend_with_db(Arc::clone(&db));
+
+    let conftest_path = tfile("test_ls_hover2", "conftest.py");
+    db.analyze_file(
+        conftest_path.clone(),
+        "import pytest\n\n@pytest.fixture\ndef hover_fixture():\n    \"\"\"Hover docstring.\"\"\"\n    return 1\n",
+    );
+    let conftest_uri = turi("test_ls_hover2", "conftest.py");
+    backend
+        .uri_cache
+        .insert(conftest_path.clone(), conftest_uri.clone());
+
+    let test_path = tfile("test_ls_hover2", "test_example.py");
+    db.analyze_file(test_path, "def test_it(hover_fixture):\n    pass\n");
+
+    let result = backend
+        .hover(HoverParams {
+            text_document_position_params: tdp(turi("test_ls_hover2", "test_example.py"), 0, 12),
+            work_done_progress_params: wdp(),
+        })
+        .await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_hover_shows_parametrize_values_and_ids() {
+    let db = Arc::new(FixtureDatabase::new());
+    let backend = make_backend_with_db(Arc::clone(&db));
+
+    db.analyze_file(
+        tfile("test_ls_hover3", "test_param.py"),
+        "import pytest\n\n@pytest.mark.parametrize(\"n\", [1, pytest.param(2, id=\"two\")])\ndef test_it(n):\n    pass\n",
+    );
+
+    let hover = backend
+        .hover(HoverParams {
+            text_document_position_params: tdp(turi("test_ls_hover3", "test_param.py"), 3, 12),
+            work_done_progress_params: wdp(),
+        })
+        .await
+        .unwrap()
+        .expect("hover for parametrized argument");
+
+    let HoverContents::Markup(markup) = hover.contents else {
+        panic!("expected markdown hover");
+    };
+    assert_eq!(
+        markup.value,
+        "```python\nn  # parametrized\n```\n\n---\n\n**2 values:**\n\n- `1`\n- `2` (id: `two`)"
+    );
+}
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_hover_shows_fixture_params_and_request_param() {
+    let db = Arc::new(FixtureDatabase::new());
+    let backend = make_backend_with_db(Arc::clone(&db));
+
+    db.analyze_file(
+        tfile("test_ls_hover4", "conftest.py"),
+        "import pytest\n\n@pytest.fixture(params=[1, 2], ids=[\"one\", \"two\"])\ndef number(request):\n    return request.param\n",
+    );
+    db.analyze_file(
+        tfile("test_ls_hover4", "test_number.py"),
+        "def test_it(number):\n    pass\n",
+    );
+
+    let hover_value = |hover: Option<Hover>| {
+        let HoverContents::Markup(markup) = hover.expect("hover").contents else {
+            panic!("expected markdown hover");
+        };
+        markup.value
+    };
+
+    // Hover on the fixture parameter lists the params
+    let hover = backend
+        .hover(HoverParams {
+            text_document_position_params: tdp(turi("test_ls_hover4", "test_number.py"), 0, 13),
+            work_done_progress_params: wdp(),
+        })
+        .await
+        .unwrap();
+    assert!(hover_value(hover).contains("**2 params:**\n\n- `1` (id: `one`)\n- `2` (id: `two`)"));
+
+    // Hover on `request.param` inside the fixture shows the same values
+    let hover = backend
+        .hover(HoverParams {
+            text_document_position_params: tdp(turi("test_ls_hover4", "conftest.py"), 4, 20),
+            work_done_progress_params: wdp(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        hover_value(hover),
+        "```python\nrequest.param  # param of fixture number\n```\n\n---\n\n**2 params:**\n\n- `1` (id: `one`)\n- `2` (id: `two`)"
+    );
+}
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_hover_on_test_name_lists_autouse_fixtures() {
+    let db = Arc::new(FixtureDatabase::new());
+    let backend = make_backend_with_db(Arc::clone(&db));
+
+    db.analyze_file(
+        tfile("test_ls_hover5", "conftest.py"),
+        "import pytest\n\n@pytest.fixture(autouse=True)\ndef reset_env():\n    yield\n\n@pytest.fixture(scope=\"session\", autouse=True)\ndef start_server():\n    yield\n",
+    );
+    db.analyze_file(
+        tfile("test_ls_hover5", "test_env.py"),
+        "def test_it():\n    pass\n",
+    );
+
+    let hover = backend
+        .hover(HoverParams {
+            text_document_position_params: tdp(turi("test_ls_hover5", "test_env.py"), 0, 6),
+            work_done_progress_params: wdp(),
+        })
+        .await
+        .unwrap()
+        .expect("hover");
+    let HoverContents::Markup(markup) = hover.contents else {
+        panic!("expected markdown hover");
+    };
+    assert_eq!(
+        markup.value,
+        "```python\ndef test_it(...)  # test\n```\n\n---\n\n**2 autouse fixtures:**\n\n- `start_server` (session) — `conftest.py:8`\n- `reset_env` (function) — `conftest.py:4`"
+    );
+}
+
+// ── references ────────────────────────────────────────────────────────────
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_references_returns_ok_on_empty_db() {
+    let backend = make_backend();
+    let result = backend
+        .references(ReferenceParams {
+            text_document_position: tdp(turi("test_ls_refs", "test.py"), 0, 0),
+            context: ReferenceContext {
+                include_declaration: true,
+            },
+            work_done_progress_params: wdp(),
+            partial_result_params: prp(),
+        })
+        .await;
+    assert!(result.is_ok());
+}
+
+// ── rename ────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_prepare_rename_returns_none_on_empty_db() {
+    let backend = make_backend();
+    let result = backend
+        .prepare_rename(tdp(turi("test_ls_rename", "test.py"), 0, 0))
+        .await;
+    assert!(result.is_ok());
+    assert!(result.unwrap().is_none());
+}
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_prepare_rename_returns_usage_range() {
+    let db = Arc::new(FixtureDatabase::new());
+    let backend = make_backend_with_db(Arc::clone(&db));
+
+    db.analyze_file(
+        tfile("test_ls_rename2", "conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef my_fixture():\n    return 1\n",
+    );
+    db.analyze_file(
+        tfile("test_ls_rename2", "test_example.py"),
+        "def test_it(my_fixture):\n    assert my_fixture == 1\n",
+    );
+
+    let result = backend
+        .prepare_rename(tdp(turi("test_ls_rename2", "test_example.py"), 0, 14))
+        .await
+        .unwrap();
+    match result {
+        Some(PrepareRenameResponse::RangeWithPlaceholder { range, placeholder }) => {
+            assert_eq!(range, rng(0, 12, 0, 22));
+            assert_eq!(placeholder, "my_fixture");
+        }
+        other => panic!("expected RangeWithPlaceholder, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_rename_edits_definition_and_usages() {
+    let db = Arc::new(FixtureDatabase::new());
+    let backend = make_backend_with_db(Arc::clone(&db));
+
+    let conftest_path = tfile("test_ls_rename3", "conftest.py");
+    db.analyze_file(
+        conftest_path.clone(),
+        "import pytest\n\n@pytest.fixture\ndef my_fixture():\n    return 1\n",
+    );
+    let test_path = tfile("test_ls_rename3", "test_example.py");
+    db.analyze_file(
+        test_path.clone(),
+        "def test_it(my_fixture):\n    assert my_fixture == 1\n",
+    );
+    let conftest_uri = turi("test_ls_rename3", "conftest.py");
+    let test_uri = turi("test_ls_rename3", "test_example.py");
+    backend
+        .uri_cache
+        .insert(conftest_path, conftest_uri.clone());
+    backend.uri_cache.insert(test_path, test_uri.clone());
+
+    let edit = backend
+        .rename(RenameParams {
+            text_document_position: tdp(conftest_uri.clone(), 3, 6),
+            new_name: "renamed".to_string(),
+            work_done_progress_params: wdp(),
+        })
+        .await
+        .unwrap()
+        .expect("workspace edit");
+
+    let changes = edit.changes.expect("changes");
+    let conftest_edits = &changes[&conftest_uri];
+    assert_eq!(conftest_edits.len(), 1);
+    assert_eq!(conftest_edits[0].range, rng(3, 4, 3, 14));
+    assert_eq!(conftest_edits[0].new_text, "renamed");
+
+    let mut test_ranges: Vec<Range> = changes[&test_uri].iter().map(|e| e.range).collect();
+    test_ranges.sort_by_key(|r| (r.start.line, r.start.character));
+    assert_eq!(test_ranges, vec![rng(0, 12, 0, 22), rng(1, 11, 1, 21)]);
+}
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_rename_invalid_name_returns_error() {
+    let db = Arc::new(FixtureDatabase::new());
+    let backend = make_backend_with_db(Arc::clone(&db));
+
+    db.analyze_file(
+        tfile("test_ls_rename4", "conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef my_fixture():\n    return 1\n",
+    );
+
+    let result = backend
+        .rename(RenameParams {
+            text_document_position: tdp(turi("test_ls_rename4", "conftest.py"), 3, 6),
+            new_name: "1invalid".to_string(),
+            work_done_progress_params: wdp(),
+        })
+        .await;
+    assert!(result.is_err(), "invalid identifiers must be rejected");
+}
+
+// ── completion ────────────────────────────────────────────────────────────
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_completion_returns_ok() {
+    let backend = make_backend();
+    let result = backend
+        .completion(CompletionParams {
+            text_document_position: tdp(turi("test_ls_compl", "test.py"), 0, 0),
+            work_done_progress_params: wdp(),
+            partial_result_params: prp(),
+            context: None,
+        })
+        .await;
+    assert!(result.is_ok());
+}
+
+async fn completion_labels_at(
+    backend: &Backend,
+    uri: Uri,
+    line: u32,
+    character: u32,
+) -> Vec<String> {
+    let response = backend
+        .completion(CompletionParams {
+            text_document_position: tdp(uri, line, character),
+            work_done_progress_params: wdp(),
+            partial_result_params: prp(),
+            context: None,
+        })
+        .await
+        .unwrap();
+    let Some(CompletionResponse::Array(mut items)) = response else {
+        return vec![];
+    };
+    items.sort_by(|a, b| a.sort_text.cmp(&b.sort_text));
+    items.into_iter().map(|item| item.label).collect()
+}
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_completion_of_fixture_decorator_values() {
+    let backend = make_backend();
+    let uri = turi("test_ls_compl_values", "conftest.py");
+    open_file(
+        &backend,
+        uri.clone(),
+        "import pytest\n\ndef per_worker(fixture_name, config):\n    return \"session\"\n\n@pytest.fixture(scope=\n@pytest.fixture(scope=\"\n@pytest.fixture(autouse=\n",
+    )
+    .await;
+
+    assert_eq!(
+        completion_labels_at(&backend, uri.clone(), 5, 22).await,
+        [
+            "\"function\"",
+            "\"class\"",
+            "\"module\"",
+            "\"package\"",
+            "\"session\"",
+            "per_worker"
+        ]
+    );
+    assert_eq!(
+        completion_labels_at(&backend, uri.clone(), 6, 23).await,
+        ["function", "class", "module", "package", "session"]
+    );
+    assert_eq!(
+        completion_labels_at(&backend, uri, 7, 24).await,
+        ["True", "False"]
+    );
+}
+
 #[tokio::test]
 #[timeout(30000)]
-async fn test_hover_returns_none_on_empty_file() {
-    let backend = make_backend();
-    let result = backend
-        .hover(HoverParams {
-            text_document_position_params: tdp(turi("test_ls_hover", "test.py"), 0, 0),
-            work_done_progress_params: wdp(),
-        })
-        .await;
-    assert!(result.is_ok());
-    assert!(result.unwrap().is_none(), "no hover for unknown position");
+async fn test_completion_of_parametrize_argnames_and_indirect() {
+    let db = Arc::new(FixtureDatabase::new());
+    let backend = make_backend_with_db(Arc::clone(&db));
+    db.analyze_file(
+        tfile("test_ls_compl_param", "conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef user():\n    return 1\n",
+    );
+    let uri = turi("test_ls_compl_param", "test_users.py");
+    open_file(
+        &backend,
+        uri.clone(),
+        "import pytest\n\n@pytest.mark.parametrize(\"user, \n@pytest.mark.parametrize(\"user, role\", [], indirect=\ndef test_access(user, role, client):\n    pass\n",
+    )
+    .await;
+
+    // Parameters of the decorated test not listed yet, in signature order
+    assert_eq!(
+        completion_labels_at(&backend, uri.clone(), 2, 31).await,
+        ["role", "client"]
+    );
+    assert_eq!(
+        completion_labels_at(&backend, uri, 3, 53).await,
+        ["True", "False", "[\"user\"]", "[\"role\"]"]
+    );
 }
 
 #[tokio::test]
 #[timeout(30000)]
-async fn test_hover_returns_content_for_known_fixture() {
+async fn test_completion_inside_getfixturevalue() {
     let db = Arc::new(FixtureDatabase::new());
     let backend = make_backend_with_db(Arc::clone(&db));
-
-    let conftest_path = tfile("test_ls_hover2", "conftest.py");
     db.analyze_file(
-        conftest_path.clone(),
-        "import pytest\n\n@pytest.fixture\ndef hover_fixture():\n    \"\"\"Hover docstring.\"\"\"\n    return 1\n",
+        tfile("test_ls_compl_getfix", "conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef database():\n    return 1\n",
     );
-    let conftest_uri = turi("test_ls_hover2", "conftest.py");
-    backend
-        .uri_cache
-        .insert(conftest_path.clone(), conftest_uri.clone());
+    let uri = turi("test_ls_compl_getfix", "test_db.py");
+    open_file(
+        &backend,
+        uri.clone(),
+        "def test_db(request):\n    request.getfixturevalue(\"da\n",
+    )
+    .await;
 
-    let test_path = tfile("test_ls_hover2", "test_example.py");
-    db.analyze_file(test_path, "def test_it(hover_fixture):\n    pass\n");
+    assert!(completion_labels_at(&backend, uri, 1, 30)
+        .await
+        .contains(&"database".to_string()));
+}
 
-    let result = backend
-        .hover(HoverParams {
-            text_document_position_params: tdp(turi("test_ls_hover2", "test_example.py"), 0, 12),
+// ── signature_help ────────────────────────────────────────────────────────
+
+async fn signature_help_at(
+    backend: &Backend,
+    uri: Uri,
+    line: u32,
+    character: u32,
+) -> Option<SignatureHelp> {
+    backend
+        .signature_help(SignatureHelpParams {
+            context: None,
+            text_document_position_params: tdp(uri, line, character),
             work_done_progress_params: wdp(),
         })
-        .await;
-    assert!(result.is_ok());
+        .await
+        .unwrap()
 }
 
-// ── references ────────────────────────────────────────────────────────────
-
 #[tokio::test]
 #[timeout(30000)]
-async fn test_references_returns_ok_on_empty_db() {
+async fn test_signature_help_for_pytest_calls() {
     let backend = make_backend();
-    let result = backend
-        .references(ReferenceParams {
-            text_document_position: tdp(turi("test_ls_refs", "test.py"), 0, 0),
-            context: ReferenceContext {
-                include_declaration: true,
-            },
-            work_done_progress_params: wdp(),
-            partial_result_params: prp(),
-        })
-        .await;
-    assert!(result.is_ok());
-}
+    let uri = turi("test_ls_sighelp", "test_calls.py");
+    // Calls are still being typed, so the file does not parse
+    open_file(
+        &backend,
+        uri.clone(),
+        "import pytest\n\n@pytest.fixture(scope=\"se\n@pytest.mark.parametrize(\"x\", [1, 2], \n@pytest.mark.usefixtures(\"db\", \nCASES = [pytest.param(1, id=\ndef helper(a, \n",
+    )
+    .await;
 
-// ── completion ────────────────────────────────────────────────────────────
+    let active = |help: &SignatureHelp| {
+        let signature = &help.signatures[0];
+        let index = help.active_parameter.expect("active parameter") as usize;
+        let ParameterLabel::LabelOffsets([start, end]) =
+            signature.parameters.as_ref().unwrap()[index].label
+        else {
+            panic!("expected label offsets");
+        };
+        signature.label[start as usize..end as usize].to_string()
+    };
+
+    let help = signature_help_at(&backend, uri.clone(), 2, 25)
+        .await
+        .unwrap();
+    assert_eq!(
+        help.signatures[0].label,
+        "pytest.fixture(fixture_function=None, *, scope=\"function\", params=None, autouse=False, ids=None, name=None)"
+    );
+    assert_eq!(active(&help), "scope=\"function\"");
+
+    let help = signature_help_at(&backend, uri.clone(), 3, 40)
+        .await
+        .unwrap();
+    assert_eq!(active(&help), "indirect=False");
+
+    let help = signature_help_at(&backend, uri.clone(), 4, 32)
+        .await
+        .unwrap();
+    assert_eq!(active(&help), "*names");
+
+    let help = signature_help_at(&backend, uri.clone(), 5, 28)
+        .await
+        .unwrap();
+    assert_eq!(
+        help.signatures[0].label,
+        "pytest.param(*values, id=None, marks=())"
+    );
+    assert_eq!(active(&help), "id=None");
+
+    // Calls unrelated to pytest get no signature help
+    assert!(signature_help_at(&backend, uri, 6, 14).await.is_none());
+}
 
 #[tokio::test]
 #[timeout(30000)]
-async fn test_completion_returns_ok() {
-    let backend = make_backend();
-    let result = backend
-        .completion(CompletionParams {
-            text_document_position: tdp(turi("test_ls_compl", "test.py"), 0, 0),
-            work_done_progress_params: wdp(),
-            partial_result_params: prp(),
-            context: None,
-        })
-        .await;
-    assert!(result.is_ok());
+async fn test_signature_help_resolves_test_parameters() {
+    let db = Arc::new(FixtureDatabase::new());
+    let backend = make_backend_with_db(Arc::clone(&db));
+    db.analyze_file(
+        tfile("test_ls_sighelp2", "conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef client() -> Client:\n    return Client()\n",
+    );
+    let uri = turi("test_ls_sighelp2", "test_client.py");
+    open_file(&backend, uri.clone(), "def test_get(client, flag\n").await;
+
+    let help = signature_help_at(&backend, uri, 0, 25).await.unwrap();
+    let signature = &help.signatures[0];
+    assert_eq!(signature.label, "test_get(client: Client, flag)");
+    assert_eq!(help.active_parameter, Some(1));
+
+    let parameters = signature.parameters.as_ref().unwrap();
+    let Some(Documentation::MarkupContent(client_doc)) = &parameters[0].documentation else {
+        panic!("expected markdown documentation");
+    };
+    assert!(client_doc.value.starts_with("**from** `conftest.py`"));
+    let Some(Documentation::MarkupContent(flag_doc)) = &parameters[1].documentation else {
+        panic!("expected markdown documentation");
+    };
+    assert!(flag_doc.value.contains("does not resolve to a fixture"));
 }
 
 // ── code_action ───────────────────────────────────────────────────────────
@@ -844,6 +1975,56 @@ async fn test_code_action_returns_ok_no_diagnostics() {
     assert!(result.is_ok());
 }
 
+#[tokio::test]
+#[timeout(30000)]
+async fn test_code_action_suggests_closest_fixture_for_unknown_fixture() {
+    let db = Arc::new(FixtureDatabase::new());
+    let backend = make_backend_with_db(Arc::clone(&db));
+
+    db.analyze_file(
+        tfile("test_ls_ca_unknown", "conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef database():\n    return 1\n",
+    );
+    let uri = turi("test_ls_ca_unknown", "test_db.py");
+    open_file(&backend, uri.clone(), "def test_db(databse):\n    pass\n").await;
+
+    let diagnostic = Diagnostic {
+        range: rng(0, 12, 0, 19),
+        severity: Some(DiagnosticSeverity::WARNING),
+        code: Some(NumberOrString::String("unknown-fixture".to_string())),
+        source: Some("pytest-lsp".to_string()),
+        message: "Fixture 'databse' not found".to_string(),
+        ..Default::default()
+    };
+
+    let result = backend
+        .code_action(CodeActionParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            range: rng(0, 12, 0, 19),
+            context: CodeActionContext {
+                diagnostics: vec![diagnostic],
+                only: Some(vec![CodeActionKind::QUICKFIX]),
+                trigger_kind: None,
+            },
+            work_done_progress_params: wdp(),
+            partial_result_params: prp(),
+        })
+        .await
+        .unwrap()
+        .expect("quick fix expected");
+
+    assert_eq!(result.len(), 1);
+    let CodeActionOrCommand::CodeAction(action) = &result[0] else {
+        panic!("expected a code action");
+    };
+    assert_eq!(action.title, "pytest-ls: Replace 'databse' with 'database'");
+    assert_eq!(action.is_preferred, Some(true));
+    let edits = &action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].range, rng(0, 12, 0, 19));
+    assert_eq!(edits[0].new_text, "database");
+}
+
 // ── document_symbol ───────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -946,6 +2127,133 @@ async fn test_code_lens_returns_ok() {
     assert!(result.is_ok());
 }
 
+#[tokio::test]
+#[timeout(30000)]
+async fn test_code_lens_shows_test_variants() {
+    let db = Arc::new(FixtureDatabase::new());
+    let backend = make_backend_with_db(Arc::clone(&db));
+
+    db.analyze_file(
+        tfile("test_ls_lens_variants", "conftest.py"),
+        "import pytest\n\n@pytest.fixture(params=[1, 2, 3])\ndef number(request):\n    return request.param\n",
+    );
+    db.analyze_file(
+        tfile("test_ls_lens_variants", "test_number.py"),
+        "def test_it(number):\n    pass\n\ndef test_plain():\n    pass\n",
+    );
+
+    let lenses = backend
+        .code_lens(CodeLensParams {
+            text_document: TextDocumentIdentifier {
+                uri: turi("test_ls_lens_variants", "test_number.py"),
+            },
+            work_done_progress_params: wdp(),
+            partial_result_params: prp(),
+        })
+        .await
+        .unwrap()
+        .expect("variant lens");
+    assert_eq!(lenses.len(), 1);
+    assert_eq!(lenses[0].range.start.line, 0);
+    assert_eq!(
+        lenses[0].command.as_ref().unwrap().title,
+        "3 variants (number)"
+    );
+}
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_code_lens_shows_autouse_fixtures() {
+    let db = Arc::new(FixtureDatabase::new());
+    let backend = make_backend_with_db(Arc::clone(&db));
+
+    db.analyze_file(
+        tfile("test_ls_lens_autouse", "conftest.py"),
+        "import pytest\n\n@pytest.fixture(autouse=True)\ndef reset_env():\n    yield\n",
+    );
+    db.analyze_file(
+        tfile("test_ls_lens_autouse", "test_env.py"),
+        "import pytest\n\nclass TestDb:\n    @pytest.fixture(autouse=True)\n    def rollback(self):\n        yield\n\n    def test_query(self):\n        pass\n\ndef test_plain():\n    pass\n",
+    );
+
+    let lenses = backend
+        .code_lens(CodeLensParams {
+            text_document: TextDocumentIdentifier {
+                uri: turi("test_ls_lens_autouse", "test_env.py"),
+            },
+            work_done_progress_params: wdp(),
+            partial_result_params: prp(),
+        })
+        .await
+        .unwrap()
+        .expect("autouse lenses");
+    let mut autouse: Vec<(u32, String)> = lenses
+        .into_iter()
+        .filter(|lens| lens.command.as_ref().unwrap().command.is_empty())
+        .map(|lens| (lens.range.start.line, lens.command.unwrap().title))
+        .collect();
+    autouse.sort();
+    // The class autouse fixture only applies to tests inside the class
+    assert_eq!(
+        autouse,
+        vec![
+            (7, "2 autouse (reset_env, rollback)".to_string()),
+            (10, "1 autouse (reset_env)".to_string()),
+        ]
+    );
+}
+
+// ── fixture closure ───────────────────────────────────────────────────────
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_fixture_closure_request_for_test_at_position() {
+    let db = Arc::new(FixtureDatabase::new());
+    let backend = make_backend_with_db(Arc::clone(&db));
+
+    db.analyze_file(
+        tfile("test_ls_closure", "conftest.py"),
+        "import pytest\n\n@pytest.fixture(scope=\"module\")\ndef server():\n    yield\n\n@pytest.fixture\ndef client(server):\n    return 1\n",
+    );
+    db.analyze_file(
+        tfile("test_ls_closure", "test_api.py"),
+        "def test_get(client):\n    assert client\n",
+    );
+
+    let closure = backend
+        .handle_fixture_closure(tdp(turi("test_ls_closure", "test_api.py"), 1, 4))
+        .await
+        .unwrap()
+        .expect("closure");
+    assert_eq!(closure["test"], "test_get");
+    let fixtures: Vec<(&str, &str, &str)> = closure["fixtures"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| {
+            (
+                entry["name"].as_str().unwrap(),
+                entry["scope"].as_str().unwrap(),
+                entry["definition"]["file"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        fixtures,
+        vec![
+            ("server", "module", "conftest.py"),
+            ("client", "function", "conftest.py"),
+        ]
+    );
+
+    // Outside of a test there is no closure
+    let none = backend
+        .handle_fixture_closure(tdp(turi("test_ls_closure", "conftest.py"), 3, 4))
+        .await
+        .unwrap();
+    assert!(none.is_none());
+}
+
 // ── inlay_hint ────────────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -1297,6 +2605,146 @@ async fn test_inlay_hint_position_is_at_end_of_param_name() {
     );
 }
 
+// ── semantic_tokens ───────────────────────────────────────────────────────
+
+/// Flatten semantic tokens into `(delta_line, delta_start, length, type, modifiers)`.
+fn token_tuples(tokens: &[SemanticToken]) -> Vec<(u32, u32, u32, u32, u32)> {
+    tokens
+        .iter()
+        .map(|t| {
+            (
+                t.delta_line,
+                t.delta_start,
+                t.length,
+                t.token_type,
+                t.token_modifiers_bitset,
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_semantic_tokens_mark_fixtures() {
+    let db = Arc::new(FixtureDatabase::new());
+    let backend = make_backend_with_db(Arc::clone(&db));
+    let caps = backend
+        .initialize(InitializeParams::default())
+        .await
+        .unwrap()
+        .capabilities;
+    db.analyze_file(
+        tfile("test_ls_sem", "conftest.py"),
+        "import pytest\n\n@pytest.fixture\ndef base():\n    return 1\n\n@pytest.fixture(autouse=True)\ndef auto():\n    yield\n",
+    );
+    let uri = turi("test_ls_sem", "test_sem.py");
+    open_file(
+        &backend,
+        uri.clone(),
+        "import pytest\n\n@pytest.fixture\ndef base(base):\n    return base + 1\n\n@pytest.mark.usefixtures(\"auto\")\ndef test_it(base, tmp_path):\n    pass\n",
+    )
+    .await;
+
+    let Some(SemanticTokensServerCapabilities::SemanticTokensOptions(options)) =
+        caps.semantic_tokens_provider
+    else {
+        panic!("semantic tokens capability expected");
+    };
+    assert_eq!(options.range, Some(true));
+    let modifier = |name: &str| {
+        1 << options
+            .legend
+            .token_modifiers
+            .iter()
+            .position(|m| m.as_str() == name)
+            .unwrap()
+    };
+    let (definition, fixture, autouse, overrides) = (
+        modifier("definition"),
+        modifier("fixture"),
+        modifier("autouse"),
+        modifier("override"),
+    );
+
+    let Some(SemanticTokensResult::Tokens(tokens)) = backend
+        .semantic_tokens_full(SemanticTokensParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            work_done_progress_params: wdp(),
+            partial_result_params: prp(),
+        })
+        .await
+        .unwrap()
+    else {
+        panic!("tokens expected");
+    };
+    // The overriding definition, the parameter requesting the parent fixture,
+    // the usefixtures string and the test parameter; unknown `tmp_path` stays plain
+    assert_eq!(
+        token_tuples(&tokens.data),
+        vec![
+            (3, 4, 4, 0, definition | fixture | overrides),
+            (0, 5, 4, 1, fixture),
+            (3, 26, 4, 2, fixture | autouse),
+            (1, 12, 4, 1, fixture | overrides),
+        ]
+    );
+
+    let Some(SemanticTokensRangeResult::Tokens(tokens)) = backend
+        .semantic_tokens_range(SemanticTokensRangeParams {
+            text_document: TextDocumentIdentifier { uri },
+            range: rng(6, 0, 7, 30),
+            work_done_progress_params: wdp(),
+            partial_result_params: prp(),
+        })
+        .await
+        .unwrap()
+    else {
+        panic!("tokens expected");
+    };
+    assert_eq!(
+        token_tuples(&tokens.data),
+        vec![
+            (6, 26, 4, 2, fixture | autouse),
+            (1, 12, 4, 1, fixture | overrides),
+        ]
+    );
+}
+
+#[tokio::test]
+#[timeout(30000)]
+async fn test_semantic_tokens_redefinition_is_not_an_override() {
+    let db = Arc::new(FixtureDatabase::new());
+    let backend = make_backend_with_db(Arc::clone(&db));
+    backend
+        .initialize(InitializeParams::default())
+        .await
+        .unwrap();
+    let uri = turi("test_ls_sem_redef", "test_redef.py");
+    open_file(
+        &backend,
+        uri.clone(),
+        "import pytest\n\n@pytest.fixture\ndef base():\n    return 1\n\n@pytest.fixture\ndef base():\n    return 2\n",
+    )
+    .await;
+
+    let Some(SemanticTokensResult::Tokens(tokens)) = backend
+        .semantic_tokens_full(SemanticTokensParams {
+            text_document: TextDocumentIdentifier { uri },
+            work_done_progress_params: wdp(),
+            partial_result_params: prp(),
+        })
+        .await
+        .unwrap()
+    else {
+        panic!("tokens expected");
+    };
+    // Both definitions carry `definition | fixture` only, no `override`
+    assert_eq!(
+        token_tuples(&tokens.data),
+        vec![(3, 4, 4, 0, 0b11), (4, 4, 4, 0, 0b11)]
+    );
+}
+
 // ── prepare_call_hierarchy ────────────────────────────────────────────────
 
 #[tokio::test]
@@ -5932,6 +5932,9 @@ fn make_backend_with_db(
             scan_task: b.scan_task.clone(),
             uri_cache: b.uri_cache.clone(),
             config: b.config.clone(),
+            workspace_roots: b.workspace_roots.clone(),
+            client_settings: b.client_settings.clone(),
+            pull_diagnostics: b.pull_diagnostics.clone(),
         });
         b
     });
@@ -7463,6 +7466,7 @@ async fn test_request_inlay_hint_shown_when_definition_available() {
         scope: pytest_language_server::FixtureScope::Function,
         yield_line: None,
         autouse: false,
+        ..Default::default()
     };
     db.definitions
         .entry("request".to_string())
@@ -7532,6 +7536,7 @@ async fn test_request_code_action_fix_all_annotates_request_param() {
         scope: pytest_language_server::FixtureScope::Function,
         yield_line: None,
         autouse: false,
+        ..Default::default()
     };
     db.definitions
         .entry("request".to_string())
@@ -7644,6 +7649,7 @@ async fn test_request_hover_returns_fixture_request_type() {
         scope: pytest_language_server::FixtureScope::Function,
         yield_line: None,
         autouse: false,
+        ..Default::default()
     };
     db.definitions
         .entry("request".to_string())
@@ -792,3 +792,26 @@ def fixture_{n}():
         );
     }
 }
+
+#[test]
+#[timeout(30000)]
+fn test_cache_eviction_keeps_open_documents() {
+    // With incremental sync the cache holds the only copy of an open document
+    let db = FixtureDatabase::new();
+
+    let open_path = PathBuf::from("/tmp/test_cache_open/test_open.py");
+    db.set_document_version(open_path.clone(), 1);
+    db.analyze_file(open_path.clone(), "def test_open():\n    pass\n");
+
+    // Enough files to exceed the cache limit several times over
+    for i in 0..2500 {
+        let path = PathBuf::from(format!("/tmp/test_cache_open/file_{}.py", i));
+        db.analyze_file(path, "x = 1\n");
+    }
+
+    assert!(db.file_cache.len() < 2500, "Cache should have been evicted");
+    assert!(
+        db.file_cache.contains_key(&open_path),
+        "Open document must not be evicted"
+    );
+}